@@ -1,2 +1,3 @@
 pub mod states;
-pub mod plugin;
\ No newline at end of file
+pub mod plugin;
+pub mod run;
\ No newline at end of file
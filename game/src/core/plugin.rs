@@ -6,7 +6,7 @@ use bevy_rapier2d::{plugin::{NoUserData, RapierPhysicsPlugin}, prelude::{BevyPhy
 use debug_utils::debug_overlay::DebugOverlayRoot;
 use pixel_utils::camera::{PixelCamera, PixelCameraPlugin};
 
-use crate::{camera::plugin::CameraControllerPlugin, core::states::GameStatesPlugin, interactions::InteractionsPlugin, physics::platforms::PlatformsPlugin, ui::{plugin::UiSystemPlugin, target::UiRetargetPlugin}, utils::{cursor::CursorPlugin, custom_material_loader::SpritePreloadPlugin, debree::DebreePlugin, malf_guide::MalfunctionGuider, mouse::CursorPositionPlugin, spacial_audio::SpacialAudioPlugin}};
+use crate::{camera::plugin::CameraControllerPlugin, core::{run::RunPlugin, states::GameStatesPlugin}, interactions::InteractionsPlugin, physics::platforms::PlatformsPlugin, ui::{plugin::UiSystemPlugin, target::UiRetargetPlugin}, utils::{cursor::CursorPlugin, custom_material_loader::SpritePreloadPlugin, debree::DebreePlugin, difficulty::DifficultyPlugin, malf_guide::MalfunctionGuider, mouse::CursorPositionPlugin, rng::GameRngPlugin, spacial_audio::SpacialAudioPlugin}};
 
 const AUDIO_SCALE: f32 = 1. / 100.0;
 
@@ -41,6 +41,7 @@ impl Plugin for CorePlugin {
                 RapierPhysicsPlugin::<()>::pixels_per_meter(16.0),
                 EguiPlugin { enable_multipass_for_primary_context: true },
                 GameStatesPlugin,
+                RunPlugin,
                 PixelCameraPlugin,
                 CameraControllerPlugin,
                 UiSystemPlugin),
@@ -53,8 +54,12 @@ impl Plugin for CorePlugin {
                 CursorPositionPlugin,
                 SpritePreloadPlugin,
                 DebreePlugin,
+                GameRngPlugin,
+                DifficultyPlugin,
             ))
             .insert_resource(bevy_framepace::FramepaceSettings{limiter: bevy_framepace::Limiter::from_framerate(60.0)})
+            // the malfunction schedule runs on a fixed step so a seed replays the same run regardless of framerate
+            .insert_resource(Time::<Fixed>::from_hz(60.))
             .add_systems(Startup, init_egui_font.after(EguiPreUpdateSet::InitContexts))
             .add_systems(PreStartup, debug_ui_to_camera.after(pixel_utils::camera::setup_camera).after(debug_utils::debug_overlay::init))
         ;
@@ -0,0 +1,116 @@
+use bevy::prelude::*;
+use debug_utils::debug_overlay::DebugOverlayRoot;
+
+use crate::{core::states::{GlobalAppState, OnGame}, interactions::{collision_minigame::CollisionMinigameConsts, components::{InInteractionArray, InteractionTypes, ScrollSelector}, wave_modulator::{Spinny, WaveModulatorConsts}}, physics::player::{NearestLadders, PlayerConstants}, tilemap::plugin::respawn_map, ui::target::{lowres_container_node, LowresUiContainer}, utils::{debree::{DebreeLevel, DebreeTimer, Malfunction}, difficulty::DifficultyProfile, energy::{Energy, PowerState}, failure_graph::Strains, orbits::DebrisField, rng::{run_seed, GameRng}, save::{clear_snapshot, PendingPlayerRestore, PendingSiteRestore}, score::{RunOutcome, RunScore}}, LightFlicker};
+
+pub struct RunPlugin;
+
+impl Plugin for RunPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_schedule(OnGame)
+        .insert_resource(RunActive(false))
+        .add_systems(OnEnter(GlobalAppState::InGame), start_run)
+        .add_systems(OnEnter(GlobalAppState::Paused), pause_time)
+        .add_systems(OnExit(GlobalAppState::Paused), resume_time)
+        .add_systems(Update, toggle_pause.run_if(in_state(GlobalAppState::InGame).or(in_state(GlobalAppState::Paused))));
+    }
+}
+
+/// Despawned on restart, respawned by `OnGame`
+#[derive(Component, Default)]
+pub struct RunEntity;
+
+/// Set once `OnGame` ran for the current run, so unpausing doesn't start it again
+#[derive(Resource)]
+pub struct RunActive(pub bool);
+
+pub fn start_run(world: &mut World) {
+    if world.resource::<RunActive>().0 {
+        return;
+    }
+    world.resource_mut::<RunActive>().0 = true;
+    world.run_schedule(OnGame);
+}
+
+pub fn toggle_pause(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    state: Res<State<GlobalAppState>>,
+    mut next_state: ResMut<NextState<GlobalAppState>>,
+) {
+    if !keyboard.just_pressed(KeyCode::Escape) {
+        return;
+    }
+    match state.get() {
+        GlobalAppState::InGame => next_state.set(GlobalAppState::Paused),
+        GlobalAppState::Paused => next_state.set(GlobalAppState::InGame),
+        _ => {}
+    }
+}
+
+/// Virtual time drives the fixed step, timers and physics, so stopping it freezes the whole run
+pub fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+pub fn resume_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+/// Puts every gameplay resource back to the start of a run.
+/// Nothing is spawned or despawned, see `reset_run` for that.
+pub fn reset_run_state(world: &mut World) {
+    let profile = world.resource::<DifficultyProfile>().clone();
+    let mut debree_level = DebreeLevel::new();
+    debree_level.const_add = profile.const_add;
+    world.insert_resource(debree_level);
+    world.insert_resource(Malfunction::default());
+    world.insert_resource(DebrisField::default());
+    world.insert_resource(Strains::default());
+    world.resource_mut::<DebreeTimer>().timer.reset();
+    world.insert_resource(Energy::new());
+    world.insert_resource(PowerState::new());
+    world.insert_resource(WaveModulatorConsts::default());
+    world.insert_resource(CollisionMinigameConsts::default());
+    world.insert_resource(Spinny::default());
+    world.insert_resource(ScrollSelector::default());
+    world.insert_resource(InInteractionArray {
+        in_interaction: InteractionTypes::ChainReactionDisplay,
+        in_interaction_entity: None,
+        in_any_interaction: false,
+    });
+    world.insert_resource(PlayerConstants::default());
+    world.resource_mut::<NearestLadders>().ladders.clear();
+    world.insert_resource(GameRng::new(run_seed()));
+    world.insert_resource(RunScore::default());
+    world.insert_resource(RunOutcome::Crashed);
+    world.remove_resource::<PendingPlayerRestore>();
+    world.remove_resource::<PendingSiteRestore>();
+    clear_snapshot();
+}
+
+/// Full restart: fresh state, player and run entities gone, map spawned again from the loaded asset.
+pub fn reset_run(world: &mut World) {
+    respawn_map(world);
+    let run_entities: Vec<Entity> = world
+        .query_filtered::<Entity, With<RunEntity>>()
+        .iter(world)
+        .collect();
+    for e in run_entities {
+        if let Ok(e) = world.get_entity_mut(e) {
+            e.despawn();
+        }
+    }
+    if let Ok(ui) = world.query_filtered::<Entity, With<LowresUiContainer>>().single(world) {
+        let children: Vec<Entity> = world.get::<Children>(ui).map(|c| c.to_vec()).unwrap_or_default();
+        for c in children {
+            if world.get::<DebugOverlayRoot>(c).is_none() {
+                world.entity_mut(c).despawn();
+            }
+        }
+        world.entity_mut(ui).insert(lowres_container_node()).remove::<BackgroundColor>();
+    }
+    world.insert_resource(LightFlicker::default());
+    world.resource_mut::<RunActive>().0 = false;
+    reset_run_state(world);
+}
@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use bevy::prelude::*;
+use bevy::{ecs::schedule::ScheduleLabel, prelude::*};
 use bevy_asset_loader::{asset_collection::AssetCollection, loading_state::{config::ConfigureLoadingState, LoadingState, LoadingStateAppExt}};
 use bevy_tailwind::tw;
 
@@ -40,7 +40,7 @@ impl PreGameTasks {
     pub fn done(&mut self, task: String) {
         self.tasks.remove(&task);
     }
-    fn is_empty(&self) -> bool {
+    pub fn is_empty(&self) -> bool {
         self.tasks.is_empty()
     }
 }
@@ -53,8 +53,13 @@ impl Default for PreGameTasks {
     }
 }
 
+/// Runs once when a run starts, not when coming back from `Paused`.
+/// See `core::run::start_run`.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OnGameStart;
+
 #[allow(non_upper_case_globals)]
-pub const OnGame : OnEnter<crate::core::states::GlobalAppState> = OnEnter(crate::core::states::GlobalAppState::InGame);
+pub const OnGame : OnGameStart = OnGameStart;
 
 
 #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
@@ -76,7 +81,7 @@ pub fn try_translate(
 ){
     if *loading_state == AppLoadingAssetsSubState::Done &&
     tasks.is_empty() {
-        next_state.set(GlobalAppState::InGame);
+        next_state.set(GlobalAppState::MainMenu);
         if let Some(ls) = ls {
             cmd.entity(*ls).despawn();
         }
@@ -94,8 +99,11 @@ pub fn try_translate(
 pub enum GlobalAppState {
     #[default]
     AssetLoading,
+    MainMenu,
     InGame,
-    Defeat
+    Paused,
+    Defeat,
+    Victory,
 }
 
 #[derive(SubStates, Clone, PartialEq, Eq, Hash, Debug, Default)]
@@ -3,7 +3,7 @@ use std::{f32::consts::PI, time::Duration};
 use bevy::{prelude::*, render::render_resource::{AsBindGroup, Extent3d, ShaderRef, TextureDescriptor, TextureUsages}, ui::RelativeCursorPosition};
 use bevy_tailwind::tw;
 
-use crate::{interactions::{components::{InInteractionArray, InteractionTypes}, wave_modulator::{Spinny, SpinnyIds}}, ui::{components::{containers::{base::*, text_display::{text_display_green_handle, ui_text_display_green_with_text}, viewport_container::{ui_viewport_container, viewport_handle}}, spinny::ui_spinny, ui_submit_button::{submit_button_bundle, ui_submit_button}}, target::LowresUiContainer}, utils::{custom_material_loader::{SpinnyAtlasHandles, SpriteAssets}, debree::{Malfunction, MalfunctionType, Resolved}, energy::Energy, spacial_audio::PlaySoundEvent}};
+use crate::{interactions::{components::{InInteractionArray, InteractableId, InteractionTypes}, wave_modulator::{Spinny, SpinnyIds}}, ui::{components::{containers::{base::*, text_display::{text_display_green_handle, ui_text_display_green_with_text}, viewport_container::{ui_viewport_container, viewport_handle}}, spinny::ui_spinny, ui_submit_button::{submit_button_bundle, ui_submit_button}}, target::LowresUiContainer}, utils::{custom_material_loader::{SpinnyAtlasHandles, SpriteAssets}, debree::{Malfunction, MalfunctionType, Resolved}, energy::Energy, rng::{GameRng, RngStream, SeededRng}, spacial_audio::PlaySoundEvent}};
 
 
 #[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
@@ -66,6 +66,7 @@ pub fn open_collision_minigame_display(
     sprite_assets: Res<SpriteAssets>,
     asset_server: Res<AssetServer>,
     malfunction: Res<Malfunction>,
+    sites: Query<&InteractableId>,
     mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
     mut event_writer: EventWriter<PlaySoundEvent>,
 ) {
@@ -116,7 +117,7 @@ pub fn open_collision_minigame_display(
             }
             let mut is_active = 0.;
             let mut collision_text = TRAJECTORY_SAFE;
-            if malfunction.malfunction_types.contains(&MalfunctionType::Collision) {
+            if malfunction.broken_at(in_interaction_array.using(InteractionTypes::CollisionMinigame), &sites).is_some() {
                 is_active = 1.;
                 collision_text = COLLISION_IMPENDING;
             }
@@ -221,6 +222,8 @@ pub fn interact_with_spinny_collision(
     consts: Res<CollisionMinigameConsts>,
     time: Res<Time>,
     malfunction: Res<Malfunction>,
+    in_interaction_array: Res<InInteractionArray>,
+    sites: Query<&InteractableId>,
     mut event_writer: EventWriter<PlaySoundEvent>,
 ) {
     if let Some(material) = material_assets.get_mut(*material_handle) {
@@ -238,7 +241,7 @@ pub fn interact_with_spinny_collision(
                 if let Some(material) = material_assets.get_mut(*material_handle) {
                     if consts.is_loaded {
                         let mut is_active = 0.;
-                        if malfunction.malfunction_types.contains(&MalfunctionType::Collision) {
+                        if malfunction.broken_at(in_interaction_array.using(InteractionTypes::CollisionMinigame), &sites).is_some() {
                             is_active = 1.;
                         }
                         material.is_active = is_active;
@@ -280,9 +283,11 @@ pub fn generate_collision_minigame_consts(
     mut collision_consts: ResMut<CollisionMinigameConsts>,
     malfunction: Res<Malfunction>,
     time: Res<Time>,
+    mut rng: ResMut<GameRng>,
 ) {
     if malfunction.malfunction_types.contains(&MalfunctionType::Collision) && !collision_consts.is_loaded {
         collision_consts.is_loaded = true;
+        let rng = rng.stream(RngStream::Collision);
         let a_mi = 0.;
         let a_ma = 5.;
     
@@ -295,14 +300,14 @@ pub fn generate_collision_minigame_consts(
         let r_mi = 0.1;
         let r_ma = 1.;
 
-        let a = a_mi as f32 + ((getrandom::u32().unwrap() as f32 / u32::MAX as f32) * (a_ma + 1. - a_mi) as f32);
-        let b = b_mi as f32 + ((getrandom::u32().unwrap() as f32 / u32::MAX as f32) * (b_ma + 1. - b_mi) as f32);
+        let a = rng.range(a_mi, a_ma + 1.);
+        let b = rng.range(b_mi, b_ma + 1.);
 
-        let u = gen_collision_rng(u_mi, u_ma);
-        let r = gen_collision_rng(r_mi, r_ma);
+        let u = gen_collision_rng(rng, u_mi, u_ma);
+        let r = gen_collision_rng(rng, r_mi, r_ma);
         
-        let ru = u.1[((getrandom::u32().unwrap() as f32 / u32::MAX as f32) * NUM_COLLISION_STATES) as usize];
-        let rr = r.1[((getrandom::u32().unwrap() as f32 / u32::MAX as f32) * NUM_COLLISION_STATES) as usize];
+        let ru = u.1[rng.index(NUM_COLLISION_STATES as usize)];
+        let rr = r.1[rng.index(NUM_COLLISION_STATES as usize)];
         collision_consts.consts1 = [a, b, ru, rr];
         collision_consts.consts2 = [u.1, r.1];
         let start_time = time.elapsed_wrapped();
@@ -310,8 +315,8 @@ pub fn generate_collision_minigame_consts(
     }
 }
 
-fn gen_collision_rng(mi: f32, ma: f32) -> (f32, Vec<f32>) {
-    let a = mi as f32 + ((getrandom::u32().unwrap() as f32 / u32::MAX as f32) * (ma - mi) as f32);
+fn gen_collision_rng(rng: &mut SeededRng, mi: f32, ma: f32) -> (f32, Vec<f32>) {
+    let a = rng.range(mi, ma);
     let mut t = (0..NUM_COLLISION_STATES as i32 / 2)
     .map(|i| mi + ((a - mi) / (NUM_COLLISION_STATES / 2.) * i as f32)).collect::<Vec<f32>>();
     t.extend((0..NUM_COLLISION_STATES as i32 / 2).map(|i| a + ((ma - a) / (NUM_COLLISION_STATES / 2.) * i as f32)));
@@ -351,6 +356,8 @@ pub fn update_collision_minigame(
     material_assets: Res<Assets<CollisionGraphMaterial>>,
     material_handle: Single<&MaterialNode<CollisionGraphMaterial>>,
     mut malfunction: ResMut<Malfunction>,
+    in_interaction_array: Res<InInteractionArray>,
+    sites: Query<&InteractableId>,
     mut submited: Local<bool>,
     mut prev: Local<Interaction>,
     spinny: Res<Spinny>,
@@ -359,10 +366,9 @@ pub fn update_collision_minigame(
     mut cost: Local<f32>,
     mut event_writer: EventWriter<PlaySoundEvent>,
 ) {
-    let mut in_progress = false;
-    if malfunction.malfunction_types.contains(&MalfunctionType::Collision) {
-        in_progress = true;
-    }
+    let site = malfunction.broken_at(in_interaction_array.using(InteractionTypes::CollisionMinigame), &sites);
+    let in_progress = site.is_some();
+    let cost_mul = site.and_then(|s| malfunction.stage_at(s)).map_or(1., |s| s.manoeuvre_cost_mul());
     
     const COST_PER_ANG: f32 = 0.5;
     if spinny.angle >= 0. {
@@ -401,14 +407,14 @@ pub fn update_collision_minigame(
             a.index = index;
         }
         for mut text in text1.iter_mut() {
-            text.0 = format!("Maneuver cost: {:.2} GJ", *cost);
+            text.0 = format!("Maneuver cost: {:.2} GJ", *cost * cost_mul);
         }
         *prev = *interaction;
     }
     if let Some(material) = material_assets.get(*material_handle) {
         let intersects = find_intersection(material.a, material.b, material.u, material.r, material.time);
         for mut text in text {
-            if in_progress {
+            if let Some(site) = site {
                 if !malfunction.malfunction_types.contains(&MalfunctionType::Engine) {
                     if intersects {
                         text.0 = COLLISION_IMPENDING.to_string();
@@ -424,9 +430,10 @@ pub fn update_collision_minigame(
                         }
                         malfunction.resolved.push(Resolved {
                             resolved_type: MalfunctionType::Collision,
+                            site,
                             failed: intersects,
                         });
-                        energy.increase_consumption = (*cost, Duration::from_secs_f32(30.));
+                        energy.add_consumption(*cost * cost_mul, Duration::from_secs_f32(30.));
                         *prev = Interaction::default();
                         *submited = false;
                         *cost = 0.;
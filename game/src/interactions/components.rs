@@ -1,9 +1,6 @@
-use std::collections::HashMap;
-
 use bevy::{prelude::*, render::render_resource::{AsBindGroup, ShaderRef}, sprite::{AlphaMode2d, Material2d}};
-use tiled::PropertyValue;
+use serde::{Deserialize, Serialize};
 
-use crate::utils::debree::{Malfunction, MalfunctionType};
 
 #[derive(Component)]
 pub struct Interactable;
@@ -61,7 +58,8 @@ pub struct ScrollSelector {
     pub selection_options: Vec<Entity>,
 }
 
-#[derive(Component, Clone, Debug, PartialEq, Default, Hash, Eq)]
+#[derive(Component, Clone, Debug, PartialEq, Default, Hash, Eq, Deserialize, Reflect)]
+#[reflect(Default)]
 pub enum InteractionTypes {
     #[default]
     ChainReactionDisplay,
@@ -71,41 +69,36 @@ pub enum InteractionTypes {
     WarningInterface,
     HackMinigame,
     WiresMinigame,
+    PowerRouting,
 }
 
-impl InteractionTypes {
-    pub fn from_properties(properties: &HashMap<String, PropertyValue>) -> Option<Self> {
-        let Some(PropertyValue::StringValue(s)) = properties.get("type") else {return None};
-        match s.as_str() {
-            "MAINFRAME" => None,
-            "HACK" => Some(Self::HackMinigame),
-            "REACTOR" => Some(Self::WiresMinigame),
-            "ENGINE" => Some(Self::PipePuzzle),
-            "ANTENNA" => Some(Self::WaveModulator),
-            "WARNING" => Some(Self::WarningInterface),
-            "CHAIN" => Some(Self::ChainReactionDisplay),
-            "COLLISION" => Some(Self::CollisionMinigame),
-            _ => None
-        }
-    }
-    pub fn as_malfunction(&self) -> MalfunctionType {
-        match self {
-            InteractionTypes::ChainReactionDisplay => {MalfunctionType::NoMalfunction}
-            InteractionTypes::WaveModulator => {MalfunctionType::Waves}
-            InteractionTypes::PipePuzzle => {MalfunctionType::Engine}
-            InteractionTypes::CollisionMinigame => {MalfunctionType::Collision}
-            InteractionTypes::WarningInterface => {MalfunctionType::NoMalfunction}
-            InteractionTypes::HackMinigame => {MalfunctionType::Hack}
-            InteractionTypes::WiresMinigame => {MalfunctionType::Reactor}
-        }
-    }
+/// Authored on Tiled objects, the object's collider becomes an [`Interactable`] of that kind
+#[derive(Component, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct InteractableObject {
+    pub kind: InteractionTypes,
 }
 
+/// Tiled object id of an interactable.
+/// Malfunctions are raised on a specific interactable and remembered by this id,
+/// it stays the same across runs and saves where entities don't.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct InteractableId(pub u32);
+
 #[derive(Resource, Debug)]
 pub struct InInteractionArray {
     pub in_interaction: InteractionTypes,
+    /// interactable the player is using
+    pub in_interaction_entity: Option<Entity>,
     pub in_any_interaction: bool,
 }
 
+impl InInteractionArray {
+    /// Interactable the player is using, if it is of `kind`
+    pub fn using(&self, kind: InteractionTypes) -> Option<Entity> {
+        if self.in_any_interaction && self.in_interaction == kind {self.in_interaction_entity} else {None}
+    }
+}
+
 #[derive(Component)]
 pub struct PlayerSensor;
\ No newline at end of file
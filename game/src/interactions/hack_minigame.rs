@@ -1,7 +1,8 @@
 use bevy::{platform::collections::HashSet, prelude::*};
 use bevy_tailwind::tw;
+use serde::{Deserialize, Serialize};
 
-use crate::{interactions::components::{InInteractionArray, InteractionTypes}, ui::{components::{containers::{base::*, text_display::{text_display_green_handle, ui_text_display_green_with_text}}, hack_button::*}, target::LowresUiContainer}, utils::{debree::{get_random_range, Malfunction, MalfunctionType, Resolved}, spacial_audio::PlaySoundEvent}};
+use crate::{interactions::components::{InInteractionArray, InteractableId, InteractionTypes}, ui::{components::{containers::{base::*, text_display::{text_display_green_handle, ui_text_display_green_with_text}}, hack_button::*, ui_submit_button::{submit_button_bundle, ui_submit_button}}, target::LowresUiContainer}, utils::{debree::{get_random_range, DeorbitCommand, DeorbitFinished, Malfunction, MalfunctionType, Resolved}, orbits::{DebrisField, DEORBIT_DRAW, DEORBIT_SECS}, rng::{GameRng, RngStream, SeededRng}, severity::Stage, spacial_audio::PlaySoundEvent}};
 
 // ALSO CHANGE TW VALUE!
 pub const HACK_GRID_SIZE: u32 = 5;
@@ -22,6 +23,19 @@ pub struct GoalText;
 #[derive(Component)]
 pub struct BufferText;
 
+/// Hacked satellite the deorbit button burns down, clicking it picks the next one
+#[derive(Component)]
+pub struct DeorbitTarget;
+
+#[derive(Component)]
+pub struct DeorbitTargetText;
+
+#[derive(Component)]
+pub struct DeorbitStatusText;
+
+#[derive(Component)]
+pub struct DeorbitButton;
+
 pub fn open_hack_display(
     mut commands: Commands,
     in_interaction_array: Res<InInteractionArray>,
@@ -29,7 +43,7 @@ pub fn open_hack_display(
     lowres_container: Single<Entity, With<LowresUiContainer>>,
     asset_server: Res<AssetServer>,
     mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
-    hack_grid: Res<HackGrid>,
+    hack_grids: Query<(&InteractableId, &HackGrid)>,
     malfunction: Res<Malfunction>,
     mut event_writer: EventWriter<PlaySoundEvent>,
 ) {
@@ -39,12 +53,9 @@ pub fn open_hack_display(
             *already_spawned = None;
         }
     } else {
-        if in_interaction_array.in_interaction == InteractionTypes::HackMinigame && in_interaction_array.in_any_interaction {
+        if let Some((site, hack_grid)) = in_interaction_array.using(InteractionTypes::HackMinigame).and_then(|e| hack_grids.get(e).ok()) {
             event_writer.write(PlaySoundEvent::OpenUi);
-            let mut is_active = false;
-            if malfunction.malfunction_types.contains(&MalfunctionType::Hack) {
-                is_active = true;
-            }
+            let is_active = malfunction.is_broken(*site) && hack_grid.is_loaded;
             let hack = hack_button_bundle(&asset_server, &mut texture_atlases);
             let main = main_container_handle(&asset_server);
             let sub = sub_container_handle(&asset_server);
@@ -70,6 +81,27 @@ pub fn open_hack_display(
                 ).insert(tw!("w-[230px] items-center justify-center p-[5px]"));
             }).id();
 
+            let submit_bundle = submit_button_bundle(&asset_server, &mut texture_atlases);
+            let deorbit_target_entity = commands.spawn(
+            ui_main_container(&main, ())
+            ).with_children(|cmd|{
+                cmd.spawn(
+                ui_text_display_green_with_text(&text_bundle, (DeorbitTargetText, DeorbitTargetText), "", &asset_server)
+                ).insert((tw!("w-[230px] items-center justify-center p-[5px]"), Interaction::default(), DeorbitTarget));
+            }).id();
+            let deorbit_status_entity = commands.spawn(
+            ui_main_container(&main, ())
+            ).with_children(|cmd|{
+                cmd.spawn(
+                ui_text_display_green_with_text(&text_bundle, (DeorbitStatusText, DeorbitStatusText), &deorbit_cost_label(), &asset_server)
+                ).insert(tw!("w-[230px] items-center justify-center p-[5px]"));
+            }).id();
+            let deorbit_button_entity = commands.spawn(
+            ui_main_container(&main, ())
+            ).with_children(|cmd| {
+                cmd.spawn(ui_submit_button(&submit_bundle, DeorbitButton));
+            }).id();
+
             let mut children = vec![];
             for y in 0..HACK_GRID_SIZE {
                 for x in 0..HACK_GRID_SIZE {
@@ -111,6 +143,13 @@ pub fn open_hack_display(
                             cmd.spawn(tw!("items-center justify-center w-full h-full"),)
                             .add_child(buffer_text_entity);
                         });
+                        cmd.spawn(ui_sub_container(&sub, ()))
+                        .with_children(|cmd| {
+                            cmd.spawn(tw!("flex flex-col items-center justify-center w-full h-full"),)
+                            .add_child(deorbit_target_entity)
+                            .add_child(deorbit_status_entity)
+                            .add_child(deorbit_button_entity);
+                        });
                     });
             }).id();
             
@@ -120,28 +159,47 @@ pub fn open_hack_display(
     }
 }
 
-#[derive(Resource, Default)]
+#[derive(Component, Default, Clone, Serialize, Deserialize)]
 pub struct HackGrid {
     pub is_loaded: bool,
     pub grid: Vec<usize>,
     pub win_seq: Vec<usize>,
+    /// Wrong sequences entered since the grid was generated
+    #[serde(default)]
+    pub misses: u32,
 }
 
 pub fn init_hack_display(
     malfunction: Res<Malfunction>,
-    mut hack_grid: ResMut<HackGrid>,
+    mut hack_grids: Query<(&InteractableId, &mut HackGrid)>,
+    mut rng: ResMut<GameRng>,
 ) {
-    if malfunction.is_changed() && malfunction.malfunction_types.contains(&MalfunctionType::Hack) && !hack_grid.is_loaded {
-        hack_grid.is_loaded = true;
-        hack_grid.grid = vec![0; (HACK_GRID_SIZE * HACK_GRID_SIZE) as usize];
+    if !malfunction.is_changed() {
+        return;
+    }
+    // in a stable order, so a seed replays the same grids
+    let mut hack_grids: Vec<_> = hack_grids.iter_mut().collect();
+    hack_grids.sort_by_key(|(site, _)| **site);
+    for (site, mut hack_grid) in hack_grids {
+        if malfunction.is_broken(*site) && !hack_grid.is_loaded {
+            hack_grid.generate(rng.stream(RngStream::Hack));
+        }
+    }
+}
+
+impl HackGrid {
+    fn generate(&mut self, rng: &mut SeededRng) {
+        self.is_loaded = true;
+        self.misses = 0;
+        self.grid = vec![0; (HACK_GRID_SIZE * HACK_GRID_SIZE) as usize];
         for y in 0..HACK_GRID_SIZE as usize {
             loop {
                 let mut have_different = false;
                 for x in 0..HACK_GRID_SIZE as usize {
                     let flat_id = x + y * HACK_GRID_SIZE as usize;
-                    let index = get_random_range(0., NUM_HACK_BUTTON_TYPES) as usize;
-                    hack_grid.grid[flat_id] = index;
-                    if hack_grid.grid[y * HACK_GRID_SIZE as usize] != index {
+                    let index = get_random_range(rng, 0., NUM_HACK_BUTTON_TYPES) as usize;
+                    self.grid[flat_id] = index;
+                    if self.grid[y * HACK_GRID_SIZE as usize] != index {
                         have_different = true;
                     }
                 }
@@ -150,29 +208,29 @@ pub fn init_hack_display(
                 }
             }
         }
-        let hor_entry1 = get_random_range(1., HACK_GRID_SIZE as f32) as usize;
-        let vert_entry1 = get_random_range(1., HACK_GRID_SIZE as f32) as usize;
-        let mut hor_entry2 = get_random_range(-(hor_entry1 as f32), HACK_GRID_SIZE as f32 - hor_entry1 as f32) as usize;
+        let hor_entry1 = get_random_range(rng, 1., HACK_GRID_SIZE as f32) as usize;
+        let vert_entry1 = get_random_range(rng, 1., HACK_GRID_SIZE as f32) as usize;
+        let mut hor_entry2 = get_random_range(rng, -(hor_entry1 as f32), HACK_GRID_SIZE as f32 - hor_entry1 as f32) as usize;
         loop {
             if hor_entry2 == 0 {
-                hor_entry2 = get_random_range(-(hor_entry1 as f32), HACK_GRID_SIZE as f32 - hor_entry1 as f32) as usize;
+                hor_entry2 = get_random_range(rng, -(hor_entry1 as f32), HACK_GRID_SIZE as f32 - hor_entry1 as f32) as usize;
             } else {
                 break;
             }
         }
-        hack_grid.win_seq = vec![
-            hack_grid.grid[hor_entry1],
-            hack_grid.grid[hor_entry1 + vert_entry1 * HACK_GRID_SIZE as usize],
-            hack_grid.grid[(hor_entry1 + hor_entry2 + vert_entry1 * HACK_GRID_SIZE as usize).clamp(0, 24)]
+        self.win_seq = vec![
+            self.grid[hor_entry1],
+            self.grid[hor_entry1 + vert_entry1 * HACK_GRID_SIZE as usize],
+            self.grid[(hor_entry1 + hor_entry2 + vert_entry1 * HACK_GRID_SIZE as usize).clamp(0, 24)]
         ];
-        println!("{},0 {},{} {},{} ({}) {:?}", hor_entry1, hor_entry1, vert_entry1, hor_entry1 + hor_entry2, vert_entry1, hor_entry2, hack_grid.win_seq.iter().map(|index| HACK_BUTTON_NAMES[*index]).collect::<Vec<&str>>());
-        // println!("{:?}", (0..100).map(|_| get_random_range(1., HACK_GRID_SIZE as f32 - 1.) as usize).collect::<Vec<usize>>());
     }
 }
 
 pub fn update_hack_display(
     mut malfunction: ResMut<Malfunction>,
-    mut hack_grid: ResMut<HackGrid>,
+    in_interaction_array: Res<InInteractionArray>,
+    mut hack_grids: Query<(&InteractableId, &mut HackGrid)>,
+    mut last_console: Local<Option<Entity>>,
     mut interaction_query: Query<
         (
             Entity,
@@ -191,6 +249,15 @@ pub fn update_hack_display(
     buffer_text: Query<&mut Text, (With<BufferText>, Without<GoalText>)>,
     mut event_writer: EventWriter<PlaySoundEvent>,
 ) {
+    let console = in_interaction_array.using(InteractionTypes::HackMinigame);
+    if console != *last_console {
+        // a sequence started on another console doesn't carry over
+        *last_console = console;
+        *prev_state = Interaction::default();
+        selected_seq_pos.clear();
+        selected_seq_index.clear();
+    }
+    let Some((site, mut hack_grid)) = console.and_then(|e| hack_grids.get_mut(e).ok()) else {return;};
     for mut text in goal_text {
         let winseq = hack_grid.win_seq.iter().map(|index| HACK_BUTTON_NAMES[*index]).collect::<Vec<&str>>();
         let mut seq = String::from("Goal: ");
@@ -209,7 +276,7 @@ pub fn update_hack_display(
     }
     let mut ended = false;
     let curr_type = MalfunctionType::Hack;
-    if malfunction.malfunction_types.contains(&curr_type) && hack_grid.is_loaded {
+    if malfunction.is_broken(*site) && hack_grid.is_loaded {
         for (entity, interaction, mut node, mut hack, base) in
             &mut interaction_query
         {
@@ -240,7 +307,6 @@ pub fn update_hack_display(
             if ver_hor_lightup_condition {
                 hack.state = HackButtonState::Active;
                 if let Ok(interaction) = changed_interaction_query.get(entity) {
-                    // println!("{:?} {}", prev_state, mouse_button.just_released(MouseButton::Left));
                     if *prev_state == Interaction::Pressed && mouse_button.just_released(MouseButton::Left) && spec_condition{
                         if let Some(a) = &mut node.texture_atlas {
                             a.index = hack.get_idx(false, true);
@@ -262,18 +328,18 @@ pub fn update_hack_display(
                 }
             } 
             if selected_seq_pos.len() == hack_grid.win_seq.len() {
-                println!("{:?}", selected_seq_index.iter().map(|index| HACK_BUTTON_NAMES[*index]).collect::<Vec<&str>>());
-                hack_grid.is_loaded = false;
-                let mut failed = true;
                 ended = true;
-                if *selected_seq_index == hack_grid.win_seq {
-                    event_writer.write(PlaySoundEvent::Success);
-                    failed = false;
-                } else {
-                    event_writer.write(PlaySoundEvent::Fail);
+                let failed = *selected_seq_index != hack_grid.win_seq;
+                if failed {
+                    hack_grid.misses += 1;
+                }
+                let attempts = malfunction.stage_at(*site).unwrap_or(Stage::Warning).hack_attempts();
+                if !failed || hack_grid.misses >= attempts {
+                    hack_grid.is_loaded = false;
+                    malfunction.resolved.push(Resolved {resolved_type: curr_type.clone(), site: *site, failed});
                 }
-                malfunction.resolved.push(Resolved {resolved_type: curr_type.clone(), failed});
-                
+                event_writer.write(if failed {PlaySoundEvent::Fail} else {PlaySoundEvent::Success});
+
                 *prev_state = Interaction::default();
                 *selected_seq_pos = vec![];
                 *selected_seq_index = vec![];
@@ -289,4 +355,65 @@ pub fn update_hack_display(
         }
     }
     
-}
\ No newline at end of file
+}
+fn deorbit_cost_label() -> String {
+    format!("Burn: {:.0} GW for {:.0}s", DEORBIT_DRAW, DEORBIT_SECS)
+}
+
+/// Deorbit panel of the hack console: pick one of the hacked satellites and burn it down
+pub fn update_deorbit_display(
+    field: Res<DebrisField>,
+    target_query: Query<&Interaction, (Changed<Interaction>, With<DeorbitTarget>)>,
+    button_query: Query<&Interaction, With<DeorbitButton>>,
+    target_text: Query<&mut Text, With<DeorbitTargetText>>,
+    status_text: Query<&mut Text, (With<DeorbitStatusText>, Without<DeorbitTargetText>)>,
+    mut selected: Local<usize>,
+    mut prev: Local<Interaction>,
+    mut status: Local<Option<String>>,
+    mut commands: EventWriter<DeorbitCommand>,
+    mut finished: EventReader<DeorbitFinished>,
+    mut event_writer: EventWriter<PlaySoundEvent>,
+) {
+    for f in finished.read() {
+        if f.failed {
+            event_writer.write(PlaySoundEvent::Fail);
+            *status = Some(format!("SAT-{} lost, burn aborted", f.object));
+        } else {
+            event_writer.write(PlaySoundEvent::Success);
+            *status = Some(format!("SAT-{} down, {} debris swept", f.object, f.removed.saturating_sub(1)));
+        }
+    }
+    let hacked: Vec<u32> = field.hacked_satellites().map(|o| o.id).collect();
+    for interaction in target_query.iter() {
+        if *interaction == Interaction::Pressed && !hacked.is_empty() {
+            *selected = (*selected + 1) % hacked.len();
+            *status = None;
+            event_writer.write(PlaySoundEvent::HackButtonPress);
+        }
+    }
+    if *selected >= hacked.len() {
+        *selected = 0;
+    }
+    for interaction in button_query.iter() {
+        if *prev == Interaction::Pressed && *interaction != Interaction::Pressed && field.deorbit.is_none() {
+            if let Some(object) = hacked.get(*selected) {
+                commands.write(DeorbitCommand { object: *object });
+                *status = None;
+            }
+        }
+        *prev = *interaction;
+    }
+
+    let target = match (field.deorbit, hacked.get(*selected)) {
+        (Some(deorbit), _) => format!("Burning SAT-{}: {:.0}s", deorbit.object, deorbit.remaining.max(0.)),
+        (None, Some(object)) => format!("Deorbit SAT-{} ({}/{})", object, *selected + 1, hacked.len()),
+        (None, None) => "No hacked satellites".to_string(),
+    };
+    for mut text in target_text {
+        text.0 = target.clone();
+    }
+    let status = status.clone().unwrap_or_else(deorbit_cost_label);
+    for mut text in status_text {
+        text.0 = status.clone();
+    }
+}
@@ -1,10 +1,10 @@
 use std::time::Duration;
 
 use bevy::prelude::*;
-use components::{InInteractionArray, InteractGlowEvent, KeyTimer, ScrollSelector};
+use components::{InInteractionArray, InteractGlowEvent, InteractionTypes, KeyTimer, ScrollSelector};
 use systems::*;
 
-use crate::{core::states::{GlobalAppState, OnGame}, interactions::{chain_reaction_display::*, collision_minigame::*, hack_minigame::*, pipe_puzzle::*, warning_interface::*, wave_modulator::*, wires_minigame::*}, ui::components::hack_button::ui_hack_button_hover};
+use crate::{core::states::{GlobalAppState, OnGame}, interactions::{chain_reaction_display::*, collision_minigame::*, hack_minigame::*, pipe_puzzle::*, power_routing::*, warning_interface::*, wave_modulator::*, wires_minigame::*}, ui::components::hack_button::ui_hack_button_hover};
 
 mod systems;
 pub mod components;
@@ -15,6 +15,7 @@ pub mod collision_minigame;
 pub mod warning_interface;
 pub mod hack_minigame;
 pub mod wires_minigame;
+pub mod power_routing;
 
 pub struct InteractionsPlugin;
 
@@ -26,14 +27,12 @@ impl Plugin for InteractionsPlugin {
         .insert_resource(ScrollSelector::default())
         .insert_resource(InInteractionArray {
             in_interaction: components::InteractionTypes::ChainReactionDisplay,
+            in_interaction_entity: None,
             in_any_interaction: false,
         })
         .insert_resource(Spinny::default())
         .insert_resource(WaveModulatorConsts::default())
         .insert_resource(CollisionMinigameConsts::default())
-        .insert_resource(PipeMinigame::default())
-        .insert_resource(HackGrid::default())
-        .insert_resource(WireMinigame::default())
         .insert_resource(WarningTimer {timer: Timer::new(Duration::from_secs_f32(1.), TimerMode::Repeating)})
         .add_systems(Update, (
             (interact, update_interactables,
@@ -52,11 +51,14 @@ impl Plugin for InteractionsPlugin {
                     update_collision_minigame
                 ).chain(),
                 (
-                    init_hack_display, open_hack_display, update_hack_display.before(ui_hack_button_hover)
+                    init_hack_display, open_hack_display, update_hack_display.before(ui_hack_button_hover), update_deorbit_display
                 ).chain(),
                 (
                     open_wires_display, touch_wires_inlet
                 ).chain(),
+                (
+                    open_power_routing_display, interact_with_power_spinny, update_power_routing_display
+                ).chain(),
             )
             .run_if(in_state(GlobalAppState::InGame))
             ).chain(),
@@ -64,3 +66,13 @@ impl Plugin for InteractionsPlugin {
         ;
     }
 }
+
+/// Every interactable keeps its own puzzle, so two of a kind can be broken and fixed independently
+pub fn insert_puzzle_state(entity: &mut EntityCommands, kind: &InteractionTypes) {
+    match kind {
+        InteractionTypes::PipePuzzle => {entity.insert(PipeMinigame::default());}
+        InteractionTypes::HackMinigame => {entity.insert(HackGrid::default());}
+        InteractionTypes::WiresMinigame => {entity.insert(WireMinigame::default());}
+        _ => {}
+    }
+}
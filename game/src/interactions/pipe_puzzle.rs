@@ -1,7 +1,8 @@
 use bevy::{platform::collections::HashSet, prelude::*};
 use bevy_tailwind::tw;
+use serde::{Deserialize, Serialize};
 
-use crate::{interactions::components::{InInteractionArray, InteractionTypes}, ui::{components::containers::base::{main_container_handle, sub_container_handle, ui_main_container, ui_sub_container}, target::LowresUiContainer}, utils::{custom_material_loader::PipesAtlasHandles, debree::{Malfunction, MalfunctionType, Resolved}, spacial_audio::PlaySoundEvent}};
+use crate::{interactions::components::{InInteractionArray, InteractableId, InteractionTypes}, ui::{components::containers::base::{main_container_handle, sub_container_handle, ui_main_container, ui_sub_container}, target::LowresUiContainer}, utils::{custom_material_loader::PipesAtlasHandles, debree::{Malfunction, MalfunctionType, Resolved}, rng::SeededRng, spacial_audio::PlaySoundEvent}};
 
 // WIBECODE RULES 🤘🧑‍🎤
 const ROWS: usize = 5;
@@ -17,7 +18,7 @@ pub fn open_pipe_puzzle_display(
     lowres_container: Single<Entity, With<LowresUiContainer>>,
     pipes_atlas_handles: Res<PipesAtlasHandles>,
     asset_server: Res<AssetServer>,
-    mut pipes: ResMut<PipeMinigame>,
+    pipe_minigames: Query<&PipeMinigame>,
     mut event_writer: EventWriter<PlaySoundEvent>,
 ) {
     // TODO: add pipe sounds
@@ -28,7 +29,7 @@ pub fn open_pipe_puzzle_display(
         }
     } else {
         // pipes.fill_solved();
-        if in_interaction_array.in_interaction == InteractionTypes::PipePuzzle && in_interaction_array.in_any_interaction {
+        if let Some(pipes) = in_interaction_array.using(InteractionTypes::PipePuzzle).and_then(|e| pipe_minigames.get(e).ok()) {
             let main = main_container_handle(&asset_server);
             let sub = sub_container_handle(&asset_server);
             
@@ -103,34 +104,38 @@ pub fn open_pipe_puzzle_display(
 }
 
 pub fn update_pipes(
-    mut pipe_image_nodes: Query<(&mut PipeEntity, &mut ImageNode, &Interaction), Changed<Interaction>>,
-    mut pipes: ResMut<PipeMinigame>,
+    mut pipe_image_nodes: Query<(&mut PipeEntity, &mut ImageNode, Ref<Interaction>)>,
+    mut pipe_minigames: Query<(&InteractableId, &mut PipeMinigame)>,
     mut malfunction: ResMut<Malfunction>,
     mut event_writer: EventWriter<PlaySoundEvent>,
     in_interaction_array: Res<InInteractionArray>,
 ){
-    if malfunction.malfunction_types.contains(&MalfunctionType::Engine) {
+    let Some(entity) = in_interaction_array.using(InteractionTypes::PipePuzzle) else {return;};
+    let Ok((site, mut pipes)) = pipe_minigames.get_mut(entity) else {return;};
+    if malfunction.is_broken(*site) {
         for (mut pipe, mut pipe_image_node, pipe_interaction) in pipe_image_nodes.iter_mut() {
             if let Some(texture_atlas) = &mut pipe_image_node.texture_atlas {
-                if *pipe_interaction == Interaction::Pressed {
+                if pipe_interaction.is_changed() && *pipe_interaction == Interaction::Pressed {
                     pipes.rotate(pipe.position);
-                    if let Some(p) = pipes.get_pipe(pipe.position.x as usize, pipe.position.y as usize) {
-                        texture_atlas.index = p.get_index();
-                    }
                     if pipes.is_solved() {
                         event_writer.write(PlaySoundEvent::Success);
                         malfunction.resolved.push(Resolved {
                             resolved_type: MalfunctionType::Engine,
+                            site: *site,
                             failed: false,
                         });
                     }
                 }
+                // pipes also turn on their own as the engine fails
+                if let Some(p) = pipes.get_pipe(pipe.position.x as usize, pipe.position.y as usize) {
+                    texture_atlas.index = p.get_index();
+                }
             }
         }
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub enum PipeType {
     SINGLE,
     LINE,
@@ -142,7 +147,7 @@ pub enum PipeType {
 type PipeRotation = u8;
 type PipeSide = u8;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Pipe {
     variant: PipeType,
     rotation: PipeRotation 
@@ -231,12 +236,6 @@ pub fn get_candidates(optional: &[PipeSide], include: &[PipeSide]) -> Vec<Pipe>
     candidates
 }
 
-fn random_u32() -> u32 {
-    getrandom::u32().unwrap()
-}
-
-
-
 fn pipe_weight(pipe_type: PipeType) -> usize {
     match pipe_type {
         PipeType::SINGLE => 10,
@@ -247,7 +246,7 @@ fn pipe_weight(pipe_type: PipeType) -> usize {
     }
 }
 
-fn pick_candidate(candidates: &[Pipe]) -> Option<Pipe> {
+fn pick_candidate(rng: &mut SeededRng, candidates: &[Pipe]) -> Option<Pipe> {
     if candidates.is_empty() {
         return None;
     }
@@ -262,7 +261,7 @@ fn pick_candidate(candidates: &[Pipe]) -> Option<Pipe> {
     }
 
     // Получаем случайное число в диапазоне [0, total_weight)
-    let rnd = (random_u32() as usize) % total_weight;
+    let rnd = (rng.u32() as usize) % total_weight;
 
     // Находим индекс, соответствующий rnd
     let mut acc = 0;
@@ -409,7 +408,7 @@ fn get_include_exclude_sides(
 
 
 
-#[derive(Resource)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct PipeMinigame {
     grid: Vec<Vec<Option<Pipe>>>,    
 }
@@ -421,10 +420,10 @@ impl PipeMinigame {
         let Some(p) = v else {return;};
         p.rotation = (p.rotation + 1) % 4;
     }
-    pub fn fill_solved(&mut self) {
+    pub fn fill_solved(&mut self, rng: &mut SeededRng) {
         self.clear();
-        let start_r = random_u32() as usize % ROWS;
-        let start_c = random_u32() as usize % COLS;
+        let start_r = rng.u32() as usize % ROWS;
+        let start_c = rng.u32() as usize % COLS;
 
         let allowed = allowed_sides_grid_corners((start_r, start_c), &mut vec![0, 1, 2, 3]);
         let candidates = get_candidates(&allowed, &[]);
@@ -432,7 +431,7 @@ impl PipeMinigame {
             panic!("No candidates for start cell");
         }
 
-        let first = pick_candidate(&candidates).expect("No candidate for first");
+        let first = pick_candidate(rng, &candidates).expect("No candidate for first");
         self.grid[start_r][start_c] = Some(first);
 
         let mut queue = vec![(start_r, start_c)];
@@ -456,20 +455,37 @@ impl PipeMinigame {
                     continue;
                 }
 
-                self.grid[nr][nc] = pick_candidate(&candidates);
+                self.grid[nr][nc] = pick_candidate(rng, &candidates);
                 queue.push((nr, nc));
             }
         }
         if !self.is_solved() {
-            self.fill_solved();
+            self.fill_solved(rng);
+        }
+    }
+
+    /// Knocks `count` random pipes out of place, see `Stage::jolted_pipes`
+    pub fn jolt(&mut self, rng: &mut SeededRng, count: usize) {
+        let placed: Vec<(usize, usize)> = (0..ROWS)
+            .flat_map(|r| (0..COLS).map(move |c| (r, c)))
+            .filter(|(r, c)| self.grid[*r][*c].is_some())
+            .collect();
+        if placed.is_empty() {
+            return;
+        }
+        for _ in 0..count {
+            let (r, c) = placed[rng.index(placed.len())];
+            if let Some(pipe) = &mut self.grid[r][c] {
+                pipe.rotation = (pipe.rotation + 1 + (rng.u32() % 3) as u8) % 4;
+            }
         }
     }
 
-    pub fn shuffle(&mut self) {
+    pub fn shuffle(&mut self, rng: &mut SeededRng) {
         for row in self.grid.iter_mut() {
             for cell in row.iter_mut() {
                 if let Some(pipe) = cell {
-                    pipe.rotation = (random_u32() % 4) as u8;
+                    pipe.rotation = (rng.u32() % 4) as u8;
                 }
             }
         }
@@ -528,7 +544,8 @@ impl Default for PipeMinigame {
         let mut s = Self {
             grid: vec![vec![None; COLS]; ROWS],
         };
-        s.fill_solved();
+        // placeholder layout until the first engine malfunction draws from the run seed
+        s.fill_solved(&mut SeededRng::new(0));
         // s.shuffle();
         s
     }
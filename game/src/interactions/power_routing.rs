@@ -0,0 +1,178 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use bevy_tailwind::tw;
+
+use crate::{interactions::{components::{InInteractionArray, InteractionTypes}, wave_modulator::{Spinny, SpinnyIds}}, ui::{components::{containers::{base::{main_container_handle, sub_container_handle, ui_main_container, ui_sub_container}, text_display::{text_display_green_handle, ui_text_display_green_with_text}}, spinny::ui_spinny}, target::LowresUiContainer}, utils::{custom_material_loader::SpinnyAtlasHandles, energy::{Energy, ENGINE_MIN_ROUTING, GRAVITY_MIN_ROUTING}, spacial_audio::PlaySoundEvent}};
+
+const NUM_ROUTING_STATES: f32 = 8.;
+const ANGLE_PER_ROUTING_STATE: f32 = PI / NUM_ROUTING_STATES;
+
+const ENGINE: usize = 0;
+const GRAVITY: usize = 1;
+const LAMPS: usize = 2;
+const SUBSYSTEMS: [(usize, &str); 3] = [(ENGINE, "ENGINE"), (GRAVITY, "GRAVITY"), (LAMPS, "LAMPS")];
+
+#[derive(Component)]
+pub struct RoutingText {
+    pub id: usize,
+}
+
+#[derive(Component)]
+pub struct RoutingSurplusText;
+
+fn routing_share(energy: &Energy, id: usize) -> f32 {
+    match id {
+        ENGINE => energy.routing.engine,
+        GRAVITY => energy.routing.gravity,
+        _ => energy.routing.lamps,
+    }
+}
+
+fn routing_label(energy: &Energy, id: usize) -> String {
+    let share = routing_share(energy, id);
+    let offline = match id {
+        ENGINE => share < ENGINE_MIN_ROUTING,
+        GRAVITY => share < GRAVITY_MIN_ROUTING,
+        _ => share <= 0.,
+    };
+    let name = SUBSYSTEMS[id].1;
+    if offline {
+        format!("{} OFFLINE", name)
+    } else {
+        format!("{} {:>3}%", name, (share * 100.).round() as i32)
+    }
+}
+
+fn surplus_label(energy: &Energy) -> String {
+    format!("Surplus: {:.0} GW", energy.surplus)
+}
+
+pub fn open_power_routing_display(
+    mut commands: Commands,
+    in_interaction_array: Res<InInteractionArray>,
+    mut already_spawned: Local<Option<Entity>>,
+    spinny_atlas_handles: Res<SpinnyAtlasHandles>,
+    lowres_container: Single<Entity, With<LowresUiContainer>>,
+    asset_server: Res<AssetServer>,
+    energy: Res<Energy>,
+    mut event_writer: EventWriter<PlaySoundEvent>,
+) {
+    if let Some(entity) = *already_spawned {
+        if !in_interaction_array.in_any_interaction {
+            commands.entity(entity).despawn();
+            *already_spawned = None;
+        }
+    } else {
+        if in_interaction_array.in_interaction == InteractionTypes::PowerRouting && in_interaction_array.in_any_interaction {
+            event_writer.write(PlaySoundEvent::OpenUi);
+            let main = main_container_handle(&asset_server);
+            let sub = sub_container_handle(&asset_server);
+            let text_bundle = text_display_green_handle(&asset_server);
+
+            let mut columns = vec![];
+            for (id, _) in SUBSYSTEMS {
+                // spinnies start where the current routing is
+                let state = (routing_share(&energy, id) * (NUM_ROUTING_STATES - 1.)).round() as usize;
+                let handles = (spinny_atlas_handles.image_handle.clone(), spinny_atlas_handles.layout_handle.clone());
+                let spinny = commands.spawn(
+                    ui_spinny(&handles, SpinnyIds { id, angle: (state as f32 + 0.5) * ANGLE_PER_ROUTING_STATE }, ())
+                ).insert(
+                    ImageNode::from_atlas_image(handles.0.clone(), TextureAtlas { layout: handles.1.clone(), index: state })
+                ).id();
+                let spinny_entity = commands.spawn(ui_main_container(&main, ())).add_child(spinny).id();
+                let label = routing_label(&energy, id);
+                let text_entity = commands.spawn(
+                ui_main_container(&main, ())
+                ).with_children(|cmd| {
+                    cmd.spawn(
+                        ui_text_display_green_with_text(&text_bundle, (RoutingText { id }, RoutingText { id }), &label, &asset_server)
+                    ).insert(tw!("w-[170px] items-center justify-center p-[5px]"));
+                }).id();
+                let column = commands.spawn(tw!("flex flex-col items-center gap-[1px]"))
+                    .add_child(spinny_entity)
+                    .add_child(text_entity)
+                    .id();
+                columns.push(column);
+            }
+
+            let surplus = surplus_label(&energy);
+            let surplus_entity = commands.spawn(
+            ui_main_container(&main, ())
+            ).with_children(|cmd| {
+                cmd.spawn(
+                    ui_text_display_green_with_text(&text_bundle, (RoutingSurplusText, RoutingSurplusText), &surplus, &asset_server)
+                ).insert(tw!("w-[350px] items-center justify-center p-[5px]"));
+            }).id();
+
+            let entity = commands.spawn(
+                tw!("items-center justify-center w-full h-full"),
+            ).with_children(|cmd| {
+                cmd.spawn(ui_main_container(&main, ())).insert(
+                    tw!("flex flex-col")
+                ).with_children(|cmd| {
+                    cmd.spawn(ui_sub_container(&sub, ()))
+                    .with_children(|cmd| {
+                        cmd.spawn(tw!("items-center justify-center w-full h-full gap-[1px]"),)
+                        .add_children(&columns);
+                    });
+                    cmd.spawn(ui_sub_container(&sub, ()))
+                    .with_children(|cmd| {
+                        cmd.spawn(tw!("items-center justify-center w-full h-full"),)
+                        .add_child(surplus_entity);
+                    });
+                });
+            }).id();
+            *already_spawned = Some(entity);
+            commands.entity(*lowres_container).add_child(entity);
+        }
+    }
+}
+
+pub fn interact_with_power_spinny(
+    in_interaction_array: Res<InInteractionArray>,
+    spinny: Res<Spinny>,
+    spinny_q: Query<(&SpinnyIds, &mut ImageNode)>,
+    mut energy: ResMut<Energy>,
+    mut event_writer: EventWriter<PlaySoundEvent>,
+) {
+    if in_interaction_array.in_interaction != InteractionTypes::PowerRouting || !in_interaction_array.in_any_interaction {
+        return;
+    }
+    if !spinny.is_locked || spinny.angle < 0. {
+        return;
+    }
+    let snapped_state = ((spinny.angle / ANGLE_PER_ROUTING_STATE).floor() as usize)
+        .min(NUM_ROUTING_STATES as usize - 1);
+    for (spinny_id, mut spinny_image_node) in spinny_q {
+        if spinny_id.id != spinny.locked_id {
+            continue;
+        }
+        let share = snapped_state as f32 / (NUM_ROUTING_STATES - 1.);
+        match spinny_id.id {
+            ENGINE => energy.routing.engine = share,
+            GRAVITY => energy.routing.gravity = share,
+            LAMPS => energy.routing.lamps = share,
+            _ => {}
+        }
+        if let Some(texture_atlas) = &mut spinny_image_node.texture_atlas {
+            if texture_atlas.index != snapped_state {
+                event_writer.write(PlaySoundEvent::SpinnyClick);
+                texture_atlas.index = snapped_state;
+            }
+        }
+    }
+}
+
+pub fn update_power_routing_display(
+    energy: Res<Energy>,
+    routing_text: Query<(&mut Text, &RoutingText), Without<RoutingSurplusText>>,
+    surplus_text: Query<&mut Text, With<RoutingSurplusText>>,
+) {
+    for (mut text, t) in routing_text {
+        text.0 = routing_label(&energy, t.id);
+    }
+    for mut text in surplus_text {
+        text.0 = surplus_label(&energy);
+    }
+}
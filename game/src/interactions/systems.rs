@@ -5,7 +5,7 @@ use bevy_rapier2d::{prelude::CollisionEvent, rapier::prelude::CollisionEventFlag
 use shaders::VelocityEmmiter;
 use utils::{Easings, WrappedDelta};
 
-use crate::{interactions::components::PlayerSensor, physics::player::Player, tilemap::light::LIT_OVERLAY_LAYER, utils::{custom_material_loader::{TextureAtlasHandles, KEYS_ATLAS_SIZE}, debree::DebreeLevel, mouse::CursorPosition}};
+use crate::{core::run::RunEntity, interactions::components::PlayerSensor, physics::player::Player, tilemap::light::LIT_OVERLAY_LAYER, utils::{custom_material_loader::{TextureAtlasHandles, KEYS_ATLAS_SIZE}, debree::DebreeLevel, mouse::CursorPosition}};
 
 use super::{chain_reaction_display::ChainGraphMaterial, components::{FKey, InInteraction, InInteractionArray, InteractGlowEvent, InteractableMaterial, InteractionTypes, KeyTimer, ScrollSelector}, wave_modulator::WaveGraphMaterial};
 
@@ -32,6 +32,7 @@ pub fn interact(
         if let Ok(interaction_type) = interaction_types.get(current_entity) {
             in_interaction_array.in_any_interaction = true;
             in_interaction_array.in_interaction = interaction_type.clone();
+            in_interaction_array.in_interaction_entity = Some(current_entity);
         } else {
             warn!("SOMETHING WENT WRONG");
         }
@@ -114,7 +115,8 @@ pub fn interact(
                 Transform::from_translation(interactable_pos + Vec3::Y * 50.),
                 FKey,
                 Name::new("FKey"),
-                LIT_OVERLAY_LAYER
+                LIT_OVERLAY_LAYER,
+                RunEntity,
             )).id();
             scroll_selector.current_displayed = Some(e_key_entity.clone());
             }
@@ -4,7 +4,7 @@ use bevy::prelude::*;
 use bevy_tailwind::tw;
 use utils::WrappedDelta;
 
-use crate::{interactions::components::{InInteractionArray, InteractionTypes}, ui::{components::{containers::{base::{main_container_handle, sub_container_handle, ui_main_container, ui_sub_container}, text_display::{text_display_green_handle, ui_text_display_green_with_text}}, ui_atlas_container::ui_atlas_container}, target::LowresUiContainer}, utils::{custom_material_loader::{MalfAtlasHandles, SpriteAssets, WarningAtlasHandles}, debree::{Malfunction, MalfunctionType, TIME_TO_RESOLVE}, energy::{Energy, ENGINE_THRESHOLD}, spacial_audio::PlaySoundEvent}};
+use crate::{interactions::components::{InInteractionArray, InteractionTypes}, ui::{components::{containers::{base::{main_container_handle, sub_container_handle, ui_main_container, ui_sub_container}, text_display::{text_display_green_handle, ui_text_display_green_with_text}}, ui_atlas_container::ui_atlas_container}, target::LowresUiContainer}, utils::{custom_material_loader::{MalfAtlasHandles, SpriteAssets, WarningAtlasHandles}, debree::{Malfunction, MalfunctionType}, energy::{Energy, ENGINE_THRESHOLD}, failure_graph::{Strains, Subsystem}, malfunction_defs::{MalfunctionAssets, MalfunctionDefinitions}, spacial_audio::PlaySoundEvent}};
 
 pub const WARNING_GRID_COLUMNS: u32 = 2;
 pub const WARNING_GRID_ROWS: u32 = 2;
@@ -35,6 +35,27 @@ pub struct MalfMini {
     pub malfunction_type: MalfunctionType,
 }
 
+/// Edge of the failure graph, shows whether letting `from` run out would strain `to` or already does
+#[derive(Component, Clone)]
+pub struct CascadeEdgeText {
+    pub from: MalfunctionType,
+    pub to: Subsystem,
+}
+
+fn cascade_edge_label(edge: &CascadeEdgeText, malfunction: &Malfunction, strains: &Strains) -> String {
+    let to = match &edge.to {
+        Subsystem::Malfunction(kind) => format!("{:?}", kind),
+        Subsystem::DebreeLevel => "Debree".to_string(),
+    };
+    if let Some(secs) = strains.remaining(&edge.from, &edge.to) {
+        format!("{:?} > {} : {:.0}s", edge.from, to, secs)
+    } else if malfunction.malfunction_types.contains(&edge.from) {
+        format!("{:?} > {} !", edge.from, to)
+    } else {
+        format!("{:?} > {}", edge.from, to)
+    }
+}
+
 pub fn open_warning_interface_display(
     mut commands: Commands,
     in_interaction_array: Res<InInteractionArray>,
@@ -47,6 +68,9 @@ pub fn open_warning_interface_display(
     mut event_writer: EventWriter<PlaySoundEvent>,
     energy: Res<Energy>,
     malf_atlas_handles: Res<MalfAtlasHandles>,
+    strains: Res<Strains>,
+    malfunction_assets: Res<MalfunctionAssets>,
+    definitions: Res<Assets<MalfunctionDefinitions>>,
 ) {
     if let Some(entity) = *already_spawned {
         if !in_interaction_array.in_any_interaction {
@@ -115,6 +139,21 @@ pub fn open_warning_interface_display(
                 ).id());
             }
 
+            let mut cascade_entities = vec![];
+            let edges = definitions.get(&malfunction_assets.definitions)
+                .into_iter()
+                .flat_map(|d| d.malfunctions.iter())
+                .flat_map(|d| d.downstream.iter().map(|edge| CascadeEdgeText {
+                    from: d.kind.clone(),
+                    to: edge.to.clone(),
+                }));
+            for edge in edges {
+                let label = cascade_edge_label(&edge, &malfunction, &strains);
+                cascade_entities.push(commands.spawn(
+                    ui_text_display_green_with_text(&text_bundle, (edge.clone(), edge), &label, &asset_server)
+                ).id());
+            }
+
             let entity = commands.spawn(
                 tw!("items-center justify-center w-full h-full"),
             ).with_children(|cmd|{
@@ -143,6 +182,13 @@ pub fn open_warning_interface_display(
                         cmd.spawn(tw!("items-center justify-center w-full h-full"),)
                         .add_children(&malf_entities);
                     });
+                    if !cascade_entities.is_empty() {
+                        cmd.spawn(ui_sub_container(&sub, ()))
+                        .with_children(|cmd| {
+                            cmd.spawn(tw!("flex-col items-center justify-center w-full h-full gap-[1px]"),)
+                            .add_children(&cascade_entities);
+                        });
+                    }
                 });
             }).id();
             *already_spawned = Some(entity);
@@ -165,6 +211,8 @@ pub fn update_warning_interface_display(
     timer_text: Query<(&mut Text, &TimerText), Without<SurplusText>>,
     warning_text: Query<&mut Text, (Without<SurplusText>, Without<TimerText>, With<WarningText>)>,
     mini_image_nodes: Query<(&mut ImageNode, &MalfMini)>,
+    cascade_text: Query<(&mut Text, &CascadeEdgeText), (Without<SurplusText>, Without<TimerText>, Without<WarningText>)>,
+    strains: Res<Strains>,
     mut event_writer: EventWriter<PlaySoundEvent>,
 ) {
     warning_timer.timer.tick(Duration::from_secs_f32(time.dt()));
@@ -172,11 +220,19 @@ pub fn update_warning_interface_display(
         text.0 = format!("Power Surplus : {} GW", energy.surplus - ENGINE_THRESHOLD);
     }
     for (mut text, timer_text) in timer_text {
-        let mut time = "NaN".to_string();
-        if let Some(index) = malfunction.malfunction_types.iter().position(|r| r == &timer_text.malfunction_type) {
-            time = format!("{:.1}", TIME_TO_RESOLVE - malfunction.malfunction_timers[index].elapsed_secs());
-        }
-        text.0 = time;
+        // the same kind can be broken on several interactables, show the one that runs out first
+        let soonest = malfunction.malfunction_types.iter()
+            .zip(malfunction.malfunction_timers.iter())
+            .filter(|(r, _)| *r == &timer_text.malfunction_type)
+            .map(|(_, timer)| timer.remaining_secs())
+            .reduce(f32::min);
+        text.0 = match soonest {
+            Some(secs) => format!("{:.1}", secs),
+            None => "NaN".to_string(),
+        };
+    }
+    for (mut text, edge) in cascade_text {
+        text.0 = cascade_edge_label(edge, &malfunction, &strains);
     }
     for (mut node, mini) in mini_image_nodes {
         if let Some(atlas) = &mut node.texture_atlas {
@@ -2,10 +2,11 @@ use std::f32::consts::{PI, TAU};
 
 use bevy::{color::palettes::css::{BLUE, RED}, prelude::*, render::render_resource::{AsBindGroup, Extent3d, ShaderRef, TextureDescriptor, TextureUsages}, sprite::{AlphaMode2d, Material2d}, ui::RelativeCursorPosition};
 use bevy_tailwind::tw;
+use serde::{Deserialize, Serialize};
 
-use crate::{interactions::components::PlayerSensor, ui::{components::{containers::{base::{main_container_handle, sub_container_handle, ui_main_container, ui_sub_container}, text_display::{text_display_green_handle, ui_text_display_green_with_text}, viewport_container::{ui_viewport_container, viewport_handle}}, spinny::ui_spinny, ui_submit_button::{submit_button_bundle, ui_submit_button}}, target::LowresUiContainer}, utils::{custom_material_loader::{SpinnyAtlasHandles, SpriteAssets}, debree::{Malfunction, MalfunctionType, Resolved}, mouse::CursorPosition, spacial_audio::PlaySoundEvent}};
+use crate::{interactions::components::PlayerSensor, ui::{components::{containers::{base::{main_container_handle, sub_container_handle, ui_main_container, ui_sub_container}, text_display::{text_display_green_handle, ui_text_display_green_with_text}, viewport_container::{ui_viewport_container, viewport_handle}}, spinny::ui_spinny, ui_submit_button::{submit_button_bundle, ui_submit_button}}, target::LowresUiContainer}, utils::{custom_material_loader::{SpinnyAtlasHandles, SpriteAssets}, debree::{Malfunction, MalfunctionType, Resolved}, mouse::CursorPosition, rng::{GameRng, RngStream, SeededRng}, severity::Stage, spacial_audio::PlaySoundEvent}};
 
-use super::components::{InInteractionArray, InteractionTypes};
+use super::components::{InInteractionArray, InteractableId, InteractionTypes};
 
 
 #[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
@@ -73,6 +74,7 @@ pub fn open_wave_modulator_display(
     sprite_assets: Res<SpriteAssets>,
     asset_server: Res<AssetServer>,
     mut malfunction: ResMut<Malfunction>,
+    sites: Query<&InteractableId>,
     mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
     mut event_writer: EventWriter<PlaySoundEvent>,
 ) {
@@ -124,7 +126,7 @@ pub fn open_wave_modulator_display(
 
             let mut is_active = 0.;
             let mut wave_text = ANTENNAS_WORK;
-            if malfunction.malfunction_types.contains(&MalfunctionType::Waves) {
+            if malfunction.broken_at(in_interaction_array.using(InteractionTypes::WaveModulator), &sites).is_some() {
                 is_active = 1.;
                 wave_text = WAVES_NOT_SYNCHRONISED;
             }
@@ -254,6 +256,8 @@ pub fn interact_with_wavemod_spinny(
     mut material_assets: ResMut<Assets<WaveGraphMaterial>>,
     modulator_consts: Res<WaveModulatorConsts>,
     malfunction: Res<Malfunction>,
+    in_interaction_array: Res<InInteractionArray>,
+    sites: Query<&InteractableId>,
     time: Res<Time>,
     mut event_writer: EventWriter<PlaySoundEvent>,
 ) {
@@ -272,7 +276,7 @@ pub fn interact_with_wavemod_spinny(
                 if let Some(material) = material_assets.get_mut(*material_handle) {
                     if modulator_consts.is_loaded {
                         let mut is_active = 0.;
-                        if malfunction.malfunction_types.contains(&MalfunctionType::Waves) {
+                        if malfunction.broken_at(in_interaction_array.using(InteractionTypes::WaveModulator), &sites).is_some() {
                             is_active = 1.;
                         }
                         material.is_active = is_active;
@@ -306,19 +310,35 @@ pub fn interact_with_wavemod_spinny(
     }
 }
 
-#[derive(Resource, Default)]
+#[derive(Resource, Default, Clone, Serialize, Deserialize)]
 pub struct WaveModulatorConsts {
     pub consts1: [f32; 8],
     pub consts2: [Vec<f32>; 4],
     pub is_loaded: bool,
 }
 
+impl WaveModulatorConsts {
+    /// Whether every knob of `current` sits within `tolerance` spinny states of its target
+    pub fn in_sync(&self, current: [f32; 4], tolerance: usize) -> bool {
+        (0..4).all(|k| {
+            let target = self.consts1[4 + k];
+            let state = |v: f32| self.consts2[k].iter().position(|s| *s == v);
+            match (state(current[k]), state(target)) {
+                (Some(s), Some(t)) => s.abs_diff(t) <= tolerance,
+                _ => current[k] == target,
+            }
+        })
+    }
+}
+
 pub fn generate_wave_modulator_consts(
     mut consts: ResMut<WaveModulatorConsts>,
     malfunction: Res<Malfunction>,
+    mut rng: ResMut<GameRng>,
 ) {
     if malfunction.malfunction_types.contains(&MalfunctionType::Waves) && !consts.is_loaded {
         consts.is_loaded = true;
+        let rng = rng.stream(RngStream::Waves);
         // For vertical offset (a, ra)
         let mi_offset = 0.4;
         let ma_offset = 0.6;
@@ -335,22 +355,22 @@ pub fn generate_wave_modulator_consts(
         let mi_freq = 1.0;
         let ma_freq = 3.0; // 1 to 3 waves across the texture
 
-        let a = gen_wave_rng(mi_offset, ma_offset);
-        let b = gen_wave_rng(mi_amplitude, ma_amplitude);
-        let c = gen_wave_rng(mi_phase, ma_phase);
-        let d = gen_wave_rng(mi_freq, ma_freq);
+        let a = gen_wave_rng(rng, mi_offset, ma_offset);
+        let b = gen_wave_rng(rng, mi_amplitude, ma_amplitude);
+        let c = gen_wave_rng(rng, mi_phase, ma_phase);
+        let d = gen_wave_rng(rng, mi_freq, ma_freq);
 
-        let ra = a.1[((getrandom::u32().unwrap() as f32 / u32::MAX as f32) * NUM_SPINNY_STATES) as usize];
-        let rb = b.1[((getrandom::u32().unwrap() as f32 / u32::MAX as f32) * NUM_SPINNY_STATES) as usize];
-        let rc = c.1[((getrandom::u32().unwrap() as f32 / u32::MAX as f32) * NUM_SPINNY_STATES) as usize];
-        let rd = d.1[((getrandom::u32().unwrap() as f32 / u32::MAX as f32) * NUM_SPINNY_STATES) as usize];
+        let ra = a.1[rng.index(NUM_SPINNY_STATES as usize)];
+        let rb = b.1[rng.index(NUM_SPINNY_STATES as usize)];
+        let rc = c.1[rng.index(NUM_SPINNY_STATES as usize)];
+        let rd = d.1[rng.index(NUM_SPINNY_STATES as usize)];
         consts.consts1 = [a.0, b.0, c.0, d.0, ra, rb, rc, rd];
         consts.consts2 = [a.1, b.1, c.1, d.1];
     }
 }
 
-fn gen_wave_rng(mi: f32, ma: f32) -> (f32, Vec<f32>) {
-    let a = mi as f32 + ((getrandom::u32().unwrap() as f32 / u32::MAX as f32) * (ma - mi) as f32);
+fn gen_wave_rng(rng: &mut SeededRng, mi: f32, ma: f32) -> (f32, Vec<f32>) {
+    let a = rng.range(mi, ma);
     let mut t = (0..NUM_SPINNY_STATES as i32 / 2)
     .map(|i| mi + ((a - mi) / (NUM_SPINNY_STATES / 2.) * i as f32)).collect::<Vec<f32>>();
     t.extend((0..NUM_SPINNY_STATES as i32 / 2).map(|i| a + ((ma - a) / (NUM_SPINNY_STATES / 2.) * i as f32)));
@@ -359,6 +379,8 @@ fn gen_wave_rng(mi: f32, ma: f32) -> (f32, Vec<f32>) {
 
 pub fn update_wave_modulator_display(
     mut malfunction: ResMut<Malfunction>,
+    in_interaction_array: Res<InInteractionArray>,
+    sites: Query<&InteractableId>,
     modulator_consts: Res<WaveModulatorConsts>,
     material_handle: Single<&MaterialNode<WaveGraphMaterial>>,
     mut material_assets: ResMut<Assets<WaveGraphMaterial>>,
@@ -368,10 +390,8 @@ pub fn update_wave_modulator_display(
     text: Query<&mut Text, With<WaveModText>>,
     mut event_writer: EventWriter<PlaySoundEvent>,
 ) {
-    let mut in_progress = false;
-    if malfunction.malfunction_types.contains(&MalfunctionType::Waves) {
-        in_progress = true;
-    }
+    let site = malfunction.broken_at(in_interaction_array.using(InteractionTypes::WaveModulator), &sites);
+    let in_progress = site.is_some();
     for (interaction, mut node) in
         &mut interaction_query
     {
@@ -389,24 +409,10 @@ pub fn update_wave_modulator_display(
         }
         *prev = *interaction;
     }
-    if in_progress && modulator_consts.is_loaded {
+    if let Some(site) = site.filter(|_| modulator_consts.is_loaded) {
         if let Some(material) = material_assets.get_mut(*material_handle) {
-            let a1 = material.ra;
-            let b1 = material.rb;
-            let c1 = material.rc;
-            let d1 = material.rd;
-
-            let [a2, b2, c2, d2] = [
-                material.a,
-                material.b,
-                material.c,
-                material.d,
-            ];
-
-            let mut in_sync = false;
-            if a1 == a2 && b1 == b2 && c1 == c2 && d1 == d2 {
-                in_sync = true;
-            }
+            let tolerance = malfunction.stage_at(site).unwrap_or(Stage::Warning).wave_tolerance();
+            let in_sync = modulator_consts.in_sync([material.a, material.b, material.c, material.d], tolerance);
             for mut text in text {
                 if in_sync {
                     text.0 = WAVES_SYNCHRONISED.to_string();
@@ -422,6 +428,7 @@ pub fn update_wave_modulator_display(
                     }
                     malfunction.resolved.push(Resolved {
                         resolved_type: MalfunctionType::Waves,
+                        site,
                         failed: !in_sync,
                     });
                     *prev = Interaction::default();
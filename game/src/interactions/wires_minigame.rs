@@ -4,12 +4,12 @@ use bevy::{prelude::*, ui::RelativeCursorPosition};
 use bevy_tailwind::tw;
 use pixel_utils::camera::{PixelCamera, TARGET_HEIGHT, TARGET_WIDTH};
 
-use crate::{interactions::components::{InInteractionArray, InteractionTypes}, ui::{components::{containers::{base::{main_container_handle, sub_container_handle, ui_main_container, ui_sub_container}, text_display::text_display_green_handle, viewport_container::viewport_handle}, spinny::ui_spinny, ui_submit_button::submit_button_bundle, wire_inlet::{ui_wire_inlet, wire_inlet_bundle}}, target::LowresUiContainer}, utils::{custom_material_loader::SpriteAssets, debree::{get_random_range, Malfunction, MalfunctionType, Resolved}, mouse::CursorPosition, spacial_audio::PlaySoundEvent}};
+use crate::{interactions::components::{InInteractionArray, InteractableId, InteractionTypes}, ui::{components::{containers::{base::{main_container_handle, sub_container_handle, ui_main_container, ui_sub_container}, text_display::text_display_green_handle, viewport_container::viewport_handle}, spinny::ui_spinny, ui_submit_button::submit_button_bundle, wire_inlet::{ui_wire_inlet, wire_inlet_bundle}}, target::LowresUiContainer}, utils::{custom_material_loader::SpriteAssets, debree::{Malfunction, Resolved}, mouse::CursorPosition, rng::{GameRng, RngStream, SeededRng}, severity::Stage, spacial_audio::PlaySoundEvent}};
 
 
 
 
-#[derive(Resource, Debug)]
+#[derive(Component, Debug)]
 pub struct WireMinigame {
     pub locked_id: Option<usize>,
     pub socket_positions: HashMap<usize, Vec2>,
@@ -54,11 +54,14 @@ pub struct Wire {
 #[derive(Component)]
 pub struct WireContainer;
 
-const WIRES : usize = 5;
+/// Sockets on each side of the panel, the most wires a stage can ask for
+pub const WIRES : usize = 5;
 const WIRE_SOCKETS : usize = WIRES * 2;
 
 pub fn refresh_game(
-    g: &mut ResMut<WireMinigame>,
+    g: &mut WireMinigame,
+    pairs: usize,
+    rng: &mut SeededRng,
 ) {
     g.task = HashMap::new();
     g.connected = HashMap::new();
@@ -95,9 +98,9 @@ pub fn refresh_game(
     };
 
 
-    for i in 0..WIRES {
-        let a = getrandom::u32().unwrap() as usize % WIRE_SOCKETS;
-        let b = getrandom::u32().unwrap() as usize % WIRE_SOCKETS;
+    for i in 0..pairs.min(WIRES) {
+        let a = rng.u32() as usize % WIRE_SOCKETS;
+        let b = rng.u32() as usize % WIRE_SOCKETS;
         let (a, b) = get_free_ids(a, b);
         info!("Bind {} to {}", a, b);
         g.calc_color(a, i);
@@ -122,8 +125,9 @@ pub fn open_wires_display(
     asset_server: Res<AssetServer>,
     mut malfunction: ResMut<Malfunction>,
     
-    mut wires: ResMut<WireMinigame>,
+    mut wire_minigames: Query<(&InteractableId, &mut WireMinigame)>,
     mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
+    mut rng: ResMut<GameRng>,
 ) {
     // TODO: add touch and open sfx and success sfx
     if let Some(entity) = *already_spawned {
@@ -132,13 +136,14 @@ pub fn open_wires_display(
             *already_spawned = None;
         }
     } else {
-        if in_interaction_array.in_interaction == InteractionTypes::WiresMinigame && in_interaction_array.in_any_interaction {
+        if let Some((site, mut wires)) = in_interaction_array.using(InteractionTypes::WiresMinigame).and_then(|e| wire_minigames.get_mut(e).ok()) {
             let main = main_container_handle(&asset_server);
             let sub = sub_container_handle(&asset_server);
             let text_bundle = text_display_green_handle(&asset_server);
             let submit_bundle = submit_button_bundle(&asset_server, &mut texture_atlases);
             let wire_bundle = wire_inlet_bundle(&asset_server);
-            refresh_game(&mut wires);
+            let pairs = malfunction.stage_at(*site).unwrap_or(Stage::Warning).wires();
+            refresh_game(&mut wires, pairs, rng.stream(RngStream::Wires));
 
             let mut wires_e = vec![];
             for i in 0..WIRE_SOCKETS {
@@ -199,7 +204,7 @@ pub fn touch_wires_inlet(
     wires_q: Query<(Entity ,&RelativeCursorPosition, &Wire, &GlobalTransform,  &Transform, &ComputedNode)>,
     wires_container: Query<(Entity, &RelativeCursorPosition, &ComputedNode), With<WireContainer>>,
     mouse_button: Res<ButtonInput<MouseButton>>,
-    mut wires: ResMut<WireMinigame>,
+    mut wire_minigames: Query<(&InteractableId, &mut WireMinigame)>,
     ui_scale : Res<UiScale>,
     sprite_assets: Res<SpriteAssets>,
     cursor: Res<CursorPosition>,
@@ -211,7 +216,9 @@ pub fn touch_wires_inlet(
     mut event_writer: EventWriter<PlaySoundEvent>,
     in_interaction_array: Res<InInteractionArray>,
 ) {
-    if !malfunction.malfunction_types.contains(&MalfunctionType::Reactor) {return;}
+    let Some((site, mut wires)) = in_interaction_array.using(InteractionTypes::WiresMinigame).and_then(|e| wire_minigames.get_mut(e).ok()) else {return;};
+    let site = *site;
+    if !malfunction.is_broken(site) {return;}
     let prev_locked = wires.locked_id.clone();
     if mouse_button.just_released(MouseButton::Left) {
         wires.locked_id = None;
@@ -328,6 +335,7 @@ pub fn touch_wires_inlet(
                         if wires.task.get(&locked_id) != Some(&wire.id) {
                             malfunction.resolved.push(Resolved {
                                 resolved_type: crate::utils::debree::MalfunctionType::Reactor,
+                                site,
                                 failed: true,
                             });
                             event_writer.write(PlaySoundEvent::Fail);
@@ -355,6 +363,7 @@ pub fn touch_wires_inlet(
                             event_writer.write(PlaySoundEvent::Success);
                             malfunction.resolved.push(Resolved {
                                 resolved_type: crate::utils::debree::MalfunctionType::Reactor,
+                                site,
                                 failed: false,
                             });
                         };
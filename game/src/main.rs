@@ -12,11 +12,17 @@ use std::collections::HashMap;
 use crate::core::states::OnGame;
 use crate::interactions::components::PlayerSensor;
 use crate::physics::constants::{INTERACTABLE_CG, PLAYER_SENSOR_CG};
+use crate::physics::platforms::PlatformsPlugin;
 use crate::physics::player::{spawn_player, Player, PlayerPlugin};
 use crate::tilemap::light::{LightEmitter, LightPlugin, LIT_OVERLAY_LAYER};
 use crate::tilemap::plugin::MapPlugin;
+use crate::utils::args::launch_arg;
 use crate::utils::background::StarBackgroundPlugin;
-use crate::utils::energy::EnergyPlugin;
+use crate::utils::debree::Malfunction;
+use crate::utils::energy::{Energy, EnergyPlugin, PowerEvent, PoweredSystem};
+use crate::utils::save::SavePlugin;
+use crate::utils::score::ScorePlugin;
+use crate::utils::history::HistoryPlugin;
 
 mod core;
 mod ui;
@@ -27,23 +33,33 @@ mod interactions;
 mod tilemap;
 
 fn main() {
+    if let Some(runs) = launch_arg("simulate") {
+        utils::sim::run_simulation(runs.parse().unwrap_or(1000));
+        return;
+    }
+    if let Some(path) = launch_arg("validate-map") {
+        std::process::exit(tilemap::validate::run_validation(&path));
+    }
     let mut app = App::new();
     app
         .add_plugins((
             CorePlugin,
             StarBackgroundPlugin,
             PlayerPlugin,
+            PlatformsPlugin,
             LightPlugin,
             MapPlugin,
             EnergyPlugin,
+            SavePlugin,
+            ScorePlugin,
+            HistoryPlugin,
             // SwitchableEguiInspectorPlugin::default(),
             // SwitchableRapierDebugPlugin::default(),
             DebugOverlayPlugin::default(),
         ))
         .add_systems(OnGame, spawn.after(spawn_player))
         .add_systems(Startup, setup)
-        .add_systems(OnGame, lights_setup)
-        .add_systems(Update, (light_flickering, set_volume))
+        .add_systems(Update, ((lights_setup, light_flickering).chain(), set_volume))
         .insert_resource(LightFlicker::default())
         // .add_systems(Update, update)
         .run();
@@ -75,33 +91,65 @@ fn set_volume(
 }
 
 
+const LAMPS_EMERGENCY_GLOW: f32 = 0.15;
+/// how far into the alarm colour lamps pulse while a malfunction escalates
+const LAMPS_ALARM_TINT: f32 = 0.7;
+
 #[derive(Default, Resource)]
 pub struct LightFlicker {
-    emitters: HashMap<Entity, f32>
+    /// default intensity and colour
+    emitters: HashMap<Entity, (f32, Vec3)>,
+    /// lamps are underpowered
+    brownout: bool,
 }
 
 pub fn lights_setup(
-    mut emitters: Query<(Entity, &LightEmitter)>,
+    mut emitters: Query<(Entity, &LightEmitter), Added<LightEmitter>>,
     mut fl : ResMut<LightFlicker>
 ){
+    // emitters come and go with the map, pick up new ones as they spawn
     for (e, em) in emitters.iter_mut() {
         // store defaults
-        fl.emitters.insert(e, em.intensity);
+        fl.emitters.insert(e, (em.intensity, em.color_and_rotation.truncate()));
     }
 }
 
 pub fn light_flickering(
     time: Res<Time>,
     mut emitters: Query<(Entity, &mut LightEmitter, &Transform)>,
-    fl : ResMut<LightFlicker>
+    mut fl : ResMut<LightFlicker>,
+    mut power_events: EventReader<PowerEvent>,
+    energy: Res<Energy>,
+    malfunction: Res<Malfunction>,
 ) {
+    for event in power_events.read() {
+        if event.system == PoweredSystem::Lamps {
+            fl.brownout = !event.powered;
+        }
+    }
     let t = time.elapsed_secs();
+    // lamps rerouted away still keep a faint emergency glow
+    let routed = LAMPS_EMERGENCY_GLOW + (1.0 - LAMPS_EMERGENCY_GLOW) * energy.routing.lamps;
+    // every lamp pulses together towards the colour of the worst malfunction stage
+    let alarm = malfunction.worst_stage().and_then(|s| s.light_tint())
+        .map(|(tint, hz)| (tint, LAMPS_ALARM_TINT * (0.5 - 0.5 * (t * hz * std::f32::consts::TAU).cos())));
     for (e, mut emitter, transform) in emitters.iter_mut() {
-        if let Some(base_intensity) = fl.emitters.get(&e) {
+        if let Some((base_intensity, base_color)) = fl.emitters.get(&e) {
             let pos = transform.translation;
             let noise = (pos.x.sin() * 13.37 + pos.y.cos() * 42.0 + t * 5.0).sin();
-            let flicker = 0.8 + 0.2 * noise;
-            emitter.intensity = base_intensity * flicker;
+            let flicker = if fl.brownout {
+                // dimmer, with short dropouts out of sync between lamps
+                let stutter = (pos.x * 7.1 + pos.y * 3.3 + t * 23.0).sin();
+                if stutter > 0.85 { 0.05 } else { 0.45 + 0.15 * noise }
+            } else {
+                0.8 + 0.2 * noise
+            };
+            emitter.intensity = base_intensity * flicker * routed;
+            let color = match alarm {
+                Some((tint, amount)) => base_color.lerp(tint, amount),
+                None => *base_color,
+            };
+            emitter.color_and_rotation = color.extend(emitter.color_and_rotation.w);
         }
     }
 }
@@ -178,11 +226,7 @@ pub fn spawn(
     p: Single<Entity, With<Player>>,
 ) {
     const GAP: f32 = 50.;
-    commands.entity(*p).insert((
-        SpatialListener::new(GAP),
-            // Transform::from_translation(vec3(0.0, 0.0, 0.0))
-        Transform::from_translation(vec3(774.582, 80.0, 0.0))
-    ));
+    commands.entity(*p).insert(SpatialListener::new(GAP));
     commands.entity(*p).with_children(|cmd|{
         cmd.spawn((
             Name::new("Player sensor"),
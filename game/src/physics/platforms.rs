@@ -16,10 +16,33 @@ impl Plugin for PlatformsPlugin {
 }
 
 
+#[derive(Clone, Copy, Debug, Default, Reflect)]
 pub enum MovingPlatformMode {
+    #[default]
     Loop,
     PingPong,
 }
+
+/// Path authored on a Tiled object, the object moves along it as a [`MovingPlatform`]
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component, Default)]
+pub struct PlatformPath {
+    /// offsets from the object, in pixels with y pointing down like in Tiled
+    pub positions: Vec<Vec2>,
+    /// pixels per 1/60 s
+    pub speed: f32,
+    pub mode: MovingPlatformMode,
+}
+
+impl Default for PlatformPath {
+    fn default() -> Self {
+        Self {
+            positions: vec![],
+            speed: 1.,
+            mode: MovingPlatformMode::Loop,
+        }
+    }
+}
 #[derive(Component)]
 pub struct MovingPlatform {
     pub positions: Vec<Vec2>,
@@ -1,11 +1,11 @@
 use std::{collections::HashMap, f32::consts::PI, sync::{Arc, RwLock}};
 
-use bevy::{asset::{self, LoadState}, prelude::*};
+use bevy::{asset::{self, LoadState}, prelude::*, transform::helper::TransformHelper};
 use bevy_inspector_egui::{bevy_egui::{EguiContextPass, EguiContexts}, egui};
 use bevy_rapier2d::prelude::*;
 use utils::WrappedDelta;
 
-use crate::{camera::plugin::CameraFocus, core::states::{GlobalAppState, OnGame, PreGameTasks}, interactions::components::InInteractionArray, physics::{animator::{PlayerAnimationNode, PlayerAnimations, PlayerAnimatorPlugin}, constants::*}, tilemap::{light::LightEmitter, plugin::{LadderCollider, SpacewalkCollider}}, utils::{mouse::CursorPosition, spacial_audio::PlaySoundEvent}};
+use crate::{camera::plugin::CameraFocus, core::run::RunEntity, core::states::{GlobalAppState, OnGame, PreGameTasks}, interactions::components::InInteractionArray, physics::{animator::{PlayerAnimationNode, PlayerAnimations, PlayerAnimatorPlugin}, constants::*}, tilemap::{light::LightEmitter, plugin::{LadderCollider, PlayerSpawn, SpacewalkCollider}}, utils::{energy::{Energy, PowerState}, mouse::CursorPosition, spacial_audio::PlaySoundEvent}};
 use utils::MoveTowards;
 
 
@@ -31,6 +31,7 @@ impl Plugin for PlayerPlugin {
                 (update_controllers,
                 tick_controllers).chain(),
                 listen_events,
+                gravity_failure,
                 // update_spotlight
             ).run_if(in_state(GlobalAppState::InGame)))
             .add_plugins(
@@ -198,10 +199,20 @@ impl Default for PlayerConstants {
 pub fn spawn_player(
     mut cmd: Commands,
     assets: Res<PlayerAssetCollection>,
+    spawn_points: Query<Entity, With<PlayerSpawn>>,
+    transforms: TransformHelper,
 ){
+    // the map may have spawned this very frame, before its global transforms were propagated
+    let spawn = spawn_points.iter().next()
+        .and_then(|e| transforms.compute_global_transform(e).ok())
+        .map(|t| t.translation().truncate())
+        .unwrap_or_else(|| {
+            warn!("The map has no PlayerSpawn, the player spawns above the origin");
+            vec2(0.0, 100.0)
+        });
     cmd.spawn((
         (
-            Transform::from_xyz(0.0, 100.0, 0.0),
+            Transform::from_translation(spawn.extend(0.0)),
             Player::default(),
             // ActiveHooks::MODIFY_SOLVER_CONTACTS,
             Name::new("Player"),
@@ -215,6 +226,7 @@ pub fn spawn_player(
             Ccd::enabled(),
             Visibility::default(),
             InheritedVisibility::default(),
+            RunEntity,
         ),
         CollisionGroups::new(
         Group::from_bits(PLAYER_CG).unwrap(),
@@ -276,7 +288,9 @@ pub fn spawn_player(
 #[derive(Component, Clone, Debug)]
 pub struct Player {
     pub state: PlayerState,
-    pub exiting_spacewalk: bool
+    pub exiting_spacewalk: bool,
+    /// spacewalking inside the station because gravity is unpowered
+    pub adrift: bool,
 }
 
 impl Player {
@@ -337,28 +351,13 @@ pub fn listen_events(
                 if !i {
                     if p.is_spacewalking() {
                         if pt.translation().x < t.translation().x {
-                            p.state = PlayerState::Regular { accumulated_vel: 0.0 };
-                            consts.gravity = PlayerConstants::default().gravity;
-                            p.exiting_spacewalk = true;
-                            cmd.entity(e).insert(
-                                CollisionGroups{
-                                    memberships: Group::from_bits(PLAYER_CG).unwrap(),
-                                    filters: Group::from_bits(PLAYER_DEFAULT_CG).unwrap(),
-                                }
-                            );
+                            exit_spacewalk(&mut cmd, e, &mut p, &mut consts);
+                        } else {
+                            // drifted out of the station, gravity coming back won't matter now
+                            p.adrift = false;
                         }
                     } else if pt.translation().x > t.translation().x {
-                        p.state = PlayerState::Spacewalk;
-                        consts.gravity = Vec2::ZERO;
-                        p.exiting_spacewalk = false;
-                        player_vel.angvel = 0.0;
-                        cmd.entity(e).insert(LockedAxes::ROTATION_LOCKED_X | LockedAxes::ROTATION_LOCKED_X);
-                        cmd.entity(e).insert(
-                            CollisionGroups{
-                                memberships: Group::from_bits(PLAYER_CG).unwrap(),
-                                filters: Group::from_bits(PLAYER_DEFAULT_CG & !PLATFORMS_CG).unwrap(),
-                            }
-                        );
+                        enter_spacewalk(&mut cmd, e, &mut p, &mut consts, &mut player_vel);
                     }
                 }
             }
@@ -366,6 +365,78 @@ pub fn listen_events(
     };
 }
 
+pub fn enter_spacewalk(
+    cmd: &mut Commands,
+    e: Entity,
+    p: &mut Player,
+    consts: &mut PlayerConstants,
+    player_vel: &mut Velocity,
+) {
+    p.state = PlayerState::Spacewalk;
+    consts.gravity = Vec2::ZERO;
+    p.exiting_spacewalk = false;
+    player_vel.angvel = 0.0;
+    cmd.entity(e).insert(LockedAxes::ROTATION_LOCKED_X | LockedAxes::ROTATION_LOCKED_X);
+    cmd.entity(e).insert(
+        CollisionGroups{
+            memberships: Group::from_bits(PLAYER_CG).unwrap(),
+            filters: Group::from_bits(PLAYER_DEFAULT_CG & !PLATFORMS_CG).unwrap(),
+        }
+    );
+}
+
+pub fn exit_spacewalk(
+    cmd: &mut Commands,
+    e: Entity,
+    p: &mut Player,
+    consts: &mut PlayerConstants,
+) {
+    p.state = PlayerState::Regular { accumulated_vel: 0.0 };
+    consts.gravity = PlayerConstants::default().gravity;
+    p.exiting_spacewalk = true;
+    cmd.entity(e).insert(
+        CollisionGroups{
+            memberships: Group::from_bits(PLAYER_CG).unwrap(),
+            filters: Group::from_bits(PLAYER_DEFAULT_CG).unwrap(),
+        }
+    );
+}
+
+const GRAVITY_FADE_SPEED: f32 = 150.0;
+
+/// Station gravity runs off the power grid: it fades out when unpowered
+/// and the player drifts as if spacewalking until it comes back.
+pub fn gravity_failure(
+    mut cmd: Commands,
+    power: Res<PowerState>,
+    energy: Res<Energy>,
+    time: Res<Time>,
+    player: Single<(Entity, &mut Player, &mut Velocity)>,
+    mut consts: ResMut<PlayerConstants>,
+) {
+    let (e, mut p, mut vel) = player.into_inner();
+    let default_gravity = PlayerConstants::default().gravity;
+    if power.gravity {
+        if p.adrift && p.is_spacewalking() {
+            exit_spacewalk(&mut cmd, e, &mut p, &mut consts);
+        }
+        p.adrift = false;
+        if !p.is_spacewalking() {
+            // partially routed generators give partial gravity
+            let target = default_gravity.y * energy.routing.gravity;
+            consts.gravity.y = consts.gravity.y.move_towards(target, GRAVITY_FADE_SPEED * time.dt());
+        }
+        return;
+    }
+    if p.is_spacewalking() {
+        return;
+    }
+    consts.gravity.y = consts.gravity.y.move_towards(0.0, GRAVITY_FADE_SPEED * time.dt());
+    if consts.gravity.y == 0.0 && (p.is_regular() || p.is_dancing()) {
+        enter_spacewalk(&mut cmd, e, &mut p, &mut consts, &mut vel);
+        p.adrift = true;
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct Ladder {
@@ -392,7 +463,8 @@ impl Default for Player {
     fn default() -> Self {
         Self {
             state: PlayerState::Regular{accumulated_vel: 0.0},
-            exiting_spacewalk: false
+            exiting_spacewalk: false,
+            adrift: false,
         }
     }
 }
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, f32::consts::PI};
+use std::f32::consts::PI;
 
 use bevy::{prelude::*, render::{camera::RenderTarget, render_resource::{Extent3d, ShaderType, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages}, view::{NoFrustumCulling, RenderLayers, VisibilitySystems}}};
 
@@ -12,7 +12,6 @@ use bevy::{
     render::render_resource::{AsBindGroup, ShaderRef},
     sprite::{AlphaMode2d, Material2d, Material2dPlugin},
 };
-use tiled::PropertyValue;
 
 use crate::utils::{background::ParalaxLayer, noise::get_noise_3d};
 
@@ -44,21 +43,44 @@ pub struct LightOccluderLayer;
 #[derive(Component)]
 pub struct Unshaded;
 
-impl LightEmitter {
-    pub fn from_properties(properties: &HashMap<String, PropertyValue>) -> Option<Self> {
-        let Some(PropertyValue::FloatValue(radius_px)) = properties.get("radius") else {return None};
-        let Some(PropertyValue::FloatValue(spot)) = properties.get("angle") else {return None};
-        let Some(PropertyValue::FloatValue(rotation)) = properties.get("rotation") else {return None};
-        let Some(PropertyValue::FloatValue(intensity)) = properties.get("intensity") else {return None};
-        let Some(PropertyValue::ColorValue(color)) = properties.get("color") else {return None};
-
-        Some(Self{
-            radius_px: *radius_px,
-            spot: *spot,
-            color_and_rotation: vec4(color.red as f32 / 255., color.green as f32 / 255., color.blue as f32 / 255., *rotation),
-            intensity: *intensity,
-        })
+/// Light authored on a Tiled object, spawns a [`LightEmitter`] under it
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component, Default)]
+pub struct LightSource {
+    pub radius: f32,
+    /// spot angle, degrees
+    pub angle: f32,
+    /// degrees
+    pub rotation: f32,
+    pub intensity: f32,
+    pub color: Color,
+}
+
+impl Default for LightSource {
+    fn default() -> Self {
+        Self {
+            radius: 100.,
+            angle: 0.,
+            rotation: 0.,
+            intensity: 1.,
+            color: Color::WHITE,
+        }
     }
+}
+
+impl From<&LightSource> for LightEmitter {
+    fn from(source: &LightSource) -> Self {
+        let color = source.color.to_srgba();
+        Self {
+            radius_px: source.radius,
+            spot: source.angle,
+            color_and_rotation: vec4(color.red, color.green, color.blue, source.rotation),
+            intensity: source.intensity,
+        }
+    }
+}
+
+impl LightEmitter {
     fn to_emitter(&self, relative_to_cam: Vec2) -> RelativeLightEmitter {
         RelativeLightEmitter {
             camera_relative_position: relative_to_cam,
@@ -210,7 +232,7 @@ const LIGHT_RESOLUTION : f32 = 1.0;
 
 
 // ASLO PASS IN SHADER!
-const MAX_EMITTERS: usize = 64;
+pub const MAX_EMITTERS: usize = 64;
 
 #[derive(Component)]
 struct CompositorCamera;
@@ -2,9 +2,9 @@ use bevy::{asset::LoadState, audio::{PlaybackMode, Volume}, color::palettes::css
 use bevy_ecs_tiled::prelude::*;
 use bevy_ecs_tilemap::TilemapPlugin;
 use bevy_rapier2d::prelude::{ActiveEvents, CoefficientCombineRule, Collider, CollisionGroups, Friction, Group, Sensor};
-use tiled::{ObjectShape, PropertyValue};
+use tiled::ObjectShape;
 
-use crate::{core::states::{GlobalAppState, OnGame, PreGameTasks}, interactions::components::{InInteraction, Interactable, InteractableMaterial, InteractionTypes}, physics::constants::{INTERACTABLE_CG, LADDERS_CG, PLATFORMS_CG, PLAYER_CG, PLAYER_SENSOR_CG, STRUCTURES_CG}, tilemap::light::LightEmitter, utils::{custom_material_loader::SpriteAssets, debree::{Malfunction, MalfunctionType}, spacial_audio::{AlarmSpeaker, SoundAssets}}};
+use crate::{core::states::{GlobalAppState, OnGame, PreGameTasks}, interactions::{components::{InInteraction, Interactable, InteractableId, InteractableMaterial, InteractableObject, InteractionTypes}, insert_puzzle_state}, physics::{constants::{INTERACTABLE_CG, LADDERS_CG, PLATFORMS_CG, PLAYER_CG, PLAYER_SENSOR_CG, STRUCTURES_CG}, platforms::{MovingPlatform, PlatformPath}}, tilemap::light::{LightEmitter, LightSource}, utils::{custom_material_loader::SpriteAssets, debree::{Malfunction, MalfunctionType}, spacial_audio::{AlarmSpeaker, Ambience, SoundAssets}}};
 
 
 pub struct MapPlugin;
@@ -23,36 +23,42 @@ impl Plugin for MapPlugin {
         #[cfg(target_arch = "wasm32")]
         let path = None;
 
+        // `--tiled-project assets/tilemaps/v2.0/project.tiled-project` syncs the designers' project
+        #[cfg(not(target_arch = "wasm32"))]
+        let project = crate::utils::args::launch_arg("tiled-project").map(std::path::PathBuf::from);
+        #[cfg(target_arch = "wasm32")]
+        let project = None;
+
         app
             .add_plugins((
                 TilemapPlugin,
-                TiledMapPlugin(TiledMapPluginConfig { tiled_types_export_file: path}),
+                TiledMapPlugin(TiledMapPluginConfig { tiled_types_export_file: path, tiled_project_file: project }),
                 TiledPhysicsPlugin::<CustomRapierPhysicsBackend>::default(),
             ))
-            .insert_resource(Aboba::default())
-            .register_type::<MapObject>()
-            .register_type::<LightEmitter>()
+            // classes authored on map objects
+            .register_type::<LightSource>()
+            .register_type::<InteractableObject>()
+            .register_type::<SpacewalkZone>()
+            .register_type::<AlarmSpeaker>()
+            .register_type::<Ambience>()
+            .register_type::<PlatformPath>()
+            .register_type::<PlayerSpawn>()
             .add_systems(Startup, spawn_map)
-            .add_systems(Update, ((handle_layer_spawn, hihihaha), handle_object_spawn.run_if(in_state(GlobalAppState::InGame))))
+            .add_systems(Update, (handle_layer_spawn, spawn_lights, spawn_platform_paths))
+            .add_systems(Update, (
+                spawn_ambience,
+                handle_object_colliders,
+            ).run_if(in_state(GlobalAppState::InGame)))
             // .add_observer(handle_layer_spawn)
             .add_systems(Update, (
-                check_map, event_map_created, 
+                check_map,
             ).run_if(in_state(GlobalAppState::AssetLoading)))
+            // maps are respawned on restart too
+            .add_systems(Update, event_map_created)
         ;
     }
 }
 
-#[derive(Component, Debug, Default, Reflect)]
-#[reflect(Component, Default)]
-enum MapObject {
-    #[default]
-    Ladder,
-    Interactable,
-    PlayerSpawn,
-    Light,
-    Platform
-}
-
 #[derive(Resource)]
 pub struct MapAssets {
     map: Handle<TiledMap>
@@ -65,16 +71,36 @@ pub fn spawn_map(
 ){
     let map = assets.load("tilemaps/v2.0/main.tmx");
     cmd.insert_resource(MapAssets{map: map.clone()});
-    cmd.spawn((
+    cmd.spawn(map_bundle(map));
+    tasks.add("map_loading".to_string());
+    tasks.add("map_spawn".to_string());
+}
+
+fn map_bundle(map: Handle<TiledMap>) -> impl Bundle {
+    (
         TiledMapHandle(map),
         TilemapAnchor::Center,
         TiledPhysicsSettings::<CustomRapierPhysicsBackend> {
                     objects_layer_filter: TiledName::All,
+                    // far fewer colliders, and fewer seams for the player to snag on
+                    tiles_colliders: TiledTilesColliders::MergedRectangles,
                     ..default()
         }
-    ));
-    tasks.add("map_loading".to_string());
-    tasks.add("map_spawn".to_string());
+    )
+}
+
+/// Spawns the already loaded map again, dropping everything that was spawned from it.
+pub fn respawn_map(world: &mut World) {
+    let maps: Vec<(Entity, Handle<TiledMap>)> = world
+        .query::<(Entity, &TiledMapHandle)>()
+        .iter(world)
+        .map(|(e, h)| (e, h.0.clone()))
+        .collect();
+    for (e, map) in maps {
+        world.entity_mut(e).despawn();
+        world.spawn(map_bundle(map));
+    }
+    world.resource_mut::<PreGameTasks>().add("map_spawn".to_string());
 }
 
 #[derive(Component)]
@@ -83,178 +109,136 @@ pub struct LadderCollider;
 #[derive(Component)]
 pub struct SpacewalkCollider;
 
-#[derive(Resource, Default)]
-pub struct Aboba {
-    pub data: Vec<TiledObjectCreated>,
+/// Authored on a Tiled object, the player starts there
+#[derive(Component, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct PlayerSpawn;
+
+/// Authored on Tiled objects, the object's colliders become spacewalk sensors
+#[derive(Component, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct SpacewalkZone;
+
+fn spawn_lights(
+    mut cmd: Commands,
+    sources: Query<(Entity, &LightSource), Added<LightSource>>,
+) {
+    for (e, source) in sources.iter() {
+        cmd.entity(e).with_child((LightEmitter::from(source), GlobalTransform::IDENTITY, Transform::default()));
+    }
 }
 
-fn hihihaha(
-    mut e: EventReader<TiledObjectCreated>,
-    mut aboba: ResMut<Aboba>,
+fn spawn_ambience(
+    mut cmd: Commands,
+    ambience: Query<(Entity, &Ambience), Added<Ambience>>,
+    sound_assets: Res<SoundAssets>,
 ) {
-    for e in e.read() {
-        aboba.data.push(e.clone());
+    for (e, ambience) in ambience.iter() {
+        cmd.entity(e).with_child(
+            (
+                AudioPlayer::new(ambience.sound(&sound_assets)),
+                PlaybackSettings {
+                    mode: PlaybackMode::Loop,
+                    volume: Volume::Linear(1.),
+                    speed: 1.0,
+                    paused: false,
+                    muted: false,
+                    spatial: true,
+                    spatial_scale: None,
+                },
+                Name::new(format!("{:?}", ambience)),
+                Transform::default(),
+            )
+        );
     }
 }
 
-fn handle_object_spawn(
+fn spawn_platform_paths(
     mut cmd: Commands,
-    q_c: Query<
-        (Entity, &Children)
-    >,
-    map_asset: Res<Assets<TiledMap>>,
+    paths: Query<(Entity, &PlatformPath, &Transform, &Name), Added<PlatformPath>>,
+) {
+    for (e, path, transform, name) in paths.iter() {
+        if path.positions.len() < 2 {
+            error!("{}: a platform path needs at least two positions, got {}", name, path.positions.len());
+            continue;
+        }
+        let origin = transform.translation.truncate();
+        let positions = path.positions.iter().map(|p| origin + Vec2::new(p.x, -p.y)).collect();
+        cmd.entity(e).insert(MovingPlatform::bundle(positions, path.speed, path.mode));
+    }
+}
+
+/// Colliders are spawned under their object, after its properties are inserted
+fn handle_object_colliders(
+    mut cmd: Commands,
+    colliders: Query<(Entity, &ChildOf), Added<TiledColliderMarker>>,
+    spacewalk_zones: Query<(), With<SpacewalkZone>>,
+    interactables: Query<&InteractableObject>,
+    spawned_interactables: Query<(), With<Interactable>>,
+    children: Query<&Children>,
+    storages: Query<&TiledMapStorage>,
     image_assets: Res<Assets<Image>>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut interactable_materials: ResMut<Assets<InteractableMaterial>>,
     sprite_assets: Res<SpriteAssets>,
-    // FUCKING FUCK YARO
-    mut aboba: ResMut<Aboba>,
-    sound_assets: Res<SoundAssets>,
 ) {
-    for e in aboba.data.iter() {
-        let Some(object) = e.get_object(&map_asset) else {continue;};
-
-        if let Some(l) = LightEmitter::from_properties(&object.properties) {
-            cmd.entity(e.entity).with_child((l, GlobalTransform::IDENTITY, Transform::default()));
+    let mut handled = vec![];
+    for (c, child_of) in colliders.iter() {
+        let object = child_of.parent();
+        if spacewalk_zones.contains(object) {
+            cmd.entity(c).insert((Sensor, SpacewalkCollider));
         }
-        if let Some(PropertyValue::BoolValue(true)) = object.properties.get("spacewalk") {
-            let Ok((_e, object_children_with_collider)) = q_c.get(e.entity) else {return;};
-            for c in object_children_with_collider {
-                cmd.entity(*c).insert((Sensor,SpacewalkCollider));
-            }
+        let Ok(interactable) = interactables.get(object) else {continue;};
+        // an object with several shapes still is a single interactable
+        if handled.contains(&object) || children.iter_descendants(object).any(|e| spawned_interactables.contains(e)) {
+            continue;
         }
-        if let Some(PropertyValue::BoolValue(true)) = object.properties.get("speaker") {
-            cmd.entity(e.entity).with_child(
-                (
-                AlarmSpeaker,
-                Name::new("AlarmSpeaker"),
-                Transform::default(),
-                )
-            );
-        }
-        if let Some(PropertyValue::StringValue(t)) = object.properties.get("ambience") {
-            let handle;
-            let name;
-            match t.as_str() {
-                "EngineAmbience" => {
-                    handle = sound_assets.engine_ambience.clone();
-                    name = t.clone();
-                }
-                "IndustrialAmbience" => {
-                    handle = sound_assets.industrial_ambience.clone();
-                    name = t.clone();
-                }
-                "VentAmbience" => {
-                    handle = sound_assets.vent_ambience.clone();
-                    name = t.clone();
-                }
-                _ => {unreachable!()}
-            }
-            cmd.entity(e.entity).with_child(
-                (
-                    AudioPlayer::new(handle),
-                    PlaybackSettings {
-                        mode: PlaybackMode::Loop,
-                        volume: Volume::Linear(1.),
-                        speed: 1.0,
-                        paused: false,
-                        muted: false,
-                        spatial: true,
-                        spatial_scale: None,
-                    },
-                    Name::new(name),
-                    Transform::default(),
-                )
-            );
-        }
-        if let Some(interaction) = InteractionTypes::from_properties(&object.properties){
-            let Ok((_e, object_children_with_collider)) = q_c.get(e.entity) else {return;};
-            for c in object_children_with_collider {
-                let handle;
-                match interaction {
-                    InteractionTypes::ChainReactionDisplay => {
-                        handle = sprite_assets.chain_interactable.clone();
-                    },
-                    InteractionTypes::WaveModulator => {
-                        handle = sprite_assets.wave_interactable.clone();
-                    },
-                    InteractionTypes::PipePuzzle => {
-                        handle = sprite_assets.pipe_interactable.clone();
-                    },
-                    InteractionTypes::CollisionMinigame => {
-                        handle = sprite_assets.collision_interactable.clone();
-                    },
-                    InteractionTypes::WarningInterface => {
-                        handle = sprite_assets.warning_interactable.clone();
-                    },
-                    InteractionTypes::HackMinigame => {
-                        handle = sprite_assets.hack.clone();
-                    },
-                    InteractionTypes::WiresMinigame => {
-                        handle = sprite_assets.wires.clone();
-                    },
-                }
-                let image = image_assets.get(&handle).unwrap();
-                let width = image.width();
-                let height = image.height();
-                let material = InteractableMaterial {
-                    time: 0.,
-                    sprite_handle: handle.clone(),
-                    _webgl2_padding_8b: 0,
-                    _webgl2_padding_12b: 0,
-                    _webgl2_padding_16b: 0,
-                };
-                let interactable_material_handle = interactable_materials.add(material);
-                cmd.entity(*c).insert((
-                    Mesh2d(meshes.add(Rectangle::new(width as f32, height as f32))),
-                    MeshMaterial2d(interactable_material_handle.clone()),
-                    Name::new("Interactable"),
-                    Interactable,
-                    Collider::cuboid(width as f32 / 4., height as f32 / 4.),
-                    // ActiveCollisionTypes::KINEMATIC_STATIC | ActiveCollisionTypes::KINEMATIC_KINEMATIC,
-                    CollisionGroups::new(
-                        Group::from_bits(INTERACTABLE_CG).unwrap(),
-                        Group::from_bits(PLAYER_SENSOR_CG).unwrap(),
-                    ),
-                    ActiveEvents::COLLISION_EVENTS,
-                    Sensor,
-                    InInteraction {data: false},
-                    interaction.clone(),
-                ));
-                break
-            }
-            
+        handled.push(object);
+        let handle = match interactable.kind {
+            InteractionTypes::ChainReactionDisplay => sprite_assets.chain_interactable.clone(),
+            InteractionTypes::WaveModulator => sprite_assets.wave_interactable.clone(),
+            InteractionTypes::PipePuzzle => sprite_assets.pipe_interactable.clone(),
+            InteractionTypes::CollisionMinigame => sprite_assets.collision_interactable.clone(),
+            InteractionTypes::WarningInterface => sprite_assets.warning_interactable.clone(),
+            InteractionTypes::HackMinigame => sprite_assets.hack.clone(),
+            InteractionTypes::WiresMinigame => sprite_assets.wires.clone(),
+            // shares the console sprite with the warning interface
+            InteractionTypes::PowerRouting => sprite_assets.warning_interactable.clone(),
+        };
+        let image = image_assets.get(&handle).unwrap();
+        let width = image.width();
+        let height = image.height();
+        let material = InteractableMaterial {
+            time: 0.,
+            sprite_handle: handle.clone(),
+            _webgl2_padding_8b: 0,
+            _webgl2_padding_12b: 0,
+            _webgl2_padding_16b: 0,
+        };
+        let interactable_material_handle = interactable_materials.add(material);
+        cmd.entity(c).insert((
+            Mesh2d(meshes.add(Rectangle::new(width as f32, height as f32))),
+            MeshMaterial2d(interactable_material_handle.clone()),
+            Name::new("Interactable"),
+            Interactable,
+            Collider::cuboid(width as f32 / 4., height as f32 / 4.),
+            // ActiveCollisionTypes::KINEMATIC_STATIC | ActiveCollisionTypes::KINEMATIC_KINEMATIC,
+            CollisionGroups::new(
+                Group::from_bits(INTERACTABLE_CG).unwrap(),
+                Group::from_bits(PLAYER_SENSOR_CG).unwrap(),
+            ),
+            ActiveEvents::COLLISION_EVENTS,
+            Sensor,
+            InInteraction {data: false},
+            interactable.kind.clone(),
+        ));
+        // malfunctions are raised on the interactable by its Tiled object id
+        match storages.iter().find_map(|s| s.objects.iter().find(|(_, e)| **e == object).map(|(id, _)| *id)) {
+            Some(id) => {cmd.entity(c).insert(InteractableId(id));}
+            None => warn!("Interactable {:?} isn't in a map's storage, it will never break", object),
         }
-        // if let Some(m) = MalfunctionType::from_properties(&object.properties) {
-        //     info!("MALF: {:?}", object.properties);
-        //     let Ok((_e, object_children_with_collider)) = q_c.get(e.entity) else {return;};
-        //     info!("MALF CHILD");
-        //     /*
-        //     EXISTS:
-        //         WARNING
-        //         ANTENNA
-        //         ENGINE
-        //         REACTOR
-        //         HACK
-        //         WAVE
-        //     */
-        //     info!("INSERTING SENSOR");
-        //     for c in object_children_with_collider {
-        //         cmd.entity(*c).insert((
-        //             // LIKE THAT:
-        //             Name::new("INSERTED"),
-        //             Sensor,
-        //             // LadderCollider,
-        //             // ActiveEvents::COLLISION_EVENTS,
-        //             // CollisionGroups{
-        //             //     memberships: Group::from_bits(LADDERS_CG).unwrap(),
-        //             //     filters: Group::from_bits(PLAYER_CG).unwrap(),
-        //             // }
-        //         ));
-        //     }
-        // }
+        insert_puzzle_state(&mut cmd.entity(c), &interactable.kind);
     }
-    aboba.data  = vec![];
 }
 
 
@@ -355,10 +339,11 @@ impl TiledPhysicsBackend for CustomRapierPhysicsBackend {
         filter: &TiledNameFilter,
         collider: &TiledCollider,
         anchor: &TilemapAnchor,
+        tiles_colliders: TiledTilesColliders,
     ) -> Vec<TiledColliderSpawnInfos> {
         let colliders = self
             .0
-            .spawn_colliders(commands, tiled_map, filter, collider, anchor);
+            .spawn_colliders(commands, tiled_map, filter, collider, anchor, tiles_colliders);
         for c in colliders.iter() {
             commands.entity(c.entity).insert(
                 CollisionGroups{
@@ -0,0 +1,322 @@
+// Offline map checks: `--validate-map <path.tmx>` loads the map with plain `tiled`
+// (no window, no assets) and reports authoring mistakes that would otherwise only show up in game.
+//
+//   - exactly one player spawn
+//   - at least one interactable for every interaction that resolves a malfunction
+//   - every light has all of its properties set
+//   - every ladder has an area to climb in
+//   - no view of the station has more lights than the shader can draw
+
+use std::{collections::HashSet, fmt, path::Path};
+
+use bevy::reflect::TypePath;
+use pixel_utils::camera::{TARGET_HEIGHT, TARGET_WIDTH};
+use tiled::{LayerType, Map, Object, ObjectShape, PropertyValue};
+
+use crate::{interactions::components::{InteractableObject, InteractionTypes}, tilemap::{light::{LightSource, MAX_EMITTERS}, plugin::PlayerSpawn}, utils::malfunction_defs::{MalfunctionDefinitions, DEFINITIONS_PATH}};
+
+/// Relative to the crate root, checked by the tests
+#[cfg(test)]
+pub const MAP_PATH: &str = "assets/tilemaps/v2.0/main.tmx";
+
+const LADDERS_LAYER: &str = "LADDERS";
+/// Step the view is slid by when looking for the busiest one
+const VIEW_STEP: f32 = 16.;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Violation {
+    pub layer: String,
+    /// `None` for problems with the map as a whole
+    pub object: Option<u32>,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.object {
+            Some(id) => write!(f, "layer `{}`, object {}: {}", self.layer, id, self.message),
+            None => write!(f, "layer `{}`: {}", self.layer, self.message),
+        }
+    }
+}
+
+/// Object with the layer it sits in, positions already include the layer offsets
+struct PlacedObject<'map> {
+    layer: String,
+    object: Object<'map>,
+    x: f32,
+    y: f32,
+}
+
+impl PlacedObject<'_> {
+    fn violation(&self, message: impl Into<String>) -> Violation {
+        Violation { layer: self.layer.clone(), object: Some(self.object.id()), message: message.into() }
+    }
+
+    /// Class property of the given type, whatever the property is named
+    fn class(&self, type_path: &str) -> Option<&tiled::Properties> {
+        self.object.properties.values().find_map(|v| match v {
+            PropertyValue::ClassValue { property_type, properties } if property_type == type_path => Some(properties),
+            _ => None,
+        })
+    }
+}
+
+fn collect_objects<'map>(layers: impl Iterator<Item = tiled::Layer<'map>>, offset: (f32, f32), out: &mut Vec<PlacedObject<'map>>) {
+    for layer in layers {
+        let offset = (offset.0 + layer.offset_x, offset.1 + layer.offset_y);
+        let name = layer.name.clone();
+        match layer.layer_type() {
+            LayerType::Objects(objects) => {
+                for object in objects.objects() {
+                    out.push(PlacedObject { layer: name.clone(), x: object.x + offset.0, y: object.y + offset.1, object });
+                }
+            }
+            LayerType::Group(group) => collect_objects(group.layers(), offset, out),
+            _ => {}
+        }
+    }
+}
+
+pub fn validate_map(map: &Map, defs: &MalfunctionDefinitions) -> Vec<Violation> {
+    let mut objects = vec![];
+    collect_objects(map.layers(), (0., 0.), &mut objects);
+    let mut violations = vec![];
+
+    let spawns: Vec<&PlacedObject> = objects.iter().filter(|o| o.class(PlayerSpawn::type_path()).is_some()).collect();
+    if spawns.is_empty() {
+        violations.push(Violation { layer: "*".to_string(), object: None, message: "no player spawn".to_string() });
+    }
+    for extra in spawns.iter().skip(1) {
+        violations.push(extra.violation(format!("another player spawn, object {} is already one", spawns[0].object.id())));
+    }
+
+    let mut interactables: HashSet<InteractionTypes> = HashSet::new();
+    for object in objects.iter() {
+        let Some(props) = object.class(InteractableObject::type_path()) else {
+            continue;
+        };
+        let kind = match props.get("kind") {
+            None => InteractionTypes::default(),
+            Some(PropertyValue::StringValue(kind)) => match ron::de::from_str::<InteractionTypes>(kind) {
+                Ok(kind) => kind,
+                Err(_) => {
+                    violations.push(object.violation(format!("unknown interaction kind `{}`", kind)));
+                    continue;
+                }
+            },
+            Some(other) => {
+                violations.push(object.violation(format!("interaction kind should be a string, got {:?}", other)));
+                continue;
+            }
+        };
+        interactables.insert(kind);
+    }
+    for def in defs.malfunctions.iter() {
+        if !interactables.contains(&def.resolved_by) {
+            violations.push(Violation {
+                layer: "*".to_string(),
+                object: None,
+                message: format!("no {:?} interactable, {:?} can't be resolved", def.resolved_by, def.kind),
+            });
+        }
+    }
+
+    // circles as (x, y, radius), only lights that would actually be spawned
+    let mut lights = vec![];
+    for object in objects.iter() {
+        let Some(props) = object.class(LightSource::type_path()) else {
+            continue;
+        };
+        let mut complete = true;
+        for field in ["radius", "angle", "rotation", "intensity"] {
+            match props.get(field) {
+                Some(PropertyValue::FloatValue(_)) => {}
+                Some(other) => {
+                    violations.push(object.violation(format!("light `{}` should be a float, got {:?}", field, other)));
+                    complete = false;
+                }
+                None => {
+                    violations.push(object.violation(format!("light is missing `{}`", field)));
+                    complete = false;
+                }
+            }
+        }
+        match props.get("color") {
+            Some(PropertyValue::ColorValue(_)) => {}
+            Some(other) => {
+                violations.push(object.violation(format!("light `color` should be a color, got {:?}", other)));
+                complete = false;
+            }
+            None => {
+                violations.push(object.violation("light is missing `color`"));
+                complete = false;
+            }
+        }
+        if let (true, Some(PropertyValue::FloatValue(radius))) = (complete, props.get("radius")) {
+            lights.push((object.x, object.y, *radius));
+        }
+    }
+
+    let ladders: Vec<&PlacedObject> = objects.iter().filter(|o| o.layer == LADDERS_LAYER).collect();
+    if !map.layers().any(|l| l.name == LADDERS_LAYER) {
+        violations.push(Violation { layer: LADDERS_LAYER.to_string(), object: None, message: "layer is missing".to_string() });
+    }
+    for ladder in ladders {
+        let has_area = match &ladder.object.shape {
+            ObjectShape::Rect { width, height } | ObjectShape::Ellipse { width, height } => *width > 0. && *height > 0.,
+            ObjectShape::Polygon { points } => points.len() >= 3,
+            _ => false,
+        };
+        if !has_area {
+            violations.push(ladder.violation("ladder has no collider, it needs a rectangle, ellipse or polygon with an area"));
+        }
+    }
+
+    if let Some((count, x, y)) = busiest_view(&lights) {
+        if count > MAX_EMITTERS {
+            violations.push(Violation {
+                layer: "*".to_string(),
+                object: None,
+                message: format!("{} lights in the view at ({}, {}), only {} are drawn", count, x, y, MAX_EMITTERS),
+            });
+        }
+    }
+
+    violations
+}
+
+/// Most lights touching a single screen, with the top left corner of that screen
+fn busiest_view(lights: &[(f32, f32, f32)]) -> Option<(usize, f32, f32)> {
+    let (w, h) = (TARGET_WIDTH as f32, TARGET_HEIGHT as f32);
+    let min_x = lights.iter().map(|l| l.0 - l.2).reduce(f32::min)? - w;
+    let max_x = lights.iter().map(|l| l.0 + l.2).reduce(f32::max)?;
+    let min_y = lights.iter().map(|l| l.1 - l.2).reduce(f32::min)? - h;
+    let max_y = lights.iter().map(|l| l.1 + l.2).reduce(f32::max)?;
+
+    let mut best = (0, min_x, min_y);
+    let mut y = min_y;
+    while y <= max_y {
+        let mut x = min_x;
+        while x <= max_x {
+            let count = lights.iter().filter(|(lx, ly, r)| {
+                let dx = lx - lx.clamp(x, x + w);
+                let dy = ly - ly.clamp(y, y + h);
+                dx * dx + dy * dy <= r * r
+            }).count();
+            if count > best.0 {
+                best = (count, x, y);
+            }
+            x += VIEW_STEP;
+        }
+        y += VIEW_STEP;
+    }
+    Some(best)
+}
+
+/// Prints every violation, returns the process exit code
+pub fn run_validation(path: &str) -> i32 {
+    let defs_path = std::env::var("CARGO_MANIFEST_DIR")
+        .map(|dir| Path::new(&dir).join(DEFINITIONS_PATH))
+        .unwrap_or_else(|_| DEFINITIONS_PATH.into());
+    let defs = match MalfunctionDefinitions::read(&defs_path) {
+        Ok(d) => d,
+        Err(e) => {
+            eprintln!("Can't read {}: {}", defs_path.display(), e);
+            return 2;
+        }
+    };
+    let map = match tiled::Loader::new().load_tmx_map(path) {
+        Ok(m) => m,
+        Err(e) => {
+            eprintln!("Can't load {}: {}", path, e);
+            return 2;
+        }
+    };
+
+    let violations = validate_map(&map, &defs);
+    for v in violations.iter() {
+        println!("{}", v);
+    }
+    if violations.is_empty() {
+        println!("{}: ok", path);
+        0
+    } else {
+        println!("{}: {} problems", path, violations.len());
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn station_defs() -> MalfunctionDefinitions {
+        MalfunctionDefinitions::read(&Path::new(env!("CARGO_MANIFEST_DIR")).join(DEFINITIONS_PATH)).unwrap()
+    }
+
+    #[test]
+    fn station_map_is_valid() {
+        let map = tiled::Loader::new()
+            .load_tmx_map(Path::new(env!("CARGO_MANIFEST_DIR")).join(MAP_PATH))
+            .unwrap();
+        let violations = validate_map(&map, &station_defs());
+        assert!(violations.is_empty(), "{}", violations.iter().map(|v| v.to_string()).collect::<Vec<_>>().join("\n"));
+    }
+
+    #[test]
+    fn reports_broken_objects() {
+        const BROKEN: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.10" orientation="orthogonal" renderorder="right-down" width="10" height="10" tilewidth="16" tileheight="16" infinite="0" nextlayerid="3" nextobjectid="5">
+ <objectgroup id="1" name="LADDERS">
+  <object id="1" x="0" y="0"/>
+ </objectgroup>
+ <objectgroup id="2" name="LIGHTS">
+  <object id="2" x="0" y="0">
+   <properties>
+    <property name="light" type="class" propertytype="game::tilemap::light::LightSource">
+     <properties>
+      <property name="radius" type="float" value="50"/>
+     </properties>
+    </property>
+   </properties>
+   <point/>
+  </object>
+  <object id="3" x="0" y="0">
+   <properties>
+    <property name="interactable" type="class" propertytype="game::interactions::components::InteractableObject">
+     <properties>
+      <property name="kind" propertytype="game::interactions::components::InteractionTypes" value="HackMinigame"/>
+     </properties>
+    </property>
+   </properties>
+  </object>
+  <object id="4" x="0" y="0">
+   <properties>
+    <property name="interactable" type="class" propertytype="game::interactions::components::InteractableObject">
+     <properties>
+      <property name="kind" propertytype="game::interactions::components::InteractionTypes" value="HackMinigame"/>
+     </properties>
+    </property>
+   </properties>
+  </object>
+ </objectgroup>
+</map>
+"##;
+        let map = tiled::Loader::with_reader(|_: &Path| Ok::<_, std::io::Error>(Cursor::new(BROKEN.as_bytes())))
+            .load_tmx_map("broken.tmx")
+            .unwrap();
+        let violations = validate_map(&map, &station_defs());
+        let on = |id: u32| violations.iter().filter(|v| v.object == Some(id)).count();
+
+        assert!(violations.iter().any(|v| v.object.is_none() && v.message.contains("player spawn")));
+        assert_eq!(on(1), 1);
+        // four missing light properties
+        assert_eq!(on(2), 4);
+        // a second hack console is fine, each one breaks on its own
+        assert_eq!(on(3), 0);
+        assert_eq!(on(4), 0);
+    }
+}
@@ -0,0 +1,122 @@
+use bevy::prelude::*;
+use bevy_tailwind::tw;
+use debug_utils::debug_overlay::DebugOverlayRoot;
+
+use crate::{core::states::GlobalAppState, ui::{components::containers::base::{main_container_handle, sub_container_handle, ui_main_container, ui_sub_container}, menu::{menu_panel, MenuButton}, target::LowresUiContainer}, utils::{debree::DebreeLevel, difficulty::DifficultyProfile, score::{RunOutcome, RunScore}, spacial_audio::PlaySoundEvent}};
+
+pub struct EndScreenPlugin;
+
+impl Plugin for EndScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(OnEnter(GlobalAppState::Defeat), spawn_end_screen)
+        .add_systems(OnEnter(GlobalAppState::Victory), spawn_end_screen);
+    }
+}
+
+fn breakdown_line(text: String, asset_server: &Res<AssetServer>) -> impl Bundle {
+    (
+        Text::new(text),
+        TextFont {
+            font: asset_server.load("fonts/orp_regular.ttf"),
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(Color::srgb_u8(200, 200, 200)),
+    )
+}
+
+fn format_time(secs: f32) -> String {
+    let secs = secs.max(0.) as u32;
+    format!("{:02}:{:02}", secs / 60, secs % 60)
+}
+
+pub fn spawn_end_screen(
+    mut cmd: Commands,
+    asset_server: Res<AssetServer>,
+    ui: Single<Entity, With<LowresUiContainer>>,
+    c: Query<&Children, With<LowresUiContainer>>,
+    overlay: Query<(), With<DebugOverlayRoot>>,
+    score: Res<RunScore>,
+    outcome: Res<RunOutcome>,
+    debree_level: Res<DebreeLevel>,
+    profile: Res<DifficultyProfile>,
+    mut event_writer: EventWriter<PlaySoundEvent>,
+) {
+    if *outcome != RunOutcome::Survived {
+        event_writer.write(PlaySoundEvent::Boom);
+    }
+    for c in c {
+        for c in c {
+            if !overlay.contains(*c) {
+                cmd.entity(*c).despawn();
+            }
+        }
+    }
+    let main = main_container_handle(&asset_server);
+    let sub = sub_container_handle(&asset_server);
+
+    let survived = debree_level.elapsed;
+    let breakdown = score.breakdown(survived, *outcome);
+
+    let mut malfunction_lines = vec![];
+    for (kind, tally) in score.tallies.iter() {
+        malfunction_lines.push(cmd.spawn(breakdown_line(
+            format!("{:?}: {} resolved, {} failed", kind, tally.resolved, tally.failed), &asset_server
+        )).id());
+    }
+    if malfunction_lines.is_empty() {
+        malfunction_lines.push(cmd.spawn(breakdown_line("No malfunctions".to_string(), &asset_server)).id());
+    }
+
+    let mut score_lines = vec![
+        format!("Malfunctions {:+}", breakdown.malfunctions),
+        format!("Survived {} {:+}", format_time(survived), breakdown.time),
+        format!("Power kept up {:.0}% {:+}", score.efficiency(survived) * 100., breakdown.efficiency),
+    ];
+    if breakdown.victory > 0 {
+        score_lines.push(format!("Orbit held {:+}", breakdown.victory));
+    }
+    score_lines.push(format!("SCORE {}", breakdown.total));
+    let score_lines: Vec<Entity> = score_lines.into_iter()
+        .map(|l| cmd.spawn(breakdown_line(l, &asset_server)).id())
+        .collect();
+
+    let breakdown_panel = cmd.spawn(ui_main_container(&main, ())).insert(
+        tw!("flex flex-col")
+    ).with_children(|cmd| {
+        cmd.spawn(ui_sub_container(&sub, ()))
+        .with_children(|cmd| {
+            cmd.spawn(tw!("flex flex-col p-[5px] gap-[2px]"))
+            .add_children(&malfunction_lines);
+        });
+        cmd.spawn(ui_sub_container(&sub, ()))
+        .with_children(|cmd| {
+            cmd.spawn(tw!("flex flex-col p-[5px] gap-[2px]"))
+            .add_children(&score_lines);
+        });
+    }).id();
+
+    let title = if *outcome == RunOutcome::Survived {"ORBIT STABLE"} else {"RUN OVER"};
+    let menu = menu_panel(&mut cmd, &asset_server, title,
+        &[MenuButton::Restart, MenuButton::MainMenu], profile.difficulty);
+
+    cmd.entity(*ui).insert((
+        tw!("flex flex-col w-full h-full bg-black items-center content-center justify-center gap-[10px]"),
+        children![(
+            Text::new(outcome.title()),
+            TextFont {
+                font: asset_server.load("fonts/orp_regular.ttf"),
+                font_size: 24.0,
+                ..default()
+            },
+            TextColor(Color::srgb_u8(200, 200, 200)),
+            tw!("z-10"),
+        )]
+    ));
+    let row = cmd.spawn(tw!("flex items-start justify-center gap-[10px]"))
+        .add_child(breakdown_panel)
+        .add_child(menu)
+        .id();
+    cmd.entity(*ui).add_child(row);
+}
@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+use bevy_tailwind::tw;
+
+use crate::{ui::components::containers::base::{main_container_handle, sub_container_handle, ui_main_container, ui_sub_container}, utils::{difficulty::Difficulty, history::RunHistory, score::RunOutcome}};
+
+const LEADERBOARD_ROWS: usize = 8;
+
+fn leaderboard_line(text: String, asset_server: &Res<AssetServer>) -> impl Bundle {
+    (
+        Text::new(text),
+        TextFont {
+            font: asset_server.load("fonts/orp_regular.ttf"),
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(Color::srgb_u8(200, 200, 200)),
+    )
+}
+
+/// Best runs of the given difficulty, not attached to anything yet
+pub fn leaderboard_panel(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    history: &RunHistory,
+    difficulty: Difficulty,
+) -> Entity {
+    let main = main_container_handle(asset_server);
+    let sub = sub_container_handle(asset_server);
+
+    let mut lines = vec![];
+    for (place, run) in history.leaderboard(Some(difficulty), LEADERBOARD_ROWS).iter().enumerate() {
+        let secs = run.duration.max(0.) as u32;
+        let outcome = if run.outcome == RunOutcome::Survived {"HELD"} else {"LOST"};
+        lines.push(commands.spawn(leaderboard_line(
+            format!("{}. {:>6}  {:02}:{:02}  {}  0x{:x}", place + 1, run.score, secs / 60, secs % 60, outcome, run.seed),
+            asset_server,
+        )).id());
+    }
+    if lines.is_empty() {
+        lines.push(commands.spawn(leaderboard_line("No runs yet".to_string(), asset_server)).id());
+    }
+    let total = history.runs.iter().filter(|r| r.difficulty == difficulty).count();
+    let footer = commands.spawn(leaderboard_line(format!("{} runs played", total), asset_server)).id();
+
+    commands.spawn(ui_main_container(&main, ())).insert(
+        tw!("flex flex-col")
+    ).with_children(|cmd| {
+        cmd.spawn(ui_sub_container(&sub, ()))
+        .with_children(|cmd| {
+            cmd.spawn(tw!("flex flex-col p-[5px] gap-[2px]"))
+            .add_children(&lines);
+        });
+        cmd.spawn(ui_sub_container(&sub, ()))
+        .with_children(|cmd| {
+            cmd.spawn(tw!("flex flex-col p-[5px]"))
+            .add_child(footer);
+        });
+    }).id()
+}
@@ -0,0 +1,270 @@
+use bevy::prelude::*;
+use bevy_tailwind::tw;
+
+use crate::{core::{run::{reset_run, reset_run_state}, states::{GlobalAppState, PreGameTasks}}, ui::{components::containers::{base::{main_container_handle, sub_container_handle, ui_main_container, ui_sub_container}, text_display::{text_display_green_handle, ui_text_display_green_with_text}}, leaderboard::leaderboard_panel, target::LowresUiContainer}, utils::{difficulty::{Difficulty, DifficultyProfile}, history::RunHistory, save::PendingPlayerRestore, spacial_audio::PlaySoundEvent}};
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(MenuScreen::Main)
+        .add_systems(OnEnter(GlobalAppState::MainMenu), open_main_menu)
+        .add_systems(Update, switch_menu_screen.run_if(in_state(GlobalAppState::MainMenu).and(resource_changed::<MenuScreen>)))
+        .add_systems(OnExit(GlobalAppState::MainMenu), despawn_menu)
+        .add_systems(OnEnter(GlobalAppState::Paused), spawn_pause_menu)
+        .add_systems(OnExit(GlobalAppState::Paused), despawn_menu)
+        .add_systems(Update, (menu_buttons, autostart).run_if(
+            in_state(GlobalAppState::MainMenu)
+            .or(in_state(GlobalAppState::Paused))
+            .or(in_state(GlobalAppState::Defeat))
+            .or(in_state(GlobalAppState::Victory))
+        ));
+    }
+}
+
+#[derive(Component)]
+pub struct Menu;
+
+#[derive(Component, Clone, Copy, PartialEq)]
+pub enum MenuButton {
+    Continue,
+    NewRun,
+    Difficulty,
+    Resume,
+    Abandon,
+    Restart,
+    MainMenu,
+    Leaderboard,
+    Back,
+}
+
+/// What the main menu currently shows
+#[derive(Resource, Clone, Copy, PartialEq)]
+pub enum MenuScreen {
+    Main,
+    Leaderboard,
+}
+
+impl MenuButton {
+    fn label(&self, difficulty: Difficulty) -> String {
+        match self {
+            MenuButton::Continue => "CONTINUE".to_string(),
+            MenuButton::NewRun => "NEW RUN".to_string(),
+            MenuButton::Difficulty => format!("DIFFICULTY: {}", format!("{:?}", difficulty).to_uppercase()),
+            MenuButton::Resume => "RESUME".to_string(),
+            MenuButton::Abandon => "ABANDON RUN".to_string(),
+            MenuButton::Restart => "RESTART".to_string(),
+            MenuButton::MainMenu => "MAIN MENU".to_string(),
+            MenuButton::Leaderboard => "LEADERBOARD".to_string(),
+            MenuButton::Back => "BACK".to_string(),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct MenuButtonText(MenuButton);
+
+/// Restart goes through the main menu so the respawned map can settle, then starts on its own
+#[derive(Resource)]
+pub struct AutoStart;
+
+/// Titled column of buttons, not attached to anything yet
+pub fn menu_panel(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    title: &str,
+    buttons: &[MenuButton],
+    difficulty: Difficulty,
+) -> Entity {
+    let main = main_container_handle(asset_server);
+    let sub = sub_container_handle(asset_server);
+    let text_bundle = text_display_green_handle(asset_server);
+
+    let mut button_entities = vec![];
+    for button in buttons {
+        let label = button.label(difficulty);
+        button_entities.push(commands.spawn(
+            ui_main_container(&main, (Interaction::default(), *button))
+        ).with_children(|cmd| {
+            cmd.spawn(
+                ui_text_display_green_with_text(&text_bundle, (MenuButtonText(*button), MenuButtonText(*button)), &label, asset_server)
+            ).insert(tw!("w-[240px] items-center justify-center p-[5px]"));
+        }).id());
+    }
+
+    commands.spawn(ui_main_container(&main, ())).insert(
+        tw!("flex flex-col")
+    ).with_children(|cmd| {
+        cmd.spawn(ui_sub_container(&sub, ()))
+        .with_children(|cmd| {
+            cmd.spawn((
+                Text::new(title),
+                TextFont {
+                    font: asset_server.load("fonts/orp_regular.ttf"),
+                    font_size: 24.0,
+                    ..default()
+                },
+                TextColor(Color::srgb_u8(200, 200, 200)),
+                tw!("p-[5px]"),
+            ));
+        });
+        cmd.spawn(ui_sub_container(&sub, ()))
+        .with_children(|cmd| {
+            cmd.spawn(tw!("flex flex-col items-center justify-center w-full h-full gap-[1px]"))
+            .add_children(&button_entities);
+        });
+    }).id()
+}
+
+fn spawn_menu(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    lowres_container: Entity,
+    title: &str,
+    buttons: &[MenuButton],
+    difficulty: Difficulty,
+) {
+    let panel = menu_panel(commands, asset_server, title, buttons, difficulty);
+    let entity = commands.spawn((
+        tw!("absolute items-center justify-center w-full h-full z-20"),
+        Menu,
+    )).add_child(panel).id();
+    commands.entity(lowres_container).add_child(entity);
+}
+
+pub fn open_main_menu(
+    mut commands: Commands,
+) {
+    commands.insert_resource(MenuScreen::Main);
+}
+
+pub fn switch_menu_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    lowres_container: Single<Entity, With<LowresUiContainer>>,
+    menus: Query<Entity, With<Menu>>,
+    screen: Res<MenuScreen>,
+    profile: Res<DifficultyProfile>,
+    history: Res<RunHistory>,
+    restored: Option<Res<PendingPlayerRestore>>,
+) {
+    for e in menus {
+        commands.entity(e).despawn();
+    }
+    match *screen {
+        MenuScreen::Main => {
+            let buttons: &[MenuButton] = if restored.is_some() {
+                &[MenuButton::Continue, MenuButton::NewRun, MenuButton::Difficulty, MenuButton::Leaderboard]
+            } else {
+                &[MenuButton::NewRun, MenuButton::Difficulty, MenuButton::Leaderboard]
+            };
+            spawn_menu(&mut commands, &asset_server, *lowres_container, "ORBITAL STATION", buttons, profile.difficulty);
+        }
+        MenuScreen::Leaderboard => {
+            let board = leaderboard_panel(&mut commands, &asset_server, &history, profile.difficulty);
+            let back = menu_panel(&mut commands, &asset_server, "LEADERBOARD", &[MenuButton::Difficulty, MenuButton::Back], profile.difficulty);
+            let entity = commands.spawn((
+                tw!("absolute items-center justify-center w-full h-full z-20 gap-[10px]"),
+                Menu,
+            )).add_child(board).add_child(back).id();
+            commands.entity(*lowres_container).add_child(entity);
+        }
+    }
+}
+
+pub fn spawn_pause_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    lowres_container: Single<Entity, With<LowresUiContainer>>,
+    profile: Res<DifficultyProfile>,
+) {
+    spawn_menu(&mut commands, &asset_server, *lowres_container, "PAUSED",
+        &[MenuButton::Resume, MenuButton::Abandon], profile.difficulty);
+}
+
+pub fn despawn_menu(
+    mut commands: Commands,
+    menus: Query<Entity, With<Menu>>,
+) {
+    for e in menus {
+        commands.entity(e).despawn();
+    }
+}
+
+pub fn menu_buttons(
+    mut commands: Commands,
+    buttons: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
+    mut texts: Query<(&mut Text, &MenuButtonText)>,
+    mut next_state: ResMut<NextState<GlobalAppState>>,
+    mut profile: ResMut<DifficultyProfile>,
+    tasks: Res<PreGameTasks>,
+    screen: Res<MenuScreen>,
+    mut event_writer: EventWriter<PlaySoundEvent>,
+) {
+    for (interaction, button) in buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        event_writer.write(PlaySoundEvent::SubmitButtonPress);
+        match button {
+            MenuButton::Continue => {
+                if tasks.is_empty() {
+                    next_state.set(GlobalAppState::InGame);
+                }
+            }
+            MenuButton::NewRun => {
+                if tasks.is_empty() {
+                    commands.queue(reset_run_state);
+                    next_state.set(GlobalAppState::InGame);
+                }
+            }
+            MenuButton::Difficulty => {
+                let all = Difficulty::ALL;
+                let idx = all.iter().position(|d| *d == profile.difficulty).unwrap_or(0);
+                *profile = DifficultyProfile::preset(all[(idx + 1) % all.len()]);
+                for (mut text, t) in texts.iter_mut() {
+                    if t.0 == MenuButton::Difficulty {
+                        text.0 = MenuButton::Difficulty.label(profile.difficulty);
+                    }
+                }
+                // the leaderboard is per difficulty
+                if *screen == MenuScreen::Leaderboard {
+                    commands.insert_resource(MenuScreen::Leaderboard);
+                }
+            }
+            MenuButton::Leaderboard => {
+                commands.insert_resource(MenuScreen::Leaderboard);
+            }
+            MenuButton::Back => {
+                commands.insert_resource(MenuScreen::Main);
+            }
+            MenuButton::Resume => {
+                next_state.set(GlobalAppState::InGame);
+            }
+            MenuButton::Abandon | MenuButton::MainMenu => {
+                commands.queue(reset_run);
+                next_state.set(GlobalAppState::MainMenu);
+            }
+            MenuButton::Restart => {
+                commands.queue(reset_run);
+                commands.insert_resource(AutoStart);
+                next_state.set(GlobalAppState::MainMenu);
+            }
+        }
+    }
+}
+
+pub fn autostart(
+    mut commands: Commands,
+    state: Res<State<GlobalAppState>>,
+    auto: Option<Res<AutoStart>>,
+    tasks: Res<PreGameTasks>,
+    mut next_state: ResMut<NextState<GlobalAppState>>,
+) {
+    if auto.is_none() || *state.get() != GlobalAppState::MainMenu || !tasks.is_empty() {
+        return;
+    }
+    commands.remove_resource::<AutoStart>();
+    next_state.set(GlobalAppState::InGame);
+}
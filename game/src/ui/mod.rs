@@ -1,3 +1,5 @@
 pub mod plugin;
 pub mod components;
-pub mod target;
\ No newline at end of file
+pub mod target;pub mod menu;
+pub mod end_screen;
+pub mod leaderboard;
@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use bevy_asset_loader::{asset_collection::AssetCollection, loading_state::{config::{ConfigureLoadingState, LoadingStateConfig}, LoadingStateAppExt}};
 
-use crate::{core::states::AppLoadingAssetsSubState, ui::components::{hack_button::ui_hack_button_hover, ui_submit_button::ui_submit_button_hover}};
+use crate::{core::states::AppLoadingAssetsSubState, ui::{components::{hack_button::ui_hack_button_hover, ui_submit_button::ui_submit_button_hover}, end_screen::EndScreenPlugin, menu::MenuPlugin}};
 
 
 
@@ -11,6 +11,7 @@ pub struct UiSystemPlugin;
 impl Plugin for UiSystemPlugin {
     fn build(&self, app: &mut App) {
         app
+            .add_plugins((MenuPlugin, EndScreenPlugin))
             .configure_loading_state(
                 LoadingStateConfig::new(AppLoadingAssetsSubState::Loading)
                     .load_collection::<UiAssetCollection>(),
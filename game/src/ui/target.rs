@@ -22,6 +22,14 @@ pub struct HighresUiRoot;
 #[derive(Component)]
 pub struct LowresUiContainer;
 
+pub fn lowres_container_node() -> Node {
+    Node {
+        width: Val::Px(TARGET_WIDTH as f32),
+        height: Val::Px(TARGET_HEIGHT as f32),
+        ..default()
+    }
+}
+
 
 
 pub fn init(
@@ -41,11 +49,7 @@ pub fn init(
         // BackgroundColor(Color::linear_rgba(1.0, 0.0, 0.0, 0.3)),
     )).id();
     let lowres = cmd.spawn((
-        Node {
-            width: Val::Px(TARGET_WIDTH as f32),
-            height: Val::Px(TARGET_HEIGHT as f32),
-            ..default()
-        },
+        lowres_container_node(),
         Name::new("LowresUiContainer"),
         LowresUiContainer,
         // BackgroundColor(Color::linear_rgba(0.0, 1.0, 0.0, 0.3)),
@@ -0,0 +1,28 @@
+// Launch options: `--name value` / `--name=value` natively, `?name=value` in the page URL on wasm.
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn launch_arg(name: &str) -> Option<String> {
+    let flag = format!("--{name}");
+    let prefix = format!("--{name}=");
+    let mut args = std::env::args();
+    while let Some(arg) = args.next() {
+        if let Some(v) = arg.strip_prefix(&prefix) {
+            return Some(v.to_string());
+        }
+        if arg == flag {
+            return args.next();
+        }
+    }
+    None
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn launch_arg(name: &str) -> Option<String> {
+    let search = web_sys::window()?.location().search().ok()?;
+    let prefix = format!("{name}=");
+    search
+        .trim_start_matches('?')
+        .split('&')
+        .find_map(|kv| kv.strip_prefix(&prefix))
+        .map(|v| v.to_string())
+}
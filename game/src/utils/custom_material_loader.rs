@@ -4,7 +4,7 @@ use bevy_rapier2d::prelude::{ActiveCollisionTypes, ActiveEvents, Collider, Colli
 use pixel_utils::camera::{PixelCamera, TARGET_HEIGHT, TARGET_WIDTH};
 use shaders::components::*;
 
-use crate::{core::states::{AppLoadingAssetsSubState, GameUpdate, GlobalAppState, OnGame}, interactions::{chain_reaction_display::ChainGraphMaterial, collision_minigame::CollisionGraphMaterial, components::{InInteraction, Interactable, InteractableMaterial, InteractionTypes}, hack_minigame::{HACK_ATLAS_COLUMNS, HACK_ATLAS_ROWS, HACK_PIXEL_GRID_SIZE}, pipe_puzzle::SINGLE_PIPE_TEX_SIZE, warning_interface::{WARNING_GRID_COLUMNS, WARNING_GRID_ROWS, WARNING_GRID_SIZE}, wave_modulator::{WaveGraphMaterial, NUM_SPINNY_STATES, SPINNY_SIZE}}, physics::{animator::PlayerAnimations, constants::*, player::Player}, tilemap::light::LightEmitter, utils::{mouse::CursorPosition, spacial_audio::SoundAssets}};
+use crate::{core::{run::RunEntity, states::{AppLoadingAssetsSubState, GameUpdate, GlobalAppState, OnGame}}, interactions::{chain_reaction_display::ChainGraphMaterial, collision_minigame::CollisionGraphMaterial, components::{InInteraction, Interactable, InteractableMaterial, InteractionTypes}, hack_minigame::{HACK_ATLAS_COLUMNS, HACK_ATLAS_ROWS, HACK_PIXEL_GRID_SIZE}, pipe_puzzle::SINGLE_PIPE_TEX_SIZE, warning_interface::{WARNING_GRID_COLUMNS, WARNING_GRID_ROWS, WARNING_GRID_SIZE}, wave_modulator::{WaveGraphMaterial, NUM_SPINNY_STATES, SPINNY_SIZE}}, physics::{animator::PlayerAnimations, constants::*, player::Player}, tilemap::light::LightEmitter, utils::{mouse::CursorPosition, spacial_audio::SoundAssets}};
 
 
 
@@ -214,6 +214,7 @@ fn spawn_faz(
         Sprite::from_image(sprite_assets.faz.clone()),
         Transform::from_translation(Vec3::new(300., 100., 0.,)),
         Faz,
+        RunEntity,
         children![(
             FazLight,
             GlobalTransform::default(),
@@ -1,24 +1,50 @@
 use std::{collections::{HashMap, VecDeque}, time::Duration};
 
 use bevy::prelude::*;
-use bevy_tailwind::tw;
+use bevy_asset_loader::loading_state::{config::{ConfigureLoadingState, LoadingStateConfig}, LoadingStateAppExt};
 use debug_utils::{debug_overlay::DebugOverlayEvent, overlay_text};
+use serde::{Deserialize, Serialize};
 use tiled::PropertyValue;
 use utils::WrappedDelta;
 
-use crate::{core::states::GlobalAppState, interactions::{chain_reaction_display::CHAIN_GRAPH_LENGTH, pipe_puzzle::PipeMinigame, warning_interface::WarningData}, ui::target::LowresUiContainer, utils::{custom_material_loader::SpriteAssets, energy::Energy, spacial_audio::PlaySoundEvent}};
+use crate::{core::states::{AppLoadingAssetsSubState, GlobalAppState}, interactions::{chain_reaction_display::CHAIN_GRAPH_LENGTH, components::{InteractableId, InteractionTypes}, pipe_puzzle::PipeMinigame, warning_interface::WarningData}, utils::{difficulty::DifficultyProfile, energy::{Energy, ENGINE_THRESHOLD}, failure_graph::{relieve_strains, Strains, Subsystem}, malfunction_defs::{log_definitions_reload, MalfunctionAssets, MalfunctionDefinition, MalfunctionDefinitions, MalfunctionDefinitionsLoader}, orbits::{raised_by_approaches, DebrisField, OrbitalKind, CASCADE_RATE, DEORBIT_DRAW, DEORBIT_SECS}, rng::{GameRng, RngStream, SeededRng}, score::{RunOutcome, RunScore}, severity::{escalate_malfunctions, Stage, StageChanged}}};
 
 pub struct DebreePlugin;
 
 impl Plugin for DebreePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_plugins(DebreeSimPlugin)
+        .init_asset::<MalfunctionDefinitions>()
+        .init_asset_loader::<MalfunctionDefinitionsLoader>()
+        .configure_loading_state(
+            LoadingStateConfig::new(AppLoadingAssetsSubState::Loading)
+                .load_collection::<MalfunctionAssets>(),
+        )
+        .add_systems(Update, log_definitions_reload);
+    }
+}
+
+/// Debris and malfunction loop on its own: no rendering, UI or asset loading.
+/// Expects `MalfunctionAssets`, `Assets<MalfunctionDefinitions>`, `GameRng` and
+/// `DifficultyProfile` to be provided, and interactables with an `InteractableId` to break, see `utils::sim`.
+pub struct DebreeSimPlugin;
+
+impl Plugin for DebreeSimPlugin {
     fn build(&self, app: &mut App) {
         app
         .add_event::<GameEndEvent>()
+        .add_event::<DeorbitCommand>()
+        .add_event::<DeorbitFinished>()
+        .add_event::<StageChanged>()
         .insert_resource(DebreeLevel::new())
         .insert_resource(Malfunction::default())
+        .insert_resource(DebrisField::default())
+        .insert_resource(Strains::default())
         .insert_resource(DebreeTimer {timer: Timer::new(Duration::from_secs_f32(1.), TimerMode::Repeating)})
-        .add_systems(Update, (debree_level_management, manage_malfunctions, resolve_malfunctions,
-            tick_malfunctions, end_game).run_if(in_state(GlobalAppState::InGame)));
+        .add_systems(FixedUpdate, (relieve_strains, debree_level_management, manage_malfunctions,
+            raise_close_approaches, tick_malfunctions, escalate_malfunctions).chain().run_if(in_state(GlobalAppState::InGame)))
+        .add_systems(Update, (resolve_malfunctions, deorbit_satellites, end_game).run_if(in_state(GlobalAppState::InGame)));
     }
 }
 
@@ -30,6 +56,7 @@ pub struct DebreeLevel {
     pub chain_reaction: f32,
     pub malfunction_probability: f32,
     pub chain_reaction_graph: VecDeque<f32>,
+    pub elapsed: f32,
 }
 
 impl DebreeLevel {
@@ -50,31 +77,45 @@ pub fn debree_level_management(
     mut debree_level: ResMut<DebreeLevel>,
     mut overlay_events: EventWriter<DebugOverlayEvent>,
     mut timer: ResMut<DebreeTimer>,
+    mut field: ResMut<DebrisField>,
+    mut rng: ResMut<GameRng>,
+    profile: Res<DifficultyProfile>,
+    strains: Res<Strains>,
 ) {
-    // debree level 0..inf -> chain reaction 0..100% & malfunction probability per frame
+    // tracked objects -> chain reaction 0..100% & malfunction probability per frame
     // causes player to manage chain reaction via hack+deorbit, antennas level and condition
-    // debree level is not linearly prop to chain reaction; strategic deorbit can lower chain reaction
-    let t = time.elapsed_secs_wrapped();
-    let start = 0.00035;
-    let growth = 0.004;
-    debree_level.base_level = start * (growth * t).exp();
+    // chain reaction follows the breakup rate, which grows with the square of the objects sharing a shell,
+    // so strategic deorbit of crowded shells lowers it the most
+    // counted from the start of the run, not app startup, so asset loading time doesn't leak into a seeded run
+    debree_level.elapsed += time.dt();
+    let t = debree_level.elapsed;
+    if !field.is_populated() {
+        field.populate(&profile, rng.stream(RngStream::Debris));
+        debree_level.chain_reaction = 100. * field.breakup_rate(profile.fragmentation) / CASCADE_RATE;
+    }
+    debree_level.base_level = profile.start * field.objects.len() as f32 / field.initial_count as f32;
 
     debree_level.level = debree_level.base_level + debree_level.const_add;
-    debree_level.malfunction_probability = debree_level.level;
-    // debree_level.malfunction_probability = 0.;
+    debree_level.malfunction_probability = debree_level.level * strains.chance_mul(&Subsystem::DebreeLevel);
     // malfunc prob is perframe
-    debree_level.chain_reaction = debree_level.level / 0.7;
     overlay_text!(
         overlay_events;
         TopLeft;
         DEBREE_LEVEL:format!(
             "Debree base level {:.5?}
             Malfunction probability {:.2} %
+            Tracked objects {}, breakups {}
+            Seed {} {:?}
             ",
-            debree_level.base_level, debree_level.malfunction_probability * 100.),(255, 255, 255);
+            debree_level.base_level, debree_level.malfunction_probability * 100., field.objects.len(), field.breakups, rng.seed(), profile.difficulty),(255, 255, 255);
     );
     timer.timer.tick(Duration::from_secs_f32(time.dt()));
     if timer.timer.finished() {
+        // the field is stepped once a second, breakups are rare enough for that
+        let dt = timer.timer.duration().as_secs_f32();
+        field.step_breakups(t, dt, profile.fragmentation, rng.stream(RngStream::Debris));
+        field.expire_approaches(t);
+        debree_level.chain_reaction = 100. * field.breakup_rate(profile.fragmentation) / CASCADE_RATE;
         if debree_level.chain_reaction_graph.len() >= CHAIN_GRAPH_LENGTH * 4 {
             debree_level.chain_reaction_graph.pop_front();
         }
@@ -86,20 +127,59 @@ pub fn debree_level_management(
 #[derive(Clone)]
 pub struct Resolved {
     pub resolved_type: MalfunctionType,
+    /// interactable the malfunction was raised on
+    pub site: InteractableId,
     pub failed: bool,
 }
 
+/// Active malfunctions, one per broken interactable.
+/// The vectors are parallel, a kind can be raised once on each interactable that resolves it.
 #[derive(Resource, Default)]
 pub struct Malfunction {
     pub in_progress: bool,
     pub malfunction_types: Vec<MalfunctionType>,
+    pub malfunction_sites: Vec<InteractableId>,
     pub malfunction_timers: Vec<Timer>,
     pub warning_data: Vec<WarningData>,
     pub resolved: Vec<Resolved>,
     pub added_new_malfunction: bool,
 }
 
-#[derive(Default, PartialEq, Clone, Debug, Hash, Eq)]
+impl Malfunction {
+    /// Index of the malfunction raised on `site`
+    pub fn at_site(&self, site: InteractableId) -> Option<usize> {
+        self.malfunction_sites.iter().position(|s| *s == site)
+    }
+
+    pub fn is_broken(&self, site: InteractableId) -> bool {
+        self.at_site(site).is_some()
+    }
+
+    /// Stage of the malfunction raised on `site`
+    pub fn stage_at(&self, site: InteractableId) -> Option<Stage> {
+        self.at_site(site).map(|i| Stage::of(&self.malfunction_timers[i]))
+    }
+
+    /// Most escalated stage among the active malfunctions
+    pub fn worst_stage(&self) -> Option<Stage> {
+        self.malfunction_timers.iter().map(Stage::of).max()
+    }
+
+    /// Site of the interactable `entity`, if a malfunction is raised on it
+    pub fn broken_at(&self, entity: Option<Entity>, sites: &Query<&InteractableId>) -> Option<InteractableId> {
+        let site = *sites.get(entity?).ok()?;
+        self.is_broken(site).then_some(site)
+    }
+}
+
+/// Interactables that can break, in a stable order for seeded runs
+fn sites_of(sites: &Query<(&InteractableId, &InteractionTypes)>, kind: &InteractionTypes) -> Vec<InteractableId> {
+    let mut found: Vec<InteractableId> = sites.iter().filter(|(_, k)| *k == kind).map(|(id, _)| *id).collect();
+    found.sort();
+    found
+}
+
+#[derive(Default, PartialEq, Clone, Debug, Hash, Eq, Serialize, Deserialize)]
 pub enum MalfunctionType {
     #[default]
     NoMalfunction,
@@ -110,164 +190,170 @@ pub enum MalfunctionType {
     Engine,
 }
 
-const MALFUNCTION_TYPES_NUM: usize = 6;
-const ALL_MALFUNCTION_TYPES: [MalfunctionType; MALFUNCTION_TYPES_NUM - 1] = [
-    MalfunctionType::Reactor,
-    MalfunctionType::Collision,
-    MalfunctionType::Hack,
-    MalfunctionType::Waves,
-    MalfunctionType::Engine,
-];
-
 pub fn manage_malfunctions(
     debree_level: Res<DebreeLevel>,
-    keyboard: Res<ButtonInput<KeyCode>>,
     mut malfunction: ResMut<Malfunction>,
-    sprite_assets: Res<SpriteAssets>,
-    mut pipe_minigame: ResMut<PipeMinigame>,
+    sites: Query<(&InteractableId, &InteractionTypes)>,
+    mut pipe_minigames: Query<(&InteractableId, &mut PipeMinigame)>,
+    mut rng: ResMut<GameRng>,
+    malfunction_assets: Res<MalfunctionAssets>,
+    definitions: Res<Assets<MalfunctionDefinitions>>,
     time: Res<Time>,
+    profile: Res<DifficultyProfile>,
+    strains: Res<Strains>,
+    mut score: ResMut<RunScore>,
     mut minimal_delta: Local<Duration>
 ) {
     *minimal_delta += Duration::from_secs_f32(time.dt());
-    let rand = getrandom::u32().unwrap() as f32 / u32::MAX as f32;
-    if rand < debree_level.malfunction_probability /*|| keyboard.just_released(KeyCode::KeyP)*/ {
-        // println!("{:?}", minimal_delta);
-        if minimal_delta.as_secs_f32() > 10. {
-            // println!("AAAAAAAAAAAAAAAAAAAAAAAAAAA");
+    let rand = rng.stream(RngStream::Malfunctions).f32();
+    if rand < debree_level.malfunction_probability {
+        if minimal_delta.as_secs_f32() > profile.malfunction_gap {
             *minimal_delta = Duration::ZERO
         } else {
             return;
         }
+        let Some(definitions) = definitions.get(&malfunction_assets.definitions) else {
+            warn!("Malfunction definitions are not loaded");
+            return;
+        };
         malfunction.in_progress = true;
         let mut available_for_malfunction = vec![];
-        for malf_type in ALL_MALFUNCTION_TYPES.iter() {
-            if !malfunction.malfunction_types.contains(malf_type) {
-                available_for_malfunction.push(malf_type.clone());
+        for definition in definitions.malfunctions.iter() {
+            if raised_by_approaches(&definition.kind) {
+                continue;
+            }
+            for site in sites_of(&sites, &definition.resolved_by) {
+                if !malfunction.is_broken(site) {
+                    available_for_malfunction.push((definition, site));
+                }
             }
         }
         if available_for_malfunction.is_empty() {
-            println!("all possible malfunctions are in progress {} {}", rand, debree_level.malfunction_probability);
+            debug!("all possible malfunctions are in progress {} {}", rand, debree_level.malfunction_probability);
             malfunction.added_new_malfunction = false;
             return;
         }
         malfunction.added_new_malfunction = true;
-        let malfunc_type_idx = ((getrandom::u32().unwrap() as f32 / u32::MAX as f32) * available_for_malfunction.len() as f32) as usize;
-        let malfunc_type = available_for_malfunction[malfunc_type_idx].clone();
-        match malfunc_type {
-            MalfunctionType::Reactor => {
-                malfunction.malfunction_types.push(malfunc_type);
-                malfunction.warning_data.push(WarningData {
-                    color: true,
-                    text: "Reactor malfunctioned!".to_string(),
-                });
-                malfunction.malfunction_timers.push(Timer::new(Duration::from_secs_f32(TIME_TO_RESOLVE), TimerMode::Once));
-            },
-            MalfunctionType::Collision => {
-                malfunction.malfunction_types.push(malfunc_type);
-                malfunction.warning_data.push(WarningData {
-                    color: false,
-                    text: "The ship is on a trajectory to collide with debree!".to_string(),
-                });
-                malfunction.malfunction_timers.push(Timer::new(Duration::from_secs_f32(TIME_TO_RESOLVE), TimerMode::Once));
-            },
-            MalfunctionType::Hack => {
-                malfunction.malfunction_types.push(malfunc_type);
-                malfunction.warning_data.push(WarningData {
-                    color: true,
-                    text: "A sattelite is on a collision trajectory!".to_string(),
-                });
-                malfunction.malfunction_timers.push(Timer::new(Duration::from_secs_f32(TIME_TO_RESOLVE), TimerMode::Once));
-            },
-            MalfunctionType::Waves => {
-                malfunction.malfunction_types.push(malfunc_type);
-                malfunction.warning_data.push(WarningData {
-                    color: true,
-                    text: "Antenna malfunctioned!".to_string(),
-                });
-                malfunction.malfunction_timers.push(Timer::new(Duration::from_secs_f32(TIME_TO_RESOLVE1), TimerMode::Once));
-            },
-            MalfunctionType::Engine => {
-                malfunction.malfunction_types.push(malfunc_type);
-                pipe_minigame.fill_solved();
-                pipe_minigame.shuffle();
-                malfunction.warning_data.push(WarningData {
-                    color: false,
-                    text: "Engine malfunctioned!".to_string(),
-                });
-                malfunction.malfunction_timers.push(Timer::new(Duration::from_secs_f32(TIME_TO_RESOLVE1), TimerMode::Once));
-            },
-            MalfunctionType::NoMalfunction => unreachable!()
-        };
-        println!("new malfunc: {:?}", malfunction.warning_data[malfunction.warning_data.len() - 1].text);
+        // kinds strained by a failure upstream come up more often
+        let weights: Vec<f32> = available_for_malfunction.iter()
+            .map(|(definition, _)| strains.chance_mul(&Subsystem::Malfunction(definition.kind.clone())))
+            .collect();
+        let malfunc_type_idx = rng.stream(RngStream::Malfunctions).weighted(&weights);
+        let (definition, site) = available_for_malfunction[malfunc_type_idx];
+        if definition.kind == MalfunctionType::Engine {
+            let pipes_rng = rng.stream(RngStream::Pipes);
+            for (_, mut pipe_minigame) in pipe_minigames.iter_mut().filter(|(id, _)| **id == site) {
+                pipe_minigame.fill_solved(pipes_rng);
+                pipe_minigame.shuffle(pipes_rng);
+            }
+        }
+        let secs = definition.time_to_resolve * profile.time_to_resolve_mul * strains.time_mul(&Subsystem::Malfunction(definition.kind.clone()));
+        raise_malfunction(&mut malfunction, definition, site, secs, &mut score, debree_level.elapsed);
     }
 }
 
-pub fn get_random_range(mi: f32, ma: f32) -> f32 {
-    let rand = getrandom::u32().unwrap() as f32 / (u32::MAX as f32);
-    mi + rand * (ma - mi) // TODO: IS THERE MA + 1????
+pub fn raise_malfunction(
+    malfunction: &mut Malfunction,
+    definition: &MalfunctionDefinition,
+    site: InteractableId,
+    secs: f32,
+    score: &mut RunScore,
+    elapsed: f32,
+) {
+    malfunction.in_progress = true;
+    malfunction.malfunction_types.push(definition.kind.clone());
+    malfunction.malfunction_sites.push(site);
+    score.raise(definition.kind.clone(), site, elapsed);
+    malfunction.warning_data.push(definition.warning.to_warning_data());
+    malfunction.malfunction_timers.push(Timer::new(Duration::from_secs_f32(secs), TimerMode::Once));
+    info!("new malfunction {:?} on {:?}: {:?}", definition.kind, site, malfunction.warning_data[malfunction.warning_data.len() - 1].text);
 }
 
-const WAVE_COST: f32 = 0.0004;
-const HACK_COST: f32 = 0.0004;
+/// Share of the time to resolve a close approach is at least warned ahead by.
+/// Approaches found later than that, e.g. while the same malfunction is already raised, pass unnoticed.
+const MIN_APPROACH_WARNING: f32 = 0.9;
+
+/// Collisions and hacks come from the debris field: an object predicted to pass the station
+/// within the time the malfunction gives to resolve it raises it, and its timer runs out at the approach
+pub fn raise_close_approaches(
+    debree_level: Res<DebreeLevel>,
+    timer: Res<DebreeTimer>,
+    mut field: ResMut<DebrisField>,
+    mut malfunction: ResMut<Malfunction>,
+    sites: Query<(&InteractableId, &InteractionTypes)>,
+    malfunction_assets: Res<MalfunctionAssets>,
+    definitions: Res<Assets<MalfunctionDefinitions>>,
+    profile: Res<DifficultyProfile>,
+    strains: Res<Strains>,
+    mut score: ResMut<RunScore>,
+) {
+    if !timer.timer.just_finished() {
+        return;
+    }
+    let Some(definitions) = definitions.get(&malfunction_assets.definitions) else {
+        return;
+    };
+    let t = debree_level.elapsed;
+    for kind in [OrbitalKind::Debris, OrbitalKind::Satellite] {
+        let Some(definition) = definitions.get(&kind.malfunction()) else {
+            continue;
+        };
+        // one approach per interactable that can deal with it
+        let Some(site) = sites_of(&sites, &definition.resolved_by).into_iter().find(|s| !malfunction.is_broken(*s)) else {
+            continue;
+        };
+        let horizon = definition.time_to_resolve * profile.time_to_resolve_mul * strains.time_mul(&Subsystem::Malfunction(definition.kind.clone()));
+        let Some(approach) = field.next_approach(kind, t + horizon * MIN_APPROACH_WARNING, t + horizon) else {
+            continue;
+        };
+        field.track(site, approach);
+        raise_malfunction(&mut malfunction, definition, site, approach.at - t, &mut score, t);
+    }
+}
+
+pub fn get_random_range(rng: &mut SeededRng, mi: f32, ma: f32) -> f32 {
+    rng.range(mi, ma)
+}
 
 pub fn resolve_malfunctions(
     mut malfunction: ResMut<Malfunction>,
     mut debree_level: ResMut<DebreeLevel>,
     mut energy: ResMut<Energy>,
+    mut field: ResMut<DebrisField>,
+    mut rng: ResMut<GameRng>,
     mut event_writer: EventWriter<GameEndEvent>,
+    malfunction_assets: Res<MalfunctionAssets>,
+    definitions: Res<Assets<MalfunctionDefinitions>>,
+    profile: Res<DifficultyProfile>,
+    mut strains: ResMut<Strains>,
+    mut score: ResMut<RunScore>,
 ) {
     if !malfunction.resolved.is_empty() {
+        let definitions = definitions.get(&malfunction_assets.definitions);
         for resolved in malfunction.resolved.clone() {
-            let index = malfunction.malfunction_types.iter().position(|r: &MalfunctionType| r == &resolved.resolved_type);
-            let Some(index) = index else {warn!("NOTHING TO REMOVE?"); continue};
+            let index = malfunction.at_site(resolved.site).filter(|i| malfunction.malfunction_types[*i] == resolved.resolved_type);
+            let Some(index) = index else {warn!("No {:?} raised on {:?} to resolve", resolved.resolved_type, resolved.site); continue};
+            // the earlier it's dealt with the more a fix gives back
+            let strength = if resolved.failed {1.} else {Stage::of(&malfunction.malfunction_timers[index]).outcome_mul()};
             let to_be_resolved = malfunction.malfunction_types.remove(index);
+            malfunction.malfunction_sites.remove(index);
             malfunction.malfunction_timers.remove(index);
-            match to_be_resolved {
-                MalfunctionType::Hack => {
-                    if resolved.failed {
-                        debree_level.const_add += HACK_COST;
-                        println!("failed hack");
-                    } else {
-                        debree_level.const_add -= HACK_COST / 2.;
-                        println!("resolved hack");
-                    }
-                },
-                MalfunctionType::Collision => {
-                    if resolved.failed {
-                        println!("failed collision"); // end
-                        event_writer.write(GameEndEvent);
-                    } else {
-                        println!("resolved collision"); // go on
-                    }
-                },
-                MalfunctionType::Reactor => {
-                    if resolved.failed {
-                        energy.generated *= 0.9;
-                        println!("failed reactor");
-                    } else {
-                        energy.generated *= 1.1;
-                        println!("resolved reactor");
-                    }
-                },
-                MalfunctionType::Waves => {
-                    if resolved.failed {
-                        debree_level.const_add += WAVE_COST;
-                        println!("failed waves");
-                    } else {
-                        debree_level.const_add -= WAVE_COST / 2.;
-                        println!("resolved waves");
-                    }
-                },
-                MalfunctionType::Engine => {
-                    if resolved.failed {
-                        event_writer.write(GameEndEvent);
-                        println!("failed engine"); // end
-                    } else {
-                        println!("resolved engine");
-                    }
-                },
-                MalfunctionType::NoMalfunction => {unreachable!()}
+            malfunction.warning_data.remove(index);
+            score.record(&resolved, debree_level.elapsed);
+            let Some(definition) = definitions.and_then(|d| d.get(&to_be_resolved)) else {
+                warn!("No definition for {:?}, skipping its effects", to_be_resolved);
+                field.forget(resolved.site);
+                continue;
+            };
+            let effects = if resolved.failed {&definition.on_failure} else {&definition.on_success};
+            if resolved.failed {
+                strains.fail(&to_be_resolved, &definition.downstream);
+            }
+            for effect in effects {
+                effect.apply(resolved.site, strength, &mut debree_level, &mut energy, &mut field, &mut rng, &mut event_writer, &profile);
             }
+            field.forget(resolved.site);
+            info!("{} {:?} on {:?}", if resolved.failed {"failed"} else {"resolved"}, to_be_resolved, resolved.site);
         }
         malfunction.resolved = vec![];
         if malfunction.malfunction_types.is_empty() {
@@ -276,28 +362,87 @@ pub fn resolve_malfunctions(
     }
 }
 
-pub const TIME_TO_RESOLVE: f32 = 60.;
-pub const TIME_TO_RESOLVE1: f32 = TIME_TO_RESOLVE * 2.;
-
 pub fn tick_malfunctions(
     mut malfunction: ResMut<Malfunction>,
     time: Res<Time>,
 ) {
-    // TODO: check for resolved errors and nulling locals after failing
     for idx in 0..malfunction.malfunction_types.len() {
         let timer = &mut malfunction.malfunction_timers[idx];
         timer.tick(Duration::from_secs_f32(time.dt()));
-        if timer.finished() {
+        // resolving runs in Update, several fixed steps can pass before it
+        if timer.just_finished() {
             let resolved_type = malfunction.malfunction_types[idx].clone();
-            println!("FAILED DUE TO TIME {:?}", resolved_type);
+            let site = malfunction.malfunction_sites[idx];
+            debug!("{:?} on {:?} ran out of time", resolved_type, site);
             malfunction.resolved.push(Resolved {
                 resolved_type,
+                site,
                 failed: true,
             });
         }
     }
 }
 
+/// Sent by the hack console to burn a hacked satellite down
+#[derive(Event, Clone, Copy, Debug)]
+pub struct DeorbitCommand {
+    pub object: u32,
+}
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct DeorbitFinished {
+    pub object: u32,
+    /// objects that left the field, the satellite included
+    pub removed: usize,
+    pub failed: bool,
+}
+
+/// Burns hacked satellites down, each one drags the debris of its shell along and lowers the chain reaction.
+/// The station drives the burn: it won't start without the power to spare,
+/// and if the surplus drops below what the engine needs mid-burn control of the satellite is lost.
+pub fn deorbit_satellites(
+    time: Res<Time>,
+    mut field: ResMut<DebrisField>,
+    mut energy: ResMut<Energy>,
+    mut commands: EventReader<DeorbitCommand>,
+    mut finished: EventWriter<DeorbitFinished>,
+) {
+    for command in commands.read() {
+        if field.deorbit.is_some() {
+            continue;
+        }
+        if !energy.check_if_enough(DEORBIT_DRAW) {
+            finished.write(DeorbitFinished { object: command.object, removed: 0, failed: true });
+            continue;
+        }
+        if field.start_deorbit(command.object) {
+            energy.add_consumption(DEORBIT_DRAW, Duration::from_secs_f32(DEORBIT_SECS));
+        }
+    }
+    let Some(mut deorbit) = field.deorbit else {
+        return;
+    };
+    // control is lost without the power to drive it, or with nothing left to drive
+    if energy.surplus < ENGINE_THRESHOLD || !field.objects.iter().any(|o| o.id == deorbit.object) {
+        field.hacked.retain(|h| *h != deorbit.object);
+        field.deorbit = None;
+        energy.ease_consumption(DEORBIT_DRAW);
+        finished.write(DeorbitFinished { object: deorbit.object, removed: 0, failed: true });
+        return;
+    }
+    deorbit.remaining -= time.delta_secs();
+    if deorbit.remaining > 0. {
+        // the extra load can't run out before the burn does
+        energy.add_consumption(0., Duration::from_secs_f32(deorbit.remaining));
+        field.deorbit = Some(deorbit);
+        return;
+    }
+    field.deorbit = None;
+    energy.ease_consumption(DEORBIT_DRAW);
+    let removed = field.deorbit(deorbit.object);
+    finished.write(DeorbitFinished { object: deorbit.object, removed, failed: removed == 0 });
+}
+
 #[derive(Event)]
 pub struct GameEndEvent;
 
@@ -306,43 +451,20 @@ pub fn end_game(
     time: Res<Time>,
     mut next_state: ResMut<NextState<GlobalAppState>>,
     mut event_reader: EventReader<GameEndEvent>,
-    mut event_writer: EventWriter<PlaySoundEvent>,
-    asset_server: Res<AssetServer>,
-    mut cmd: Commands,
-    ui: Single<Entity, With<LowresUiContainer>>,
-    c: Query<&Children, With<LowresUiContainer>>
+    mut outcome: ResMut<RunOutcome>,
 ) {
-    let mut end = false;
+    let mut end = None;
     for _event in event_reader.read() {
-        end = true;
-        println!("END {}", time.elapsed_secs());
+        end = Some(RunOutcome::Crashed);
+        info!("run crashed at {}", time.elapsed_secs());
     }
     if debree_level.chain_reaction >= 100. {
-        end = true;
-        println!("END {}", time.elapsed_secs());
+        end = Some(RunOutcome::ChainReaction);
+        info!("chain reaction at {}", time.elapsed_secs());
     }
-    if end {
-        event_writer.write(PlaySoundEvent::Boom);
+    if let Some(end) = end {
+        // the end screen and the boom come on entering `Defeat`, see `ui::end_screen`
+        *outcome = end;
         next_state.set(GlobalAppState::Defeat);
-        for c in c {
-            for c in c {
-                cmd.entity(*c).despawn();
-            }
-        }
-        cmd.entity(*ui).insert((
-            tw!("flex w-full h-full bg-black items-center content-center justify-center"),
-            children![(
-                Text::new("YOUR SPACESHIP CRASHED INTO THE DEBRIS..."),
-                TextFont {
-                    font: asset_server.load("fonts/orp_regular.ttf"),
-                    font_size: 24.0,
-                    ..default()
-                },
-                TextColor(Color::srgb_u8(200, 200, 200)),
-                tw!("z-10"),
-            )]
-        ));
     }
-    
 }
-
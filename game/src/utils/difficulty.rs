@@ -0,0 +1,127 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::{args::launch_arg, debree::DebreeLevel};
+
+pub struct DifficultyPlugin;
+
+impl Plugin for DifficultyPlugin {
+    fn build(&self, app: &mut App) {
+        let difficulty = launch_arg("difficulty")
+            .and_then(|v| Difficulty::parse(&v))
+            .unwrap_or_default();
+        info!("Difficulty: {:?}", difficulty);
+        app
+        .insert_resource(DifficultyProfile::preset(difficulty))
+        .add_systems(Startup, apply_difficulty);
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Difficulty {
+    Story,
+    #[default]
+    Normal,
+    Hard,
+    Endless,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [Difficulty::Story, Difficulty::Normal, Difficulty::Hard, Difficulty::Endless];
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "story" => Some(Self::Story),
+            "normal" => Some(Self::Normal),
+            "hard" => Some(Self::Hard),
+            "endless" => Some(Self::Endless),
+            _ => None,
+        }
+    }
+}
+
+/// Knobs of the debris field and malfunction pacing.
+/// Debris base level is `start` scaled by how much the tracked field has grown, see `utils::orbits`.
+#[derive(Resource, Clone, Debug)]
+pub struct DifficultyProfile {
+    pub difficulty: Difficulty,
+    pub start: f32,
+    /// tracked debris and satellites at the start of the run
+    pub initial_debris: u32,
+    pub initial_satellites: u32,
+    /// breakups per second for each pair of objects sharing an altitude shell
+    pub fragmentation: f32,
+    /// initial `DebreeLevel::const_add`
+    pub const_add: f32,
+    /// minimal seconds between two malfunctions
+    pub malfunction_gap: f32,
+    /// scales `time_to_resolve` of every malfunction definition
+    pub time_to_resolve_mul: f32,
+    /// scales debris added by failed hacks and antenna syncs
+    pub debris_cost_mul: f32,
+    /// the run is won after this many seconds, `None` plays until the station is lost
+    pub survive_secs: Option<f32>,
+}
+
+impl DifficultyProfile {
+    pub fn preset(difficulty: Difficulty) -> Self {
+        match difficulty {
+            Difficulty::Story => Self {
+                difficulty,
+                start: 0.00025,
+                initial_debris: 300,
+                initial_satellites: 50,
+                fragmentation: 3e-6,
+                const_add: 0.0005,
+                malfunction_gap: 20.,
+                time_to_resolve_mul: 1.5,
+                debris_cost_mul: 0.5,
+                survive_secs: Some(600.),
+            },
+            Difficulty::Normal => Self {
+                difficulty,
+                start: 0.00035,
+                initial_debris: 400,
+                initial_satellites: 60,
+                fragmentation: 4e-6,
+                const_add: 0.0008,
+                malfunction_gap: 10.,
+                time_to_resolve_mul: 1.,
+                debris_cost_mul: 1.,
+                survive_secs: Some(900.),
+            },
+            Difficulty::Hard => Self {
+                difficulty,
+                start: 0.0005,
+                initial_debris: 500,
+                initial_satellites: 70,
+                fragmentation: 5e-6,
+                const_add: 0.001,
+                malfunction_gap: 6.,
+                time_to_resolve_mul: 0.75,
+                debris_cost_mul: 1.5,
+                survive_secs: Some(1200.),
+            },
+            // slow curve for long sessions, the field still wins eventually
+            Difficulty::Endless => Self {
+                difficulty,
+                start: 0.00035,
+                initial_debris: 400,
+                initial_satellites: 60,
+                fragmentation: 1.5e-6,
+                const_add: 0.0008,
+                malfunction_gap: 12.,
+                time_to_resolve_mul: 1.,
+                debris_cost_mul: 1.,
+                survive_secs: None,
+            },
+        }
+    }
+}
+
+pub fn apply_difficulty(
+    profile: Res<DifficultyProfile>,
+    mut debree_level: ResMut<DebreeLevel>,
+) {
+    debree_level.const_add = profile.const_add;
+}
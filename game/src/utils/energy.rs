@@ -2,6 +2,9 @@ use std::time::Duration;
 
 use bevy::prelude::*;
 use debug_utils::{debug_overlay::DebugOverlayEvent, overlay_text};
+use serde::{Deserialize, Serialize};
+
+use crate::{core::states::GlobalAppState, utils::debree::GameEndEvent};
 
 pub struct EnergyPlugin;
 
@@ -9,7 +12,9 @@ impl Plugin for EnergyPlugin {
     fn build(&self, app: &mut App) {
         app
         .insert_resource(Energy::new())
-        .add_systems(Update, manage_energy)
+        .insert_resource(PowerState::new())
+        .add_event::<PowerEvent>()
+        .add_systems(Update, manage_energy.run_if(in_state(GlobalAppState::InGame)))
         ;
     }
 }
@@ -21,62 +26,167 @@ pub struct Energy {
     pub gravity_consumption: f32,
     pub lamps_consumption: f32,
     pub surplus: f32,
-    pub increase_consumption: (f32, Duration)
+    /// extra load and how much longer it lasts, loads added on top of each other share the time
+    pub increase_consumption: (f32, Duration),
+    /// load from escalated malfunctions, see `utils::severity`
+    pub malfunction_drain: f32,
+    pub routing: PowerRouting,
 }
 
 impl Energy {
     pub fn new() -> Self {
         Self {
             generated: 150.,
-            engine_consumption: 20.,
-            gravity_consumption: 10.,
-            lamps_consumption: 10.,
+            engine_consumption: ENGINE_DRAW,
+            gravity_consumption: GRAVITY_DRAW,
+            lamps_consumption: LAMPS_DRAW,
             surplus: 0.,
-            increase_consumption: (0., Duration::ZERO)
+            increase_consumption: (0., Duration::ZERO),
+            malfunction_drain: 0.,
+            routing: PowerRouting::default(),
+        }
+    }
+}
+
+const ENGINE_DRAW: f32 = 20.;
+const GRAVITY_DRAW: f32 = 10.;
+const LAMPS_DRAW: f32 = 10.;
+
+/// Share of the nominal draw each subsystem gets, 0..=1.
+/// Set from the power routing console.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct PowerRouting {
+    pub engine: f32,
+    pub gravity: f32,
+    pub lamps: f32,
+}
+
+impl Default for PowerRouting {
+    fn default() -> Self {
+        Self {
+            engine: 1.,
+            gravity: 1.,
+            lamps: 1.,
         }
     }
 }
 
+/// below this share the engine can't hold orbit, however much surplus there is
+pub const ENGINE_MIN_ROUTING: f32 = 0.5;
+/// below this share gravity generators shut down
+pub const GRAVITY_MIN_ROUTING: f32 = 0.25;
+
 impl Energy {
     pub fn check_if_enough(&self, increased: f32) -> bool {
         self.generated - self.engine_consumption - self.gravity_consumption
-        - self.lamps_consumption - increased > ENGINE_THRESHOLD
+        - self.lamps_consumption - self.increase_consumption.0 - self.malfunction_drain
+        - increased > ENGINE_THRESHOLD
+    }
+
+    /// Adds `amount` to the extra load, which then lasts at least `duration`
+    pub fn add_consumption(&mut self, amount: f32, duration: Duration) {
+        self.increase_consumption.0 += amount;
+        self.increase_consumption.1 = self.increase_consumption.1.max(duration);
+    }
+
+    /// Takes `amount` back off the extra load before it runs out
+    pub fn ease_consumption(&mut self, amount: f32) {
+        self.increase_consumption.0 = (self.increase_consumption.0 - amount).max(0.);
     }
 }
 
 const LAMPS_THRESHOLD: f32 = 90.;
 const GRAVITY_THRESHOLD: f32 = 80.;
 pub const ENGINE_THRESHOLD: f32 = 70.;
+/// how long the engine holds the orbit without power
+const ENGINE_COUNTDOWN_SECS: f32 = 30.;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PoweredSystem {
+    Lamps,
+    Gravity,
+    Engine,
+}
+
+/// Sent whenever the surplus crosses a threshold, in either direction
+#[derive(Event, Clone, Copy, Debug)]
+pub struct PowerEvent {
+    pub system: PoweredSystem,
+    pub powered: bool,
+}
+
+#[derive(Resource)]
+pub struct PowerState {
+    pub lamps: bool,
+    pub gravity: bool,
+    pub engine: bool,
+    pub engine_countdown: Timer,
+}
+
+impl PowerState {
+    pub fn new() -> Self {
+        Self {
+            lamps: true,
+            gravity: true,
+            engine: true,
+            engine_countdown: Timer::from_seconds(ENGINE_COUNTDOWN_SECS, TimerMode::Once),
+        }
+    }
+
+    fn set(&mut self, system: PoweredSystem, powered: bool, events: &mut EventWriter<PowerEvent>) {
+        let current = match system {
+            PoweredSystem::Lamps => &mut self.lamps,
+            PoweredSystem::Gravity => &mut self.gravity,
+            PoweredSystem::Engine => &mut self.engine,
+        };
+        if *current != powered {
+            *current = powered;
+            events.write(PowerEvent { system, powered });
+        }
+    }
+}
 
 pub fn manage_energy(
     mut energy: ResMut<Energy>,
+    mut power: ResMut<PowerState>,
     time: Res<Time>,
     mut overlay_events: EventWriter<DebugOverlayEvent>,
+    mut power_events: EventWriter<PowerEvent>,
+    mut game_end: EventWriter<GameEndEvent>,
 ) {
     if !energy.increase_consumption.1.is_zero() {
         energy.increase_consumption.1 = energy.increase_consumption.1.saturating_sub(time.delta());
     } else {
         energy.increase_consumption.0 = 0.;
     }
+    energy.engine_consumption = ENGINE_DRAW * energy.routing.engine;
+    energy.gravity_consumption = GRAVITY_DRAW * energy.routing.gravity;
+    energy.lamps_consumption = LAMPS_DRAW * energy.routing.lamps;
     energy.surplus = energy.generated - energy.engine_consumption - energy.gravity_consumption
-    - energy.lamps_consumption - energy.increase_consumption.0;
+    - energy.lamps_consumption - energy.increase_consumption.0 - energy.malfunction_drain;
     overlay_text!(
         overlay_events;
         TopLeft;
         ENERGY:format!(
-            "surp {} = generated {} - eng cons {} - grav cons {} - lamps cons {} - incr cons {}", 
+            "surp {} = generated {} - eng cons {} - grav cons {} - lamps cons {} - incr cons {} - malf drain {}
+            engine countdown {:.1}", 
             energy.surplus, energy.generated, energy.engine_consumption, energy.gravity_consumption, energy.lamps_consumption, 
-            energy.increase_consumption.0
+            energy.increase_consumption.0, energy.malfunction_drain, power.engine_countdown.remaining_secs()
         ),
         (255, 255, 255);
     );
-    if energy.surplus < LAMPS_THRESHOLD {
-        // jiggle lamps
-    }
-    if energy.surplus < GRAVITY_THRESHOLD {
-        // turn off gravity
-    }
-    if energy.surplus < ENGINE_THRESHOLD {
-        // die
+    // lamps brown out, gravity fades and the engine starts losing orbit
+    // rerouted subsystems fail too, that's the price for keeping the others up
+    let routing = energy.routing;
+    power.set(PoweredSystem::Lamps, energy.surplus >= LAMPS_THRESHOLD && routing.lamps > 0., &mut power_events);
+    power.set(PoweredSystem::Gravity, energy.surplus >= GRAVITY_THRESHOLD && routing.gravity >= GRAVITY_MIN_ROUTING, &mut power_events);
+    power.set(PoweredSystem::Engine, energy.surplus >= ENGINE_THRESHOLD && routing.engine >= ENGINE_MIN_ROUTING, &mut power_events);
+    if power.engine {
+        power.engine_countdown.reset();
+    } else {
+        power.engine_countdown.tick(time.delta());
+        if power.engine_countdown.just_finished() {
+            game_end.write(GameEndEvent);
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,139 @@
+// Cascading failures between the station's subsystems.
+// The graph is declared in `station.malfunctions.ron`: every malfunction lists the subsystems
+// downstream of it, and letting it run out strains them for a while. A strained malfunction is
+// picked more often and raised with less time to resolve it, a strained debree level raises
+// malfunctions more often. Strains from different failures stack, the same failure refreshes its own.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::debree::MalfunctionType;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Subsystem {
+    Malfunction(MalfunctionType),
+    DebreeLevel,
+}
+
+fn unchanged() -> f32 {
+    1.
+}
+
+/// Edge of the graph, declared on the malfunction whose failure strains `to`
+#[derive(Deserialize, Debug, Clone)]
+pub struct Downstream {
+    pub to: Subsystem,
+    /// multiplies how likely `to` is picked, for the debree level the malfunction probability
+    #[serde(default = "unchanged")]
+    pub chance_mul: f32,
+    /// multiplies the time to resolve `to`, for collisions and hacks how far ahead approaches are warned about
+    #[serde(default = "unchanged")]
+    pub time_mul: f32,
+    /// how long the strain lasts after the failure
+    pub secs: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Strain {
+    pub from: MalfunctionType,
+    pub to: Subsystem,
+    pub chance_mul: f32,
+    pub time_mul: f32,
+    pub remaining: f32,
+}
+
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Strains {
+    pub active: Vec<Strain>,
+}
+
+impl Strains {
+    /// `from` ran out, strain everything downstream of it
+    pub fn fail(&mut self, from: &MalfunctionType, downstream: &[Downstream]) {
+        for edge in downstream {
+            self.active.retain(|s| !(&s.from == from && s.to == edge.to));
+            self.active.push(Strain {
+                from: from.clone(),
+                to: edge.to.clone(),
+                chance_mul: edge.chance_mul,
+                time_mul: edge.time_mul,
+                remaining: edge.secs,
+            });
+        }
+    }
+
+    pub fn chance_mul(&self, to: &Subsystem) -> f32 {
+        self.active.iter().filter(|s| &s.to == to).map(|s| s.chance_mul).product()
+    }
+
+    pub fn time_mul(&self, to: &Subsystem) -> f32 {
+        self.active.iter().filter(|s| &s.to == to).map(|s| s.time_mul).product()
+    }
+
+    /// Seconds left on the strain `from` put on `to`
+    pub fn remaining(&self, from: &MalfunctionType, to: &Subsystem) -> Option<f32> {
+        self.active.iter().find(|s| &s.from == from && &s.to == to).map(|s| s.remaining)
+    }
+
+    pub fn relieve(&mut self, dt: f32) {
+        for strain in self.active.iter_mut() {
+            strain.remaining -= dt;
+        }
+        self.active.retain(|s| s.remaining > 0.);
+    }
+}
+
+pub fn relieve_strains(
+    time: Res<Time>,
+    mut strains: ResMut<Strains>,
+) {
+    if !strains.active.is_empty() {
+        strains.relieve(time.delta_secs());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::malfunction_defs::{MalfunctionDefinitions, DEFINITIONS_PATH};
+
+    fn edge(to: Subsystem, chance_mul: f32, time_mul: f32, secs: f32) -> Downstream {
+        Downstream { to, chance_mul, time_mul, secs }
+    }
+
+    #[test]
+    fn strains_stack_refresh_and_wear_off() {
+        let engine = Subsystem::Malfunction(MalfunctionType::Engine);
+        let mut strains = Strains::default();
+        strains.fail(&MalfunctionType::Reactor, &[edge(engine.clone(), 2., 0.5, 10.)]);
+        strains.fail(&MalfunctionType::Waves, &[edge(engine.clone(), 3., 1., 20.)]);
+        assert_eq!(strains.chance_mul(&engine), 6.);
+        assert_eq!(strains.time_mul(&engine), 0.5);
+        assert_eq!(strains.chance_mul(&Subsystem::DebreeLevel), 1.);
+
+        strains.relieve(5.);
+        strains.fail(&MalfunctionType::Reactor, &[edge(engine.clone(), 2., 0.5, 10.)]);
+        assert_eq!(strains.active.len(), 2);
+        assert_eq!(strains.remaining(&MalfunctionType::Reactor, &engine), Some(10.));
+
+        strains.relieve(15.);
+        assert_eq!(strains.chance_mul(&engine), 1.);
+        assert!(strains.active.is_empty());
+    }
+
+    #[test]
+    fn station_graph_points_at_defined_malfunctions() {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(DEFINITIONS_PATH);
+        let defs = MalfunctionDefinitions::read(&path).unwrap();
+        for def in defs.malfunctions.iter() {
+            for edge in def.downstream.iter() {
+                if let Subsystem::Malfunction(kind) = &edge.to {
+                    assert!(defs.get(kind).is_some(), "{:?} strains undefined {:?}", def.kind, kind);
+                    assert_ne!(kind, &def.kind, "{:?} strains itself", def.kind);
+                }
+                assert!(edge.secs > 0. && edge.chance_mul > 0. && edge.time_mul > 0.);
+            }
+        }
+        assert!(defs.malfunctions.iter().any(|d| !d.downstream.is_empty()));
+    }
+}
@@ -0,0 +1,217 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{core::states::GlobalAppState, utils::{debree::DebreeLevel, difficulty::{Difficulty, DifficultyProfile}, rng::GameRng, score::{MalfunctionLogEntry, RunOutcome, RunScore}, storage}};
+
+const HISTORY_KEY: &str = "history";
+/// oldest runs are dropped past this
+const MAX_RUNS: usize = 200;
+
+pub struct HistoryPlugin;
+
+impl Plugin for HistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(RunHistory::load())
+        .add_systems(OnEnter(GlobalAppState::Defeat), record_run)
+        .add_systems(OnEnter(GlobalAppState::Victory), record_run);
+    }
+}
+
+/// A finished run, kept across sessions
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub seed: u64,
+    pub difficulty: Difficulty,
+    /// seconds survived
+    pub duration: f32,
+    pub outcome: RunOutcome,
+    pub score: i32,
+    pub malfunctions: Vec<MalfunctionLogEntry>,
+}
+
+impl RunRecord {
+    pub fn new(seed: u64, difficulty: Difficulty, duration: f32, outcome: RunOutcome, score: &RunScore) -> Self {
+        Self {
+            seed,
+            difficulty,
+            duration,
+            outcome,
+            score: score.breakdown(duration, outcome).total,
+            malfunctions: score.log.clone(),
+        }
+    }
+}
+
+/// Every recorded run, oldest first
+#[derive(Resource, Default, Clone, Debug, Serialize, Deserialize)]
+pub struct RunHistory {
+    pub runs: Vec<RunRecord>,
+}
+
+impl RunHistory {
+    pub fn load() -> Self {
+        let Some(data) = storage::read(HISTORY_KEY) else {
+            return Self::default();
+        };
+        ron::de::from_str(&data).unwrap_or_else(|e| {
+            warn!("Discarding unreadable run history: {}", e);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) {
+        match ron::ser::to_string(self) {
+            Ok(data) => {
+                if let Err(e) = storage::write(HISTORY_KEY, &data) {
+                    warn!("Failed to save run history: {}", e);
+                }
+            }
+            Err(e) => warn!("Failed to serialize run history: {}", e),
+        }
+    }
+
+    pub fn push(&mut self, record: RunRecord) {
+        self.runs.push(record);
+        if self.runs.len() > MAX_RUNS {
+            let extra = self.runs.len() - MAX_RUNS;
+            self.runs.drain(..extra);
+        }
+    }
+
+    /// Best runs first, ties go to the longer run
+    pub fn leaderboard(&self, difficulty: Option<Difficulty>, count: usize) -> Vec<&RunRecord> {
+        let mut runs: Vec<&RunRecord> = self.runs.iter()
+            .filter(|r| difficulty.is_none_or(|d| r.difficulty == d))
+            .collect();
+        runs.sort_by(|a, b| b.score.cmp(&a.score).then(b.duration.total_cmp(&a.duration)));
+        runs.truncate(count);
+        runs
+    }
+}
+
+pub fn record_run(
+    mut history: ResMut<RunHistory>,
+    rng: Res<GameRng>,
+    profile: Res<DifficultyProfile>,
+    debree_level: Res<DebreeLevel>,
+    outcome: Res<RunOutcome>,
+    score: Res<RunScore>,
+) {
+    let record = RunRecord::new(rng.seed(), profile.difficulty, debree_level.elapsed, *outcome, &score);
+    info!("Run over: {:?}, score {}", record.outcome, record.score);
+    history.push(record);
+    history.save();
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::{interactions::components::InteractableId, utils::{debree::{resolve_malfunctions, GameEndEvent, Malfunction, MalfunctionType, Resolved}, energy::Energy, failure_graph::Strains, malfunction_defs::{MalfunctionAssets, MalfunctionDefinitions}, orbits::DebrisField}};
+
+    fn headless_app() -> App {
+        let mut app = App::new();
+        app.add_event::<GameEndEvent>()
+            .insert_resource(DebreeLevel::new())
+            .insert_resource(Energy::new())
+            .insert_resource(Malfunction::default())
+            .insert_resource(DebrisField::default())
+            .insert_resource(Strains::default())
+            .insert_resource(GameRng::new(0))
+            .insert_resource(DifficultyProfile::preset(Difficulty::Normal))
+            .insert_resource(RunScore::default())
+            .insert_resource(Assets::<MalfunctionDefinitions>::default())
+            .insert_resource(MalfunctionAssets { definitions: Handle::default() });
+        app
+    }
+
+    fn raise(app: &mut App, kind: MalfunctionType, at: f32) {
+        let world = app.world_mut();
+        world.resource_mut::<DebreeLevel>().elapsed = at;
+        let site = InteractableId(world.resource::<Malfunction>().malfunction_types.len() as u32);
+        world.resource_mut::<Malfunction>().malfunction_sites.push(site);
+        world.resource_mut::<Malfunction>().malfunction_types.push(kind.clone());
+        world.resource_mut::<Malfunction>().malfunction_timers.push(Timer::from_seconds(60., TimerMode::Once));
+        world.resource_mut::<Malfunction>().warning_data.push(default());
+        world.resource_mut::<RunScore>().raise(kind, site, at);
+    }
+
+    fn resolve(app: &mut App, kind: MalfunctionType, failed: bool, at: f32) {
+        let world = app.world_mut();
+        world.resource_mut::<DebreeLevel>().elapsed = at;
+        let malfunction = world.resource::<Malfunction>();
+        let idx = malfunction.malfunction_types.iter().position(|t| *t == kind).unwrap();
+        let site = malfunction.malfunction_sites[idx];
+        world.resource_mut::<Malfunction>().resolved.push(Resolved { resolved_type: kind, site, failed });
+        world.run_system_once(resolve_malfunctions).unwrap();
+    }
+
+    #[test]
+    fn resolved_flow_fills_the_log() {
+        let mut app = headless_app();
+        raise(&mut app, MalfunctionType::Hack, 5.);
+        raise(&mut app, MalfunctionType::Waves, 8.);
+        resolve(&mut app, MalfunctionType::Hack, false, 12.);
+        resolve(&mut app, MalfunctionType::Waves, true, 30.);
+        raise(&mut app, MalfunctionType::Hack, 40.);
+
+        let score = app.world().resource::<RunScore>();
+        assert_eq!(score.resolved(), 1);
+        assert_eq!(score.failed(), 1);
+        assert_eq!(score.log, vec![
+            MalfunctionLogEntry { kind: MalfunctionType::Hack, site: Some(InteractableId(0)), raised: 5., resolved: Some(12.), failed: false },
+            MalfunctionLogEntry { kind: MalfunctionType::Waves, site: Some(InteractableId(1)), raised: 8., resolved: Some(30.), failed: true },
+            MalfunctionLogEntry { kind: MalfunctionType::Hack, site: Some(InteractableId(0)), raised: 40., resolved: None, failed: false },
+        ]);
+        assert!(app.world().resource::<Malfunction>().malfunction_types == vec![MalfunctionType::Hack]);
+
+        let record = RunRecord::new(7, Difficulty::Normal, 50., RunOutcome::Crashed, score);
+        assert_eq!(record.malfunctions.len(), 3);
+        assert_eq!(record.score, score.breakdown(50., RunOutcome::Crashed).total);
+    }
+
+    #[test]
+    fn log_follows_the_site() {
+        let mut app = headless_app();
+        raise(&mut app, MalfunctionType::Hack, 5.);
+        raise(&mut app, MalfunctionType::Hack, 8.);
+        // the later one is dealt with first
+        let world = app.world_mut();
+        world.resource_mut::<DebreeLevel>().elapsed = 10.;
+        world.resource_mut::<Malfunction>().resolved.push(Resolved { resolved_type: MalfunctionType::Hack, site: InteractableId(1), failed: false });
+        world.run_system_once(resolve_malfunctions).unwrap();
+
+        let log = &app.world().resource::<RunScore>().log;
+        assert_eq!(log[0].resolved, None);
+        assert_eq!(log[1].resolved, Some(10.));
+    }
+
+    #[test]
+    fn leaderboard_sorts_and_filters() {
+        let mut history = RunHistory::default();
+        let run = |score, duration, difficulty| RunRecord {
+            seed: 0,
+            difficulty,
+            duration,
+            outcome: RunOutcome::Crashed,
+            score,
+            malfunctions: vec![],
+        };
+        history.push(run(100, 10., Difficulty::Normal));
+        history.push(run(300, 10., Difficulty::Hard));
+        history.push(run(100, 20., Difficulty::Normal));
+
+        let all: Vec<(i32, f32)> = history.leaderboard(None, 10).iter().map(|r| (r.score, r.duration)).collect();
+        assert_eq!(all, vec![(300, 10.), (100, 20.), (100, 10.)]);
+        assert_eq!(history.leaderboard(Some(Difficulty::Normal), 1)[0].duration, 20.);
+        assert!(history.leaderboard(Some(Difficulty::Story), 10).is_empty());
+
+        for _ in 0..MAX_RUNS {
+            history.push(run(0, 0., Difficulty::Story));
+        }
+        assert_eq!(history.runs.len(), MAX_RUNS);
+        assert!(history.leaderboard(Some(Difficulty::Hard), 10).is_empty());
+    }
+}
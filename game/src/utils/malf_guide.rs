@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use pixel_utils::camera::{PixelCamera, TARGET_HEIGHT, TARGET_WIDTH};
 use std::collections::HashMap;
-use crate::{interactions::components::{InInteractionArray, InteractionTypes}, physics::player::Player, tilemap::light::LIT_OVERLAY_LAYER, utils::debree::{Malfunction, MalfunctionType}};
+use crate::{interactions::components::{InInteractionArray, InteractableId, InteractionTypes}, physics::player::Player, tilemap::light::LIT_OVERLAY_LAYER, utils::{debree::Malfunction, malfunction_defs::{MalfunctionAssets, MalfunctionDefinitions}}};
 
 
 
@@ -25,22 +25,33 @@ struct GuideArrow;
 
 pub fn guide(
     mut cmd: Commands,
-    malf: Query<(&GlobalTransform, &InteractionTypes)>,
+    malf: Query<(Entity, &GlobalTransform, &InteractionTypes, &InteractableId)>,
     cam: Single<&GlobalTransform, With<PixelCamera>>,
     malfunction: Res<Malfunction>,
     in_interaction_array: Res<InInteractionArray>,
-    mut arrows: Local<HashMap<InteractionTypes, Entity>>,
+    // one arrow per interactable, every broken one gets pointed at
+    mut arrows: Local<HashMap<Entity, Entity>>,
     asset_server: Res<AssetServer>,
     player: Single<&GlobalTransform, With<Player>>,
+    malfunction_assets: Option<Res<MalfunctionAssets>>,
+    definitions: Res<Assets<MalfunctionDefinitions>>,
 ){
+    let Some(definitions) = malfunction_assets.and_then(|a| definitions.get(&a.definitions)) else {return;};
     let target_size = Vec2::new(TARGET_WIDTH as f32, TARGET_HEIGHT as f32);
     let i = asset_server.load("pixel/arrow.png");
     let i2 = asset_server.load("pixel/arrow2.png");
-    for (pos, t) in malf.iter() {
-        let malf = t.as_malfunction();
-        if malf == MalfunctionType::NoMalfunction {continue;}
+    // interactables are gone once the map is respawned
+    arrows.retain(|interactable, arrow| {
+        let alive = malf.contains(*interactable);
+        if !alive {
+            cmd.entity(*arrow).despawn();
+        }
+        alive
+    });
+    for (interactable, pos, t, site) in malf.iter() {
+        if definitions.resolved_by(t).is_none() {continue;}
         // let h = &mut *arrows;
-        let e = if let Some(e) = arrows.get(t) {e} else {
+        let e = if let Some(e) = arrows.get(&interactable) {e} else {
             let i = if matches!(t, InteractionTypes::PipePuzzle | InteractionTypes::CollisionMinigame) {i.clone()} else {i2.clone()};
 
             let ne = cmd.spawn((
@@ -50,8 +61,8 @@ pub fn guide(
                 Transform::default(),
                 LIT_OVERLAY_LAYER,
             )).id();
-            arrows.insert(t.clone(), ne);
-            arrows.get(t).unwrap()
+            arrows.insert(interactable, ne);
+            arrows.get(&interactable).unwrap()
         };
         let dir = pos.translation().truncate() - cam.translation().truncate();
         
@@ -65,7 +76,7 @@ pub fn guide(
         // info!("{:?} {} {}", malf,  l < min_att, malfunction.malfunction_types.contains(&malf));
         cmd.entity(*e).insert((
             Transform::from_translation(pos.extend(0.)).with_rotation(Quat::from_rotation_z(rp)),
-            if l < min_att || !malfunction.malfunction_types.contains(&t.as_malfunction()) {
+            if l < min_att || !malfunction.is_broken(*site) {
                 Visibility::Hidden
             } else {
                 Visibility::Inherited
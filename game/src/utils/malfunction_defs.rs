@@ -0,0 +1,173 @@
+use std::{path::Path, time::Duration};
+
+use bevy::{asset::{io::Reader, AssetLoader, LoadContext}, prelude::*};
+use bevy_asset_loader::asset_collection::AssetCollection;
+use serde::Deserialize;
+
+use crate::{interactions::{components::{InteractableId, InteractionTypes}, warning_interface::WarningData}, utils::{debree::{DebreeLevel, GameEndEvent, MalfunctionType}, difficulty::DifficultyProfile, energy::Energy, failure_graph::Downstream, orbits::DebrisField, rng::{GameRng, RngStream}}};
+
+/// Relative to the crate root, for tools reading it from disk
+pub const DEFINITIONS_PATH: &str = "assets/data/station.malfunctions.ron";
+
+#[derive(AssetCollection, Resource)]
+pub struct MalfunctionAssets {
+    #[asset(path = "data/station.malfunctions.ron")]
+    pub definitions: Handle<MalfunctionDefinitions>,
+}
+
+/// Every malfunction kind the station can raise.
+/// Kinds missing from the file are never raised; order matters for seeded runs.
+#[derive(Asset, TypePath, Deserialize, Debug, Clone)]
+pub struct MalfunctionDefinitions {
+    pub malfunctions: Vec<MalfunctionDefinition>,
+}
+
+impl MalfunctionDefinitions {
+    pub fn get(&self, kind: &MalfunctionType) -> Option<&MalfunctionDefinition> {
+        self.malfunctions.iter().find(|d| &d.kind == kind)
+    }
+    pub fn resolved_by(&self, interaction: &InteractionTypes) -> Option<&MalfunctionDefinition> {
+        self.malfunctions.iter().find(|d| &d.resolved_by == interaction)
+    }
+    /// Reads the file directly, for tools that run without the asset server
+    pub fn read(path: &Path) -> Result<Self, String> {
+        std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|data| ron::de::from_str(&data).map_err(|e| e.to_string()))
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct MalfunctionDefinition {
+    pub kind: MalfunctionType,
+    pub warning: WarningDefinition,
+    pub time_to_resolve: f32,
+    pub resolved_by: InteractionTypes,
+    #[serde(default)]
+    pub on_success: Vec<MalfunctionEffect>,
+    #[serde(default)]
+    pub on_failure: Vec<MalfunctionEffect>,
+    /// subsystems strained when this one runs out, see `utils::failure_graph`
+    #[serde(default)]
+    pub downstream: Vec<Downstream>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct WarningDefinition {
+    pub text: String,
+    pub color: WarningColor,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum WarningColor {
+    Red,
+    Yellow,
+}
+
+impl WarningDefinition {
+    pub fn to_warning_data(&self) -> WarningData {
+        WarningData {
+            color: self.color == WarningColor::Yellow,
+            text: self.text.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub enum MalfunctionEffect {
+    /// Shifts `DebreeLevel::const_add`; increases are scaled by the difficulty
+    DebreeConstAdd(f32),
+    /// Multiplies `Energy::generated`
+    EnergyGeneratedMul(f32),
+    /// Adds a temporary load on top of the regular consumers
+    IncreaseConsumption { amount: f32, secs: f32 },
+    /// The object whose close approach raised the malfunction breaks up into this many fragments
+    Fragmentation(u32),
+    /// The object whose close approach raised the malfunction moves its orbit this much away
+    /// from the station's, in station orbit radii, and circularises it
+    Manoeuvre(f32),
+    /// The satellite whose close approach raised the malfunction can be deorbited from the hack console
+    Hijack,
+    EndGame,
+}
+
+impl MalfunctionEffect {
+    /// `strength` scales what success effects give back, lower the later the malfunction was resolved
+    #[allow(clippy::too_many_arguments)]
+    pub fn apply(
+        &self,
+        site: InteractableId,
+        strength: f32,
+        debree_level: &mut DebreeLevel,
+        energy: &mut Energy,
+        field: &mut DebrisField,
+        rng: &mut GameRng,
+        game_end: &mut EventWriter<GameEndEvent>,
+        profile: &DifficultyProfile,
+    ) {
+        match self {
+            MalfunctionEffect::DebreeConstAdd(v) => {
+                let v = if *v > 0. {v * profile.debris_cost_mul} else {v * strength};
+                debree_level.const_add += v;
+            }
+            MalfunctionEffect::EnergyGeneratedMul(v) => {
+                energy.generated *= 1. + (v - 1.) * strength;
+            }
+            MalfunctionEffect::IncreaseConsumption { amount, secs } => {
+                energy.add_consumption(*amount, Duration::from_secs_f32(*secs));
+            }
+            MalfunctionEffect::Fragmentation(fragments) => {
+                if let Some(id) = field.tracked_object(site) {
+                    field.break_up(&[id], debree_level.elapsed, *fragments, rng.stream(RngStream::Debris));
+                }
+            }
+            MalfunctionEffect::Manoeuvre(distance) => {
+                if let Some(id) = field.tracked_object(site) {
+                    field.manoeuvre(id, distance * strength, debree_level.elapsed);
+                }
+            }
+            MalfunctionEffect::Hijack => {
+                if let Some(id) = field.tracked_object(site) {
+                    field.hijack(id);
+                }
+            }
+            MalfunctionEffect::EndGame => {
+                game_end.write(GameEndEvent);
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct MalfunctionDefinitionsLoader;
+
+impl AssetLoader for MalfunctionDefinitionsLoader {
+    type Asset = MalfunctionDefinitions;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["malfunctions.ron"]
+    }
+}
+
+pub fn log_definitions_reload(
+    mut events: EventReader<AssetEvent<MalfunctionDefinitions>>,
+) {
+    for event in events.read() {
+        if let AssetEvent::Modified { .. } = event {
+            info!("Malfunction definitions reloaded");
+        }
+    }
+}
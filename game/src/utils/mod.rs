@@ -6,4 +6,16 @@ pub mod spacial_audio;
 pub mod debree;
 pub mod background;
 pub mod energy;
-pub mod malf_guide;
\ No newline at end of file
+pub mod malf_guide;
+pub mod args;
+pub mod rng;
+pub mod malfunction_defs;
+pub mod storage;
+pub mod save;
+pub mod difficulty;
+pub mod score;
+pub mod history;
+pub mod sim;
+pub mod orbits;
+pub mod failure_graph;
+pub mod severity;
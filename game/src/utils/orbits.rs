@@ -0,0 +1,413 @@
+use std::{collections::BTreeMap, f32::consts::TAU};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{interactions::components::InteractableId, utils::{debree::MalfunctionType, difficulty::DifficultyProfile, rng::SeededRng}};
+
+/// Seconds the station takes to go around the planet.
+/// Distances are in station orbit radii, the station circles at radius 1.
+pub const STATION_PERIOD: f32 = 60.;
+/// Breakups are rolled between objects whose semi-major axes fall in the same shell
+const SHELL_WIDTH: f32 = 0.02;
+/// Breakups per second at which the chain reaction is at 100%
+pub const CASCADE_RATE: f32 = 0.5;
+/// Fragments left by two objects colliding
+pub const BREAKUP_FRAGMENTS: u32 = 8;
+const DEBRIS_KEEP_OUT: f32 = 0.004;
+/// Satellites are large, their approaches are flagged from further away
+const SATELLITE_KEEP_OUT: f32 = 0.015;
+/// Seconds a deorbit burn takes
+pub const DEORBIT_SECS: f32 = 20.;
+/// Extra load on the station while it drives a deorbit burn
+pub const DEORBIT_DRAW: f32 = 30.;
+/// Debris a deorbited satellite drags down with it from its shell
+const DEORBIT_SWEEP: usize = 16;
+
+pub fn station_mean_motion() -> f32 {
+    TAU / STATION_PERIOD
+}
+
+pub fn station_angle(t: f32) -> f32 {
+    station_mean_motion() * t
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrbitalKind {
+    Debris,
+    Satellite,
+}
+
+impl OrbitalKind {
+    /// Malfunction raised when one of these comes close to the station
+    pub fn malfunction(&self) -> MalfunctionType {
+        match self {
+            OrbitalKind::Debris => MalfunctionType::Collision,
+            OrbitalKind::Satellite => MalfunctionType::Hack,
+        }
+    }
+}
+
+/// Raised by predicted close approaches instead of the random roll
+pub fn raised_by_approaches(kind: &MalfunctionType) -> bool {
+    matches!(kind, MalfunctionType::Collision | MalfunctionType::Hack)
+}
+
+/// Stylised 2D orbit: the angle advances at the mean motion of the semi-major axis
+/// and the radius follows the ellipse at that angle.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OrbitalObject {
+    pub id: u32,
+    pub kind: OrbitalKind,
+    pub semi_major: f32,
+    pub eccentricity: f32,
+    /// angle of the periapsis
+    pub periapsis: f32,
+    /// angle at the start of the run
+    pub phase: f32,
+}
+
+impl OrbitalObject {
+    pub fn mean_motion(&self) -> f32 {
+        station_mean_motion() * self.semi_major.powf(-1.5)
+    }
+
+    pub fn angle_at(&self, t: f32) -> f32 {
+        self.phase + self.mean_motion() * t
+    }
+
+    pub fn radius_at_angle(&self, angle: f32) -> f32 {
+        let e = self.eccentricity;
+        self.semi_major * (1. - e * e) / (1. + e * (angle - self.periapsis).cos())
+    }
+
+    fn keep_out(&self) -> f32 {
+        match self.kind {
+            OrbitalKind::Debris => DEBRIS_KEEP_OUT,
+            OrbitalKind::Satellite => SATELLITE_KEEP_OUT,
+        }
+    }
+
+    /// First time after `t`, at most `horizon` seconds later, it passes the station within its keep out distance.
+    /// Only conjunctions are checked, so this is exact for the stylised orbits and cheap.
+    pub fn next_close_approach(&self, t: f32, horizon: f32) -> Option<f32> {
+        let relative = self.mean_motion() - station_mean_motion();
+        if relative.abs() < f32::EPSILON {
+            return None;
+        }
+        let ahead = (self.angle_at(t) - station_angle(t)).rem_euclid(TAU);
+        let mut wait = if relative > 0. {TAU - ahead} else {ahead} / relative.abs();
+        while wait <= horizon {
+            let at = t + wait;
+            if (self.radius_at_angle(station_angle(at)) - 1.).abs() < self.keep_out() {
+                return Some(at);
+            }
+            wait += TAU / relative.abs();
+        }
+        None
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CloseApproach {
+    pub object: u32,
+    /// seconds into the run
+    pub at: f32,
+}
+
+/// Catalogue of everything tracked around the station's orbit.
+/// Objects sharing an altitude shell break each other up into more debris,
+/// so the count grows faster the more there is: a Kessler cascade.
+#[derive(Resource, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DebrisField {
+    pub objects: Vec<OrbitalObject>,
+    next_id: u32,
+    /// objects at the start of the run, empty until populated
+    pub initial_count: usize,
+    pub breakups: u32,
+    /// approaches a malfunction is raised for, until it is resolved
+    pub tracked: Vec<(InteractableId, CloseApproach)>,
+    /// approaches already raised once, until they have passed
+    warned: Vec<CloseApproach>,
+    /// satellites taken over by successful hacks, they can be deorbited
+    #[serde(default)]
+    pub hacked: Vec<u32>,
+    #[serde(default)]
+    pub deorbit: Option<Deorbit>,
+}
+
+/// Burn in progress, the satellite comes down once it has run for [`DEORBIT_SECS`]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Deorbit {
+    pub object: u32,
+    pub remaining: f32,
+}
+
+impl DebrisField {
+    pub fn is_populated(&self) -> bool {
+        self.initial_count > 0
+    }
+
+    pub fn populate(&mut self, profile: &DifficultyProfile, rng: &mut SeededRng) {
+        for _ in 0..profile.initial_debris {
+            let (a, e, periapsis, angle) = (rng.range(0.8, 1.2), rng.range(0., 0.12), rng.range(0., TAU), rng.range(0., TAU));
+            self.spawn(OrbitalKind::Debris, a, e, periapsis, angle, 0.);
+        }
+        // operators keep satellites close to the useful orbits, near the station's
+        for _ in 0..profile.initial_satellites {
+            let (a, e, periapsis, angle) = (rng.range(0.9, 1.1), rng.range(0.03, 0.1), rng.range(0., TAU), rng.range(0., TAU));
+            self.spawn(OrbitalKind::Satellite, a, e, periapsis, angle, 0.);
+        }
+        self.initial_count = self.objects.len().max(1);
+    }
+
+    /// New object at `angle` at time `t`
+    pub fn spawn(&mut self, kind: OrbitalKind, semi_major: f32, eccentricity: f32, periapsis: f32, angle: f32, t: f32) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        let mut object = OrbitalObject { id, kind, semi_major, eccentricity, periapsis, phase: 0. };
+        object.phase = angle - object.mean_motion() * t;
+        self.objects.push(object);
+        id
+    }
+
+    pub fn remove(&mut self, id: u32) -> Option<OrbitalObject> {
+        let index = self.objects.iter().position(|o| o.id == id)?;
+        self.hacked.retain(|h| *h != id);
+        Some(self.objects.remove(index))
+    }
+
+    /// Object indices by altitude shell, in a stable order for seeded runs
+    fn shells(&self) -> BTreeMap<i32, Vec<usize>> {
+        let mut shells: BTreeMap<i32, Vec<usize>> = BTreeMap::new();
+        for (i, object) in self.objects.iter().enumerate() {
+            shells.entry((object.semi_major / SHELL_WIDTH).floor() as i32).or_default().push(i);
+        }
+        shells
+    }
+
+    /// Expected breakups per second, every pair sharing a shell collides at `fragmentation` per second
+    pub fn breakup_rate(&self, fragmentation: f32) -> f32 {
+        self.shells().values()
+            .map(|shell| {
+                let n = shell.len() as f32;
+                fragmentation * n * (n - 1.) / 2.
+            })
+            .sum()
+    }
+
+    /// Rolls breakups over the last `dt` seconds, returns how many happened
+    pub fn step_breakups(&mut self, t: f32, dt: f32, fragmentation: f32, rng: &mut SeededRng) -> u32 {
+        let mut pairs = vec![];
+        for shell in self.shells().values() {
+            let n = shell.len() as f32;
+            if shell.len() < 2 || rng.f32() >= fragmentation * n * (n - 1.) / 2. * dt {
+                continue;
+            }
+            let first = rng.index(shell.len());
+            let mut second = rng.index(shell.len() - 1);
+            if second >= first {
+                second += 1;
+            }
+            pairs.push([self.objects[shell[first]].id, self.objects[shell[second]].id]);
+        }
+        let mut count = 0;
+        for pair in pairs {
+            // the approach behind an active malfunction stays until it is resolved,
+            // and the satellite being burned down is under the station's control
+            if pair.iter().any(|id| self.is_tracked(*id) || self.deorbit.is_some_and(|d| d.object == *id)) {
+                continue;
+            }
+            self.break_up(&pair, t, BREAKUP_FRAGMENTS, rng);
+            count += 1;
+        }
+        count
+    }
+
+    /// Replaces the objects with `fragments` pieces of debris scattered around where the first one is at `t`
+    pub fn break_up(&mut self, ids: &[u32], t: f32, fragments: u32, rng: &mut SeededRng) {
+        let removed: Vec<OrbitalObject> = ids.iter().filter_map(|id| self.remove(*id)).collect();
+        let Some(first) = removed.first() else {
+            return;
+        };
+        let angle = first.angle_at(t);
+        let semi_major = removed.iter().map(|o| o.semi_major).sum::<f32>() / removed.len() as f32;
+        for _ in 0..fragments {
+            let (a, e, periapsis, spread) = (semi_major * rng.range(0.96, 1.04), rng.range(0., 0.1), rng.range(0., TAU), rng.range(-0.05, 0.05));
+            self.spawn(OrbitalKind::Debris, a, e, periapsis, angle + spread, t);
+        }
+        self.breakups += 1;
+    }
+
+    /// Moves the object's orbit `distance` further away from the station's and circularises it
+    pub fn manoeuvre(&mut self, id: u32, distance: f32, t: f32) {
+        let Some(object) = self.objects.iter_mut().find(|o| o.id == id) else {
+            return;
+        };
+        let angle = object.angle_at(t);
+        object.semi_major += distance.copysign(object.semi_major - 1.);
+        object.eccentricity = 0.;
+        object.phase = angle - object.mean_motion() * t;
+    }
+
+    /// Puts a satellite under the station's control
+    pub fn hijack(&mut self, id: u32) {
+        let is_satellite = self.objects.iter().any(|o| o.id == id && o.kind == OrbitalKind::Satellite);
+        if is_satellite && !self.hacked.contains(&id) {
+            self.hacked.push(id);
+        }
+    }
+
+    pub fn hacked_satellites(&self) -> impl Iterator<Item = &OrbitalObject> {
+        self.objects.iter().filter(|o| self.hacked.contains(&o.id))
+    }
+
+    /// Starts burning a hacked satellite down, only one burn runs at a time
+    pub fn start_deorbit(&mut self, id: u32) -> bool {
+        if self.deorbit.is_some() || !self.hacked.contains(&id) {
+            return false;
+        }
+        self.deorbit = Some(Deorbit { object: id, remaining: DEORBIT_SECS });
+        true
+    }
+
+    /// Brings the satellite down through its shell, sweeping the debris closest to its orbit along.
+    /// Returns how many objects left the field, 0 if the satellite is gone already.
+    pub fn deorbit(&mut self, id: u32) -> usize {
+        let Some(satellite) = self.remove(id) else {
+            return 0;
+        };
+        let shell = (satellite.semi_major / SHELL_WIDTH).floor() as i32;
+        let mut swept: Vec<&OrbitalObject> = self.objects.iter()
+            .filter(|o| o.kind == OrbitalKind::Debris && (o.semi_major / SHELL_WIDTH).floor() as i32 == shell && !self.is_tracked(o.id))
+            .collect();
+        swept.sort_by(|a, b| (a.semi_major - satellite.semi_major).abs().total_cmp(&(b.semi_major - satellite.semi_major).abs()));
+        let swept: Vec<u32> = swept.iter().take(DEORBIT_SWEEP).map(|o| o.id).collect();
+        for id in swept.iter() {
+            self.remove(*id);
+        }
+        1 + swept.len()
+    }
+
+    /// Soonest approach between `from` and `to` of an object of `kind` that nothing was raised for yet
+    pub fn next_approach(&self, kind: OrbitalKind, from: f32, to: f32) -> Option<CloseApproach> {
+        self.objects.iter()
+            .filter(|o| o.kind == kind && !self.warned.iter().any(|w| w.object == o.id))
+            .filter_map(|o| o.next_close_approach(from, to - from).map(|at| CloseApproach { object: o.id, at }))
+            .min_by(|a, b| a.at.total_cmp(&b.at))
+    }
+
+    /// Ties the approach to the malfunction raised for it on `site`
+    pub fn track(&mut self, site: InteractableId, approach: CloseApproach) {
+        self.warned.push(approach);
+        self.tracked.push((site, approach));
+    }
+
+    pub fn tracked_object(&self, site: InteractableId) -> Option<u32> {
+        self.tracked.iter().find(|(s, _)| *s == site).map(|(_, a)| a.object)
+    }
+
+    fn is_tracked(&self, id: u32) -> bool {
+        self.tracked.iter().any(|(_, a)| a.object == id)
+    }
+
+    pub fn forget(&mut self, site: InteractableId) {
+        self.tracked.retain(|(s, _)| *s != site);
+    }
+
+    /// Approaches that have passed can be raised again next time around
+    pub fn expire_approaches(&mut self, t: f32) {
+        self.warned.retain(|w| w.at > t);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::difficulty::Difficulty;
+
+    #[test]
+    fn predicted_approach_matches_sampling() {
+        let object = OrbitalObject { id: 0, kind: OrbitalKind::Debris, semi_major: 1.05, eccentricity: 0.08, periapsis: 2., phase: 1. };
+        let mut t = 0.;
+        let mut found = None;
+        while t < 20000. && found.is_none() {
+            found = object.next_close_approach(t, 600.);
+            t += 600.;
+        }
+        let at = found.expect("crossing orbits meet eventually");
+        let distance = |t: f32| {
+            let (o, s) = (object.angle_at(t), station_angle(t));
+            (Vec2::from_angle(o) * object.radius_at_angle(o)).distance(Vec2::from_angle(s))
+        };
+        assert!(distance(at) < DEBRIS_KEEP_OUT * 1.01, "{}", distance(at));
+        // nothing closer was skipped on the way, the closest point can be a moment off the conjunction
+        let mut s = at - 600.;
+        while s < at - 2. {
+            assert!(distance(s) > DEBRIS_KEEP_OUT * 0.9, "missed an approach at {}", s);
+            s += 0.01;
+        }
+    }
+
+    #[test]
+    fn breakups_cascade() {
+        let profile = DifficultyProfile::preset(Difficulty::Hard);
+        let mut rng = SeededRng::new(7);
+        let mut field = DebrisField::default();
+        field.populate(&profile, &mut rng);
+        let start_rate = field.breakup_rate(profile.fragmentation);
+
+        let mut per_minute = vec![];
+        for minute in 0..40 {
+            let before = field.breakups;
+            for s in 0..60 {
+                field.step_breakups((minute * 60 + s) as f32, 1., profile.fragmentation, &mut rng);
+            }
+            per_minute.push(field.breakups - before);
+        }
+        assert!(field.objects.len() > field.initial_count);
+        assert!(field.breakup_rate(profile.fragmentation) > start_rate * 2.);
+        let early: u32 = per_minute[..10].iter().sum();
+        let late: u32 = per_minute[30..].iter().sum();
+        assert!(late > early, "{:?}", per_minute);
+    }
+
+    #[test]
+    fn deorbit_empties_the_shell() {
+        let profile = DifficultyProfile::preset(Difficulty::Normal);
+        let mut field = DebrisField::default();
+        field.populate(&profile, &mut SeededRng::new(3));
+        let satellite = field.objects.iter().find(|o| o.kind == OrbitalKind::Satellite).unwrap().id;
+        assert!(!field.start_deorbit(satellite), "only hacked satellites can be deorbited");
+
+        field.hijack(satellite);
+        assert!(field.start_deorbit(satellite));
+        let (count, rate) = (field.objects.len(), field.breakup_rate(profile.fragmentation));
+        let removed = field.deorbit(satellite);
+        assert!(removed > 1);
+        assert_eq!(field.objects.len(), count - removed);
+        assert!(field.breakup_rate(profile.fragmentation) < rate);
+        assert!(field.hacked.is_empty());
+    }
+
+    #[test]
+    fn burning_satellite_breaking_up_removes_nothing() {
+        let profile = DifficultyProfile::preset(Difficulty::Hard);
+        let mut rng = SeededRng::new(5);
+        let mut field = DebrisField::default();
+        field.populate(&profile, &mut rng);
+        let satellite = field.objects.iter().find(|o| o.kind == OrbitalKind::Satellite).unwrap().id;
+        field.hijack(satellite);
+        assert!(field.start_deorbit(satellite));
+
+        let breakups = (0..60).map(|s| field.step_breakups(s as f32, 1., 1., &mut rng)).sum::<u32>();
+        assert!(breakups > 0);
+        assert!(field.objects.iter().any(|o| o.id == satellite), "breakups spare the burning satellite");
+
+        // a malfunction can still blow it up mid-burn
+        field.break_up(&[satellite], 60., BREAKUP_FRAGMENTS, &mut rng);
+        let count = field.objects.len();
+        assert_eq!(field.deorbit(satellite), 0);
+        assert_eq!(field.objects.len(), count);
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::args::launch_arg;
+
+pub struct GameRngPlugin;
+
+impl Plugin for GameRngPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(GameRng::new(run_seed()));
+    }
+}
+
+/// Seed for a new run: the one passed on launch, so restarts replay it, or a random one
+pub fn run_seed() -> u64 {
+    let seed = seed_from_env().unwrap_or_else(|| getrandom::u64().unwrap());
+    info!("Run seed: {}", seed);
+    seed
+}
+
+/// Independent random streams, one per subsystem.
+/// Drawing from one stream never shifts the sequence of another,
+/// so e.g. opening the wires panel twice does not change the malfunction schedule.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RngStream {
+    Malfunctions,
+    Waves,
+    Collision,
+    Hack,
+    Pipes,
+    Wires,
+    /// breakups and fragments of the debris field
+    Debris,
+    /// scripted player of the headless simulation
+    Policy,
+}
+
+impl RngStream {
+    fn salt(&self) -> u64 {
+        match self {
+            RngStream::Malfunctions => 0x6d61_6c66,
+            RngStream::Waves => 0x7761_7665,
+            RngStream::Collision => 0x636f_6c6c,
+            RngStream::Hack => 0x6861_636b,
+            RngStream::Pipes => 0x7069_7065,
+            RngStream::Wires => 0x7769_7265,
+            RngStream::Debris => 0x6465_6272,
+            RngStream::Policy => 0x706f_6c69,
+        }
+    }
+}
+
+/// Small splitmix64 generator. Not cryptographic, but stable across platforms,
+/// which is all a replayable run needs.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    pub fn u32(&mut self) -> u32 {
+        (self.u64() >> 32) as u32
+    }
+
+    /// Uniform in [0, 1)
+    pub fn f32(&mut self) -> f32 {
+        (self.u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    /// Uniform in [mi, ma)
+    pub fn range(&mut self, mi: f32, ma: f32) -> f32 {
+        mi + self.f32() * (ma - mi)
+    }
+
+    /// Uniform index in [0, len)
+    pub fn index(&mut self, len: usize) -> usize {
+        (self.f32() * len as f32) as usize % len.max(1)
+    }
+
+    /// Index picked in proportion to its weight, the same as `index` when the weights are equal
+    pub fn weighted(&mut self, weights: &[f32]) -> usize {
+        let total: f32 = weights.iter().sum();
+        let mut x = self.f32() * total;
+        for (i, w) in weights.iter().enumerate() {
+            if x < *w {
+                return i;
+            }
+            x -= w;
+        }
+        weights.len().saturating_sub(1)
+    }
+}
+
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+pub struct GameRng {
+    seed: u64,
+    streams: HashMap<RngStream, SeededRng>,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            streams: HashMap::new(),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn stream(&mut self, stream: RngStream) -> &mut SeededRng {
+        let seed = self.seed;
+        self.streams.entry(stream).or_insert_with(|| {
+            // mix the salt through one round so neighbouring seeds don't share streams
+            SeededRng::new(SeededRng::new(seed ^ stream.salt()).u64())
+        })
+    }
+}
+
+fn seed_from_env() -> Option<u64> {
+    launch_arg("seed").and_then(|v| parse_seed(&v))
+}
+
+pub fn parse_seed(s: &str) -> Option<u64> {
+    let s = s.trim();
+    if let Some(hex) = s.strip_prefix("0x") {
+        return u64::from_str_radix(hex, 16).ok();
+    }
+    s.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STREAMS: [RngStream; 8] = [
+        RngStream::Malfunctions, RngStream::Waves, RngStream::Collision, RngStream::Hack,
+        RngStream::Pipes, RngStream::Wires, RngStream::Debris, RngStream::Policy,
+    ];
+
+    fn draw(rng: &mut GameRng, stream: RngStream) -> Vec<u64> {
+        (0..16).map(|_| rng.stream(stream).u64()).collect()
+    }
+
+    #[test]
+    fn same_seed_replays_every_stream() {
+        let mut a = GameRng::new(42);
+        let mut b = GameRng::new(42);
+        // streams are independent, drawing them in another order doesn't change what each one gives
+        let forward: Vec<_> = STREAMS.iter().map(|s| draw(&mut a, *s)).collect();
+        let mut backward: Vec<_> = STREAMS.iter().rev().map(|s| draw(&mut b, *s)).collect();
+        backward.reverse();
+        assert_eq!(forward, backward);
+
+        let other: Vec<_> = STREAMS.iter().map(|s| draw(&mut GameRng::new(43), *s)).collect();
+        for (same, different) in forward.iter().zip(other.iter()) {
+            assert_ne!(same, different);
+        }
+        for (i, first) in forward.iter().enumerate() {
+            assert!(forward[i + 1..].iter().all(|s| s != first), "streams share a sequence");
+        }
+    }
+}
@@ -0,0 +1,412 @@
+use std::{collections::{HashMap, VecDeque}, time::Duration};
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::Velocity;
+use serde::{Deserialize, Serialize};
+
+use crate::{core::states::{AppLoadingAssetsSubState, GlobalAppState, OnGame, PreGameTasks}, interactions::{collision_minigame::CollisionMinigameConsts, components::InteractableId, hack_minigame::HackGrid, pipe_puzzle::PipeMinigame, warning_interface::WarningData, wave_modulator::WaveModulatorConsts, wires_minigame::WireMinigame}, physics::player::{enter_spacewalk, Player, PlayerConstants}, utils::{debree::{DebreeLevel, Malfunction, MalfunctionType}, difficulty::{Difficulty, DifficultyProfile}, energy::{Energy, PowerRouting, PowerState}, failure_graph::Strains, orbits::DebrisField, rng::GameRng, score::RunScore, storage}, LightFlicker};
+
+const SAVE_KEY: &str = "run";
+const RESTORE_TASK: &str = "run_restore";
+const AUTOSAVE_SECS: f32 = 10.;
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(Startup, read_snapshot)
+        .add_systems(Update, restore_resources.run_if(in_state(AppLoadingAssetsSubState::Done)))
+        .add_systems(OnGame, restore_player.after(crate::spawn))
+        .add_systems(Update, restore_sites.run_if(resource_exists::<PendingSiteRestore>))
+        .add_systems(Update, autosave.run_if(in_state(GlobalAppState::InGame)))
+        .add_systems(OnEnter(GlobalAppState::Defeat), clear_snapshot)
+        .add_systems(OnEnter(GlobalAppState::Victory), clear_snapshot);
+    }
+}
+
+/// Everything needed to put a run back where it was.
+/// Only the state that can't be rebuilt from assets is stored.
+#[derive(Serialize, Deserialize)]
+pub struct RunSnapshot {
+    pub rng: GameRng,
+    pub difficulty: Difficulty,
+    pub debree: DebreeSnapshot,
+    pub energy: EnergySnapshot,
+    pub malfunctions: Vec<MalfunctionSnapshot>,
+    /// puzzles by the interactable they are on
+    pub pipes: Vec<(InteractableId, PipeMinigame)>,
+    pub hack: Vec<(InteractableId, HackGrid)>,
+    pub wires: Vec<(InteractableId, WiresSnapshot)>,
+    pub waves: WaveModulatorConsts,
+    pub collision: CollisionSnapshot,
+    pub player: PlayerSnapshot,
+    #[serde(default)]
+    pub score: RunScore,
+    /// snapshots from before the debris field was simulated get a fresh one
+    #[serde(default)]
+    pub orbits: DebrisField,
+    #[serde(default)]
+    pub strains: Strains,
+    /// snapshots from before power was saved come back fully powered
+    #[serde(default)]
+    pub power: PowerSnapshot,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DebreeSnapshot {
+    pub const_add: f32,
+    pub elapsed: f32,
+    pub chain_reaction_graph: VecDeque<f32>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EnergySnapshot {
+    pub generated: f32,
+    pub routing: PowerRouting,
+    pub increase_consumption: f32,
+    pub increase_consumption_secs: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PowerSnapshot {
+    pub lamps: bool,
+    pub gravity: bool,
+    pub engine: bool,
+    /// seconds the engine has been holding orbit without power
+    pub engine_countdown: f32,
+}
+
+impl Default for PowerSnapshot {
+    fn default() -> Self {
+        Self { lamps: true, gravity: true, engine: true, engine_countdown: 0. }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct MalfunctionSnapshot {
+    pub kind: MalfunctionType,
+    pub site: InteractableId,
+    pub duration: f32,
+    pub elapsed: f32,
+    pub warning_text: String,
+    pub warning_yellow: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct WiresSnapshot {
+    pub task: HashMap<usize, usize>,
+    pub connected: HashMap<usize, usize>,
+    pub colors: HashMap<usize, [f32; 4]>,
+    pub allow_unordered: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CollisionSnapshot {
+    pub consts1: [f32; 4],
+    pub consts2: [Vec<f32>; 2],
+    pub is_loaded: bool,
+    /// seconds since the minigame was generated; `start_time` itself is tied to app uptime
+    pub age: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PlayerSnapshot {
+    pub translation: [f32; 3],
+    pub rotation: [f32; 4],
+    /// dancing and climbing are transient, those resume as regular walking
+    pub spacewalk: bool,
+}
+
+/// Snapshot read on boot, waiting for the world to be ready.
+#[derive(Resource)]
+pub struct PendingRestore(pub RunSnapshot);
+
+/// Player part of the snapshot, applied once the player is spawned.
+#[derive(Resource)]
+pub struct PendingPlayerRestore(pub PlayerSnapshot);
+
+/// Puzzle part of the snapshot, applied to each interactable once the map has spawned it.
+#[derive(Resource, Default)]
+pub struct PendingSiteRestore {
+    pub pipes: HashMap<InteractableId, PipeMinigame>,
+    pub hack: HashMap<InteractableId, HackGrid>,
+    pub wires: HashMap<InteractableId, WiresSnapshot>,
+}
+
+impl PendingSiteRestore {
+    fn is_empty(&self) -> bool {
+        self.pipes.is_empty() && self.hack.is_empty() && self.wires.is_empty()
+    }
+}
+
+fn wires_snapshot(wires: &WireMinigame) -> WiresSnapshot {
+    WiresSnapshot {
+        task: wires.task.clone(),
+        connected: wires.connected.clone(),
+        colors: wires.colors.iter()
+            .map(|(k, c)| (*k, c.to_srgba().to_f32_array()))
+            .collect(),
+        allow_unordered: wires.allow_unordered,
+    }
+}
+
+fn restore_wires(wires: &mut WireMinigame, s: &WiresSnapshot) {
+    wires.task = s.task.clone();
+    wires.connected = s.connected.clone();
+    wires.colors = s.colors.iter()
+        .map(|(k, c)| (*k, Color::srgba(c[0], c[1], c[2], c[3])))
+        .collect();
+    wires.allow_unordered = s.allow_unordered;
+}
+
+pub fn read_snapshot(
+    mut cmd: Commands,
+    mut tasks: ResMut<PreGameTasks>,
+) {
+    let Some(data) = storage::read(SAVE_KEY) else {
+        return;
+    };
+    match ron::de::from_str::<RunSnapshot>(&data) {
+        Ok(snapshot) => {
+            info!("Resuming saved run, seed {}", snapshot.rng.seed());
+            cmd.insert_resource(PendingRestore(snapshot));
+            tasks.add(RESTORE_TASK.to_string());
+        }
+        Err(e) => {
+            warn!("Discarding unreadable run snapshot: {}", e);
+            storage::remove(SAVE_KEY);
+        }
+    }
+}
+
+pub fn restore_resources(
+    mut cmd: Commands,
+    pending: Option<Res<PendingRestore>>,
+    mut tasks: ResMut<PreGameTasks>,
+    time: Res<Time>,
+    mut rng: ResMut<GameRng>,
+    mut profile: ResMut<DifficultyProfile>,
+    mut debree_level: ResMut<DebreeLevel>,
+    mut energy: ResMut<Energy>,
+    mut malfunction: ResMut<Malfunction>,
+    mut waves: ResMut<WaveModulatorConsts>,
+    mut collision: ResMut<CollisionMinigameConsts>,
+    mut score: ResMut<RunScore>,
+    mut field: ResMut<DebrisField>,
+    mut strains: ResMut<Strains>,
+    (mut power, mut flicker): (ResMut<PowerState>, ResMut<LightFlicker>),
+) {
+    let Some(pending) = pending else {
+        return;
+    };
+    let s = &pending.0;
+
+    *rng = s.rng.clone();
+    *profile = DifficultyProfile::preset(s.difficulty);
+
+    debree_level.const_add = s.debree.const_add;
+    debree_level.elapsed = s.debree.elapsed;
+    debree_level.chain_reaction_graph = s.debree.chain_reaction_graph.clone();
+
+    energy.generated = s.energy.generated;
+    energy.routing = s.energy.routing;
+    energy.increase_consumption = (
+        s.energy.increase_consumption,
+        Duration::from_secs_f32(s.energy.increase_consumption_secs),
+    );
+    power.lamps = s.power.lamps;
+    power.gravity = s.power.gravity;
+    power.engine = s.power.engine;
+    power.engine_countdown.set_elapsed(Duration::from_secs_f32(s.power.engine_countdown));
+    // the power events are long gone by the time the run continues
+    flicker.brownout = !s.power.lamps;
+
+    malfunction.malfunction_types.clear();
+    malfunction.malfunction_sites.clear();
+    malfunction.malfunction_timers.clear();
+    malfunction.warning_data.clear();
+    for m in s.malfunctions.iter() {
+        let mut timer = Timer::from_seconds(m.duration, TimerMode::Once);
+        timer.set_elapsed(Duration::from_secs_f32(m.elapsed));
+        malfunction.malfunction_types.push(m.kind.clone());
+        malfunction.malfunction_sites.push(m.site);
+        malfunction.malfunction_timers.push(timer);
+        malfunction.warning_data.push(WarningData {
+            color: m.warning_yellow,
+            text: m.warning_text.clone(),
+        });
+    }
+    malfunction.in_progress = !malfunction.malfunction_types.is_empty();
+    malfunction.added_new_malfunction = malfunction.in_progress;
+
+    *waves = s.waves.clone();
+    *score = s.score.clone();
+    *field = s.orbits.clone();
+    *strains = s.strains.clone();
+
+    cmd.insert_resource(PendingSiteRestore {
+        pipes: s.pipes.iter().cloned().collect(),
+        hack: s.hack.iter().cloned().collect(),
+        wires: s.wires.iter().map(|(site, w)| (*site, WiresSnapshot {
+            task: w.task.clone(),
+            connected: w.connected.clone(),
+            colors: w.colors.clone(),
+            allow_unordered: w.allow_unordered,
+        })).collect(),
+    });
+
+    collision.consts1 = s.collision.consts1;
+    collision.consts2 = s.collision.consts2.clone();
+    collision.is_loaded = s.collision.is_loaded;
+    collision.start_time = time.elapsed_wrapped().saturating_sub(Duration::from_secs_f32(s.collision.age));
+
+    cmd.insert_resource(PendingPlayerRestore(PlayerSnapshot {
+        translation: s.player.translation,
+        rotation: s.player.rotation,
+        spacewalk: s.player.spacewalk,
+    }));
+    cmd.remove_resource::<PendingRestore>();
+    tasks.done(RESTORE_TASK.to_string());
+}
+
+pub fn restore_player(
+    mut cmd: Commands,
+    pending: Option<Res<PendingPlayerRestore>>,
+    player: Single<(Entity, &mut Player, &mut Velocity)>,
+    mut consts: ResMut<PlayerConstants>,
+) {
+    let Some(pending) = pending else {
+        return;
+    };
+    let (e, mut p, mut vel) = player.into_inner();
+    cmd.entity(e).insert(
+        Transform::from_translation(Vec3::from_array(pending.0.translation))
+            .with_rotation(Quat::from_array(pending.0.rotation))
+    );
+    if pending.0.spacewalk {
+        enter_spacewalk(&mut cmd, e, &mut p, &mut consts, &mut vel);
+    }
+    cmd.remove_resource::<PendingPlayerRestore>();
+}
+
+pub fn restore_sites(
+    mut cmd: Commands,
+    mut pending: ResMut<PendingSiteRestore>,
+    mut sites: Query<(&InteractableId, Option<&mut PipeMinigame>, Option<&mut HackGrid>, Option<&mut WireMinigame>)>,
+) {
+    if sites.is_empty() {
+        return;
+    }
+    for (site, pipes, hack, wires) in sites.iter_mut() {
+        if let (Some(mut pipes), Some(saved)) = (pipes, pending.pipes.remove(site)) {
+            *pipes = saved;
+        }
+        if let (Some(mut hack), Some(saved)) = (hack, pending.hack.remove(site)) {
+            *hack = saved;
+        }
+        if let (Some(mut wires), Some(saved)) = (wires, pending.wires.remove(site)) {
+            restore_wires(&mut wires, &saved);
+        }
+    }
+    // the map spawns every site at once, whatever is left is gone from it
+    if !pending.is_empty() {
+        let mut missing: Vec<_> = pending.pipes.keys().chain(pending.hack.keys()).chain(pending.wires.keys()).collect();
+        missing.sort();
+        missing.dedup();
+        warn!("saved puzzles on {:?} have no site on the map, dropping them", missing);
+    }
+    cmd.remove_resource::<PendingSiteRestore>();
+}
+
+pub fn autosave(
+    mut timer: Local<Option<Timer>>,
+    time: Res<Time>,
+    rng: Res<GameRng>,
+    profile: Res<DifficultyProfile>,
+    debree_level: Res<DebreeLevel>,
+    energy: Res<Energy>,
+    malfunction: Res<Malfunction>,
+    sites: Query<(&InteractableId, Option<&PipeMinigame>, Option<&HackGrid>, Option<&WireMinigame>)>,
+    waves: Res<WaveModulatorConsts>,
+    collision: Res<CollisionMinigameConsts>,
+    score: Res<RunScore>,
+    field: Res<DebrisField>,
+    strains: Res<Strains>,
+    power: Res<PowerState>,
+    player: Single<(&Transform, &Player)>,
+) {
+    let timer = timer.get_or_insert_with(|| Timer::from_seconds(AUTOSAVE_SECS, TimerMode::Repeating));
+    timer.tick(time.delta());
+    if !timer.just_finished() {
+        return;
+    }
+    let (transform, p) = *player;
+    let mut sites: Vec<_> = sites.iter().collect();
+    sites.sort_by_key(|(site, ..)| **site);
+    let snapshot = RunSnapshot {
+        rng: rng.clone(),
+        difficulty: profile.difficulty,
+        debree: DebreeSnapshot {
+            const_add: debree_level.const_add,
+            elapsed: debree_level.elapsed,
+            chain_reaction_graph: debree_level.chain_reaction_graph.clone(),
+        },
+        energy: EnergySnapshot {
+            generated: energy.generated,
+            routing: energy.routing,
+            increase_consumption: energy.increase_consumption.0,
+            increase_consumption_secs: energy.increase_consumption.1.as_secs_f32(),
+        },
+        power: PowerSnapshot {
+            lamps: power.lamps,
+            gravity: power.gravity,
+            engine: power.engine,
+            engine_countdown: power.engine_countdown.elapsed_secs(),
+        },
+        malfunctions: malfunction.malfunction_types.iter()
+            .zip(malfunction.malfunction_sites.iter())
+            .zip(malfunction.malfunction_timers.iter())
+            .zip(malfunction.warning_data.iter())
+            .map(|(((kind, site), timer), warning)| MalfunctionSnapshot {
+                kind: kind.clone(),
+                site: *site,
+                duration: timer.duration().as_secs_f32(),
+                elapsed: timer.elapsed_secs(),
+                warning_text: warning.text.clone(),
+                warning_yellow: warning.color,
+            })
+            .collect(),
+        pipes: sites.iter().filter_map(|(site, pipes, ..)| Some((**site, (*pipes)?.clone()))).collect(),
+        hack: sites.iter().filter_map(|(site, _, hack, _)| Some((**site, (*hack)?.clone()))).collect(),
+        wires: sites.iter().filter_map(|(site, .., wires)| Some((**site, wires_snapshot((*wires)?)))).collect(),
+        waves: waves.clone(),
+        collision: CollisionSnapshot {
+            consts1: collision.consts1,
+            consts2: collision.consts2.clone(),
+            is_loaded: collision.is_loaded,
+            age: (time.elapsed_wrapped().saturating_sub(collision.start_time)).as_secs_f32(),
+        },
+        player: PlayerSnapshot {
+            translation: transform.translation.to_array(),
+            rotation: transform.rotation.to_array(),
+            spacewalk: p.is_spacewalking(),
+        },
+        score: score.clone(),
+        orbits: field.clone(),
+        strains: strains.clone(),
+    };
+    match ron::ser::to_string_pretty(&snapshot, ron::ser::PrettyConfig::default()) {
+        Ok(data) => {
+            if let Err(e) = storage::write(SAVE_KEY, &data) {
+                warn!("Failed to save run: {}", e);
+            }
+        }
+        Err(e) => warn!("Failed to serialize run: {}", e),
+    }
+}
+
+/// A lost run can't be resumed
+pub fn clear_snapshot() {
+    storage::remove(SAVE_KEY);
+}
@@ -0,0 +1,169 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{core::states::GlobalAppState, interactions::components::InteractableId, utils::{debree::{DebreeLevel, MalfunctionType, Resolved}, difficulty::DifficultyProfile, energy::PowerState}};
+
+pub struct ScorePlugin;
+
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(RunScore::default())
+        .insert_resource(RunOutcome::Crashed)
+        .add_systems(Update, (track_power, check_victory).run_if(in_state(GlobalAppState::InGame)));
+    }
+}
+
+const RESOLVED_POINTS: i32 = 100;
+const FAILED_POINTS: i32 = -50;
+const POINTS_PER_SECOND: f32 = 1.;
+/// awarded in full when every subsystem stayed powered the whole run
+const EFFICIENCY_POINTS: f32 = 500.;
+const VICTORY_POINTS: i32 = 1000;
+
+#[derive(Default, Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct MalfunctionTally {
+    pub resolved: u32,
+    pub failed: u32,
+}
+
+/// One raised malfunction, times are seconds into the run
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MalfunctionLogEntry {
+    pub kind: MalfunctionType,
+    /// interactable it was raised on, `None` in runs recorded before sites were logged
+    #[serde(default)]
+    pub site: Option<InteractableId>,
+    pub raised: f32,
+    /// `None` while the malfunction is still active
+    pub resolved: Option<f32>,
+    pub failed: bool,
+}
+
+/// Everything the end screen needs, counted while the run goes on.
+#[derive(Resource, Default, Clone, Debug, Serialize, Deserialize)]
+pub struct RunScore {
+    /// in the order kinds were first resolved or failed
+    pub tallies: Vec<(MalfunctionType, MalfunctionTally)>,
+    /// seconds lamps, gravity and engine were all powered
+    pub powered_secs: f32,
+    #[serde(default)]
+    pub log: Vec<MalfunctionLogEntry>,
+}
+
+impl RunScore {
+    pub fn raise(&mut self, kind: MalfunctionType, site: InteractableId, elapsed: f32) {
+        self.log.push(MalfunctionLogEntry {
+            kind,
+            site: Some(site),
+            raised: elapsed,
+            resolved: None,
+            failed: false,
+        });
+    }
+
+    pub fn record(&mut self, resolved: &Resolved, elapsed: f32) {
+        if let Some(entry) = self.log.iter_mut()
+            .find(|e| e.resolved.is_none() && e.kind == resolved.resolved_type && e.site == Some(resolved.site))
+        {
+            entry.resolved = Some(elapsed);
+            entry.failed = resolved.failed;
+        }
+        let idx = match self.tallies.iter().position(|(k, _)| k == &resolved.resolved_type) {
+            Some(idx) => idx,
+            None => {
+                self.tallies.push((resolved.resolved_type.clone(), MalfunctionTally::default()));
+                self.tallies.len() - 1
+            }
+        };
+        let tally = &mut self.tallies[idx].1;
+        if resolved.failed {
+            tally.failed += 1;
+        } else {
+            tally.resolved += 1;
+        }
+    }
+
+    pub fn resolved(&self) -> u32 {
+        self.tallies.iter().map(|(_, t)| t.resolved).sum()
+    }
+
+    pub fn failed(&self) -> u32 {
+        self.tallies.iter().map(|(_, t)| t.failed).sum()
+    }
+
+    /// 0..=1, share of the run with every subsystem powered
+    pub fn efficiency(&self, survived: f32) -> f32 {
+        if survived <= 0. {
+            return 1.;
+        }
+        (self.powered_secs / survived).clamp(0., 1.)
+    }
+
+    pub fn breakdown(&self, survived: f32, outcome: RunOutcome) -> ScoreBreakdown {
+        let malfunctions = self.resolved() as i32 * RESOLVED_POINTS + self.failed() as i32 * FAILED_POINTS;
+        let time = (survived * POINTS_PER_SECOND) as i32;
+        let efficiency = (self.efficiency(survived) * EFFICIENCY_POINTS) as i32;
+        let victory = if outcome == RunOutcome::Survived {VICTORY_POINTS} else {0};
+        ScoreBreakdown {
+            malfunctions,
+            time,
+            efficiency,
+            victory,
+            total: malfunctions + time + efficiency + victory,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ScoreBreakdown {
+    pub malfunctions: i32,
+    pub time: i32,
+    pub efficiency: i32,
+    pub victory: i32,
+    pub total: i32,
+}
+
+/// How the last run ended, set right before leaving `InGame`
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RunOutcome {
+    Survived,
+    /// a malfunction or the engine countdown took the station down
+    Crashed,
+    ChainReaction,
+}
+
+impl RunOutcome {
+    pub fn title(&self) -> &'static str {
+        match self {
+            RunOutcome::Survived => "THE STATION HELD ITS ORBIT",
+            RunOutcome::Crashed => "YOUR SPACESHIP CRASHED INTO THE DEBRIS...",
+            RunOutcome::ChainReaction => "THE CHAIN REACTION TORE THE ORBIT APART...",
+        }
+    }
+}
+
+pub fn track_power(
+    time: Res<Time>,
+    power: Res<PowerState>,
+    mut score: ResMut<RunScore>,
+) {
+    if power.lamps && power.gravity && power.engine {
+        score.powered_secs += time.delta_secs();
+    }
+}
+
+pub fn check_victory(
+    debree_level: Res<DebreeLevel>,
+    profile: Res<DifficultyProfile>,
+    mut outcome: ResMut<RunOutcome>,
+    mut next_state: ResMut<NextState<GlobalAppState>>,
+) {
+    let Some(survive_secs) = profile.survive_secs else {
+        return;
+    };
+    if debree_level.elapsed >= survive_secs {
+        *outcome = RunOutcome::Survived;
+        next_state.set(GlobalAppState::Victory);
+    }
+}
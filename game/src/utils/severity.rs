@@ -0,0 +1,193 @@
+// Malfunctions escalate as their timers run down: warning -> critical -> failing.
+// The stage is read off the timer, so saved runs pick it up without storing it.
+// Every stage has its own alarm pattern, light tint, extra energy drain and puzzle parameters,
+// and resolving a malfunction late weakens what it gives back, see `MalfunctionEffect::apply`.
+
+use bevy::{audio::{PlaybackMode, Volume}, prelude::*};
+
+use crate::{interactions::{components::InteractableId, pipe_puzzle::PipeMinigame}, utils::{debree::{Malfunction, MalfunctionType}, energy::Energy, rng::{GameRng, RngStream}}};
+
+/// Share of the time to resolve left when a malfunction turns critical
+const CRITICAL_LEFT: f32 = 0.5;
+/// Share of the time to resolve left when a malfunction starts failing
+const FAILING_LEFT: f32 = 0.2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Stage {
+    Warning,
+    Critical,
+    Failing,
+}
+
+impl Stage {
+    pub fn of(timer: &Timer) -> Self {
+        Self::at(timer.duration().as_secs_f32(), timer.elapsed_secs())
+    }
+
+    pub fn at(duration: f32, elapsed: f32) -> Self {
+        let left = if duration > 0. {1. - elapsed / duration} else {0.};
+        if left <= FAILING_LEFT {
+            Stage::Failing
+        } else if left <= CRITICAL_LEFT {
+            Stage::Critical
+        } else {
+            Stage::Warning
+        }
+    }
+
+    /// Extra load on the station for every malfunction in this stage
+    pub fn drain(&self) -> f32 {
+        match self {
+            Stage::Warning => 0.,
+            Stage::Critical => 5.,
+            Stage::Failing => 12.,
+        }
+    }
+
+    /// How the alarm speakers sound the stage, failing keeps them going until it's dealt with
+    pub fn alarm(&self) -> PlaybackSettings {
+        let (mode, volume, speed) = match self {
+            Stage::Warning => (PlaybackMode::Remove, 0.2, 1.),
+            Stage::Critical => (PlaybackMode::Remove, 0.3, 1.3),
+            Stage::Failing => (PlaybackMode::Loop, 0.4, 1.6),
+        };
+        PlaybackSettings {
+            mode,
+            volume: Volume::Linear(volume),
+            speed,
+            paused: false,
+            muted: false,
+            spatial: true,
+            spatial_scale: None,
+        }
+    }
+
+    /// Colour the station lights pulse towards and how fast, in Hz
+    pub fn light_tint(&self) -> Option<(Vec3, f32)> {
+        match self {
+            Stage::Warning => None,
+            Stage::Critical => Some((vec3(1., 0.6, 0.2), 0.5)),
+            Stage::Failing => Some((vec3(1., 0.15, 0.1), 1.5)),
+        }
+    }
+
+    /// Pipes knocked out of place when the engine reaches the stage
+    pub fn jolted_pipes(&self) -> usize {
+        match self {
+            Stage::Warning => 0,
+            Stage::Critical => 2,
+            Stage::Failing => 4,
+        }
+    }
+
+    /// Sequences a hack can be tried with before it fails
+    pub fn hack_attempts(&self) -> u32 {
+        match self {
+            Stage::Warning => 3,
+            Stage::Critical => 2,
+            Stage::Failing => 1,
+        }
+    }
+
+    /// Wires to reconnect, at most one per socket pair of the wires panel
+    pub fn wires(&self) -> usize {
+        match self {
+            Stage::Warning => 3,
+            Stage::Critical => 4,
+            Stage::Failing => 5,
+        }
+    }
+
+    /// How many spinny states each wave knob may be off by and still count as synchronised
+    pub fn wave_tolerance(&self) -> usize {
+        match self {
+            Stage::Warning => 2,
+            Stage::Critical => 1,
+            Stage::Failing => 0,
+        }
+    }
+
+    /// Multiplies what an avoidance manoeuvre costs, the later the burn the bigger it has to be
+    pub fn manoeuvre_cost_mul(&self) -> f32 {
+        match self {
+            Stage::Warning => 1.,
+            Stage::Critical => 1.5,
+            Stage::Failing => 2.5,
+        }
+    }
+
+    /// How much of the success effects a malfunction resolved in this stage gives
+    pub fn outcome_mul(&self) -> f32 {
+        match self {
+            Stage::Warning => 1.,
+            Stage::Critical => 0.6,
+            Stage::Failing => 0.3,
+        }
+    }
+}
+
+/// A malfunction moved to a later stage
+#[derive(Event, Clone, Copy, Debug)]
+pub struct StageChanged {
+    pub stage: Stage,
+}
+
+/// Runs after `tick_malfunctions`, a stage is entered on the step its timer crosses into it
+pub fn escalate_malfunctions(
+    time: Res<Time>,
+    malfunction: Res<Malfunction>,
+    mut energy: ResMut<Energy>,
+    mut pipe_minigames: Query<(&InteractableId, &mut PipeMinigame)>,
+    mut rng: ResMut<GameRng>,
+    mut changed: EventWriter<StageChanged>,
+) {
+    let dt = time.delta_secs();
+    let mut drain = 0.;
+    for ((kind, site), timer) in malfunction.malfunction_types.iter()
+        .zip(malfunction.malfunction_sites.iter())
+        .zip(malfunction.malfunction_timers.iter())
+    {
+        let stage = Stage::of(timer);
+        drain += stage.drain();
+        if timer.finished() || stage == Stage::at(timer.duration().as_secs_f32(), timer.elapsed_secs() - dt) {
+            continue;
+        }
+        if *kind == MalfunctionType::Engine {
+            let pipes_rng = rng.stream(RngStream::Pipes);
+            for (_, mut pipes) in pipe_minigames.iter_mut().filter(|(id, _)| *id == site) {
+                pipes.jolt(pipes_rng, stage.jolted_pipes());
+            }
+        }
+        changed.write(StageChanged { stage });
+    }
+    energy.malfunction_drain = drain;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interactions::wires_minigame::WIRES;
+
+    #[test]
+    fn stages_escalate_and_pay_less() {
+        assert_eq!(Stage::at(60., 0.), Stage::Warning);
+        assert_eq!(Stage::at(60., 29.), Stage::Warning);
+        assert_eq!(Stage::at(60., 31.), Stage::Critical);
+        assert_eq!(Stage::at(60., 47.), Stage::Critical);
+        assert_eq!(Stage::at(60., 47.), Stage::at(120., 94.));
+        assert_eq!(Stage::at(60., 49.), Stage::Failing);
+        assert_eq!(Stage::at(0., 0.), Stage::Failing);
+        assert!(Stage::Failing.hack_attempts() >= 1);
+        assert!(Stage::Failing.wires() <= WIRES);
+
+        let stages = [Stage::Warning, Stage::Critical, Stage::Failing];
+        for pair in stages.windows(2) {
+            assert!(pair[0] < pair[1]);
+            assert!(pair[0].outcome_mul() > pair[1].outcome_mul());
+            assert!(pair[0].drain() < pair[1].drain());
+            assert!(pair[0].hack_attempts() > pair[1].hack_attempts());
+            assert!(pair[0].wires() < pair[1].wires());
+            assert!(pair[0].wave_tolerance() > pair[1].wave_tolerance());
+        }
+    }
+}
@@ -0,0 +1,200 @@
+// Headless balancing harness: `--simulate <runs>` plays the debris/malfunction loop
+// with a scripted player instead of opening a window, then prints how long the station lasted.
+//
+//   --difficulty <name>      profile to balance, victory is ignored so every run plays out
+//   --seed <n>               first seed, runs use consecutive seeds
+//   --sim-delay <secs>       the player gets to a malfunction this long after it is raised
+//   --sim-success <0..1>     chance the player solves it once there, otherwise it runs out
+//   --sim-max-secs <secs>    runs still alive after this count as survived
+//   --sim-csv <path>         per-seed results
+
+use std::time::Duration;
+
+use bevy::{prelude::*, state::app::StatesPlugin, time::TimeUpdateStrategy};
+use debug_utils::debug_overlay::DebugOverlayEvent;
+
+use crate::{core::states::GlobalAppState, interactions::{components::InteractableId, insert_puzzle_state}, utils::{args::launch_arg, debree::{tick_malfunctions, DebreeLevel, DebreeSimPlugin, Malfunction, Resolved}, difficulty::{Difficulty, DifficultyProfile}, energy::EnergyPlugin, malfunction_defs::{MalfunctionAssets, MalfunctionDefinitions, DEFINITIONS_PATH}, rng::{parse_seed, GameRng, RngStream}, score::{RunOutcome, RunScore, ScorePlugin}}};
+
+const FIXED_STEP: f64 = 1. / 60.;
+
+/// Scripted stand-in for the player
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct SimPolicy {
+    pub delay: f32,
+    pub success: f32,
+}
+
+#[derive(Clone, Debug)]
+pub struct SimResult {
+    pub seed: u64,
+    pub outcome: RunOutcome,
+    /// seconds into the run, `None` if it lasted until the time limit
+    pub defeat_at: Option<f32>,
+    pub resolved: u32,
+    pub failed: u32,
+}
+
+/// Each malfunction gets one attempt, on the fixed step its timer crosses `delay`
+pub fn scripted_player(
+    time: Res<Time>,
+    policy: Res<SimPolicy>,
+    mut malfunction: ResMut<Malfunction>,
+    mut rng: ResMut<GameRng>,
+) {
+    let dt = time.delta_secs();
+    let mut solved = vec![];
+    for ((kind, site), timer) in malfunction.malfunction_types.iter()
+        .zip(malfunction.malfunction_sites.iter())
+        .zip(malfunction.malfunction_timers.iter())
+    {
+        let elapsed = timer.elapsed_secs();
+        if elapsed >= policy.delay && elapsed - dt < policy.delay && !timer.finished() {
+            if rng.stream(RngStream::Policy).f32() < policy.success {
+                solved.push((kind.clone(), *site));
+            }
+        }
+    }
+    for (resolved_type, site) in solved {
+        malfunction.resolved.push(Resolved { resolved_type, site, failed: false });
+    }
+}
+
+pub fn sim_app(seed: u64, profile: &DifficultyProfile, definitions: &MalfunctionDefinitions, policy: SimPolicy) -> App {
+    let mut app = App::new();
+    app
+    .add_plugins((MinimalPlugins, StatesPlugin))
+    .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(FIXED_STEP)))
+    .insert_resource(Time::<Fixed>::from_seconds(FIXED_STEP))
+    .insert_state(GlobalAppState::InGame)
+    .add_event::<DebugOverlayEvent>()
+    .insert_resource(GameRng::new(seed))
+    .insert_resource(profile.clone())
+    .insert_resource(policy)
+    .add_plugins((DebreeSimPlugin, EnergyPlugin, ScorePlugin))
+    .add_systems(FixedUpdate, scripted_player
+        .after(tick_malfunctions)
+        .run_if(in_state(GlobalAppState::InGame)));
+
+    let mut assets = Assets::<MalfunctionDefinitions>::default();
+    let handle = assets.add(definitions.clone());
+    app.insert_resource(assets)
+    .insert_resource(MalfunctionAssets { definitions: handle });
+    app.world_mut().resource_mut::<DebreeLevel>().const_add = profile.const_add;
+    // one stand-in interactable per malfunction, like the station map has
+    let mut commands = app.world_mut().commands();
+    for (i, definition) in definitions.malfunctions.iter().enumerate() {
+        let mut site = commands.spawn((InteractableId(i as u32), definition.resolved_by.clone()));
+        insert_puzzle_state(&mut site, &definition.resolved_by);
+    }
+    app.world_mut().flush();
+    app
+}
+
+pub fn simulate_run(seed: u64, profile: &DifficultyProfile, definitions: &MalfunctionDefinitions, policy: SimPolicy, max_secs: f32) -> SimResult {
+    let mut app = sim_app(seed, profile, definitions, policy);
+    let mut defeat_at = None;
+    loop {
+        app.update();
+        let world = app.world();
+        let elapsed = world.resource::<DebreeLevel>().elapsed;
+        if *world.resource::<State<GlobalAppState>>().get() != GlobalAppState::InGame {
+            defeat_at = Some(elapsed);
+            break;
+        }
+        if elapsed >= max_secs {
+            break;
+        }
+    }
+    let world = app.world();
+    let score = world.resource::<RunScore>();
+    SimResult {
+        seed,
+        outcome: if defeat_at.is_some() {*world.resource::<RunOutcome>()} else {RunOutcome::Survived},
+        defeat_at,
+        resolved: score.resolved(),
+        failed: score.failed(),
+    }
+}
+
+fn percentile(sorted: &[f32], p: f32) -> f32 {
+    if sorted.is_empty() {
+        return 0.;
+    }
+    let idx = ((sorted.len() - 1) as f32 * p).round() as usize;
+    sorted[idx]
+}
+
+fn arg_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    launch_arg(name).and_then(|v| v.parse().ok()).unwrap_or(default)
+}
+
+pub fn run_simulation(runs: u32) {
+    let difficulty = launch_arg("difficulty")
+        .and_then(|v| Difficulty::parse(&v))
+        .unwrap_or_default();
+    let mut profile = DifficultyProfile::preset(difficulty);
+    profile.survive_secs = None;
+    let first_seed = launch_arg("seed").and_then(|v| parse_seed(&v)).unwrap_or(0);
+    let policy = SimPolicy {
+        delay: arg_or("sim-delay", 20.),
+        success: arg_or("sim-success", 0.8),
+    };
+    let max_secs = arg_or("sim-max-secs", 3600.);
+
+    let path = std::env::var("CARGO_MANIFEST_DIR")
+        .map(|dir| std::path::Path::new(&dir).join(DEFINITIONS_PATH))
+        .unwrap_or_else(|_| DEFINITIONS_PATH.into());
+    let definitions = match MalfunctionDefinitions::read(&path) {
+        Ok(d) => d,
+        Err(e) => {
+            eprintln!("Can't read {}: {}", path.display(), e);
+            return;
+        }
+    };
+
+    println!("{:?}, {} runs from seed {}, {:?}, limit {}s", difficulty, runs, first_seed, policy, max_secs);
+    let mut results = vec![];
+    for i in 0..runs as u64 {
+        results.push(simulate_run(first_seed.wrapping_add(i), &profile, &definitions, policy, max_secs));
+    }
+
+    if let Some(csv) = launch_arg("sim-csv") {
+        let mut out = String::from("seed,outcome,defeat_at,resolved,failed\n");
+        for r in results.iter() {
+            out += &format!("{},{:?},{},{},{}\n", r.seed, r.outcome,
+                r.defeat_at.map(|t| t.to_string()).unwrap_or_default(), r.resolved, r.failed);
+        }
+        if let Err(e) = std::fs::write(&csv, out) {
+            eprintln!("Can't write {}: {}", csv, e);
+        }
+    }
+
+    let mut defeats: Vec<f32> = results.iter().filter_map(|r| r.defeat_at).collect();
+    defeats.sort_by(|a, b| a.total_cmp(b));
+    let survived = results.len() - defeats.len();
+    println!("survived {}/{}", survived, results.len());
+    for outcome in [RunOutcome::Crashed, RunOutcome::ChainReaction] {
+        let n = results.iter().filter(|r| r.defeat_at.is_some() && r.outcome == outcome).count();
+        println!("{:?}: {}", outcome, n);
+    }
+    if defeats.is_empty() {
+        return;
+    }
+    let mean = defeats.iter().sum::<f32>() / defeats.len() as f32;
+    println!("time to defeat: mean {:.0}s, p10 {:.0}s, p50 {:.0}s, p90 {:.0}s",
+        mean, percentile(&defeats, 0.1), percentile(&defeats, 0.5), percentile(&defeats, 0.9));
+    // one row per minute, bar scaled to the busiest minute
+    let minutes = (max_secs / 60.).ceil() as usize;
+    let mut buckets = vec![0usize; minutes.max(1)];
+    for t in defeats.iter() {
+        let idx = ((t / 60.) as usize).min(buckets.len() - 1);
+        buckets[idx] += 1;
+    }
+    let peak = *buckets.iter().max().unwrap_or(&1);
+    for (minute, n) in buckets.iter().enumerate() {
+        if *n == 0 {
+            continue;
+        }
+        println!("{:>4}m {:>6} {}", minute, n, "#".repeat((n * 50).div_ceil(peak)));
+    }
+}
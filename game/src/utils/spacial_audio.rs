@@ -4,7 +4,7 @@ use bevy::{audio::{PlaybackMode, Volume}, color::palettes::css::GREEN, prelude::
 use bevy_asset_loader::{asset_collection::AssetCollection, loading_state::{config::{ConfigureLoadingState, LoadingStateConfig}, LoadingStateAppExt}};
 use utils::WrappedDelta;
 
-use crate::{core::states::{AppLoadingAssetsSubState, GlobalAppState, OnGame}, utils::debree::Malfunction};
+use crate::{core::{run::RunEntity, states::{AppLoadingAssetsSubState, GlobalAppState, OnGame}}, utils::{debree::Malfunction, severity::{Stage, StageChanged}}};
 
 pub struct SpacialAudioPlugin;
 
@@ -66,33 +66,65 @@ pub struct SoundAssets {
     pub boom: Handle<AudioSource>,
 }
 
-#[derive(Component)]
+/// Authored on Tiled objects, plays the alarm when a malfunction is raised or escalates, see `Stage::alarm`
+#[derive(Component, Default, Reflect)]
+#[reflect(Component, Default)]
 pub struct AlarmSpeaker;
 
+/// Looping spatial ambience authored on Tiled objects
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub enum Ambience {
+    #[default]
+    EngineAmbience,
+    IndustrialAmbience,
+    VentAmbience,
+}
+
+impl Ambience {
+    pub fn sound(&self, sound_assets: &SoundAssets) -> Handle<AudioSource> {
+        match self {
+            Ambience::EngineAmbience => sound_assets.engine_ambience.clone(),
+            Ambience::IndustrialAmbience => sound_assets.industrial_ambience.clone(),
+            Ambience::VentAmbience => sound_assets.vent_ambience.clone(),
+        }
+    }
+}
+
 fn play_alarm_speakers(
     mut commands: Commands,
     speakers: Query<Entity, With<AlarmSpeaker>>,
     mut malfunction: ResMut<Malfunction>,
+    mut stages: EventReader<StageChanged>,
     sound_assets: Res<SoundAssets>,
+    mut looping: Local<bool>,
 ) {
     // TODO: IF FLYING => DISABLE
+    let mut alarm = None;
     if malfunction.is_changed() && malfunction.in_progress && malfunction.added_new_malfunction {
         malfunction.added_new_malfunction = false;
+        alarm = Some(Stage::Warning);
+    }
+    for changed in stages.read() {
+        alarm = alarm.max(Some(changed.stage));
+    }
+    if *looping && malfunction.worst_stage() != Some(Stage::Failing) {
+        *looping = false;
         for speaker_entity in speakers {
-            commands.entity(speaker_entity).insert((
-                AudioPlayer::new(sound_assets.alarm_sound.clone()),
-                PlaybackSettings {
-                    mode: PlaybackMode::Remove,
-                    volume: Volume::Linear(0.2),
-                    speed: 1.0,
-                    paused: false,
-                    muted: false,
-                    spatial: true,
-                    spatial_scale: None,
-                },
-            ));
+            commands.entity(speaker_entity).remove::<(AudioPlayer, PlaybackSettings, SpatialAudioSink)>();
         }
     }
+    // nothing tops the failing loop
+    let Some(stage) = alarm.filter(|_| !*looping) else {
+        return;
+    };
+    *looping = stage == Stage::Failing;
+    for speaker_entity in speakers {
+        commands.entity(speaker_entity).insert((
+            AudioPlayer::new(sound_assets.alarm_sound.clone()),
+            stage.alarm(),
+        ));
+    }
 }
 
 #[derive(Event)]
@@ -220,5 +252,6 @@ fn play_main_theme(
             spatial: false,
             spatial_scale: None,
         },
+        RunEntity,
     ));
 }
\ No newline at end of file
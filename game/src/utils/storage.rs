@@ -0,0 +1,53 @@
+// Tiny key-value persistence: files under `saves/` natively, localStorage on wasm.
+
+#[cfg(not(target_arch = "wasm32"))]
+const SAVE_DIR: &str = "saves";
+
+#[cfg(target_arch = "wasm32")]
+const KEY_PREFIX: &str = "pre_jam/";
+
+#[cfg(not(target_arch = "wasm32"))]
+fn path(key: &str) -> std::path::PathBuf {
+    std::path::Path::new(SAVE_DIR).join(format!("{key}.ron"))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read(key: &str) -> Option<String> {
+    std::fs::read_to_string(path(key)).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn write(key: &str, data: &str) -> Result<(), String> {
+    std::fs::create_dir_all(SAVE_DIR).map_err(|e| e.to_string())?;
+    std::fs::write(path(key), data).map_err(|e| e.to_string())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn remove(key: &str) {
+    let _ = std::fs::remove_file(path(key));
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn read(key: &str) -> Option<String> {
+    local_storage()?.get_item(&format!("{KEY_PREFIX}{key}")).ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn write(key: &str, data: &str) -> Result<(), String> {
+    let storage = local_storage().ok_or("localStorage is unavailable")?;
+    storage
+        .set_item(&format!("{KEY_PREFIX}{key}"), data)
+        .map_err(|e| format!("{:?}", e))
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn remove(key: &str) {
+    if let Some(storage) = local_storage() {
+        let _ = storage.remove_item(&format!("{KEY_PREFIX}{key}"));
+    }
+}
@@ -28,11 +28,14 @@ impl ResourceCache for TiledResourceCache {
         self.0.read().unwrap().get_tileset(path)
     }
 
+    // Templates are never cached: the cache is only cleared once a map has been reloaded,
+    // so the reload triggered by editing a template would still use the old one.
+    // They are read again with the map anyway, see [crate::reader::read_templates].
     fn get_template(
         &self,
-        path: impl AsRef<tiled::ResourcePath>,
+        _path: impl AsRef<tiled::ResourcePath>,
     ) -> Option<std::sync::Arc<tiled::Template>> {
-        self.0.read().unwrap().get_template(path)
+        None
     }
 
     fn insert_tileset(
@@ -45,9 +48,8 @@ impl ResourceCache for TiledResourceCache {
 
     fn insert_template(
         &mut self,
-        path: impl AsRef<tiled::ResourcePath>,
-        template: Arc<tiled::Template>,
+        _path: impl AsRef<tiled::ResourcePath>,
+        _template: Arc<tiled::Template>,
     ) {
-        self.0.write().unwrap().insert_template(path, template);
     }
 }
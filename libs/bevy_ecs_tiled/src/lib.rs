@@ -54,6 +54,12 @@ pub struct TiledMapPluginConfig {
     ///
     /// If [None], will not export Tiled types at startup.
     pub tiled_types_export_file: Option<PathBuf>,
+    /// Path to an existing `.tiled-project` file to merge Tiled types into.
+    ///
+    /// Unlike [TiledMapPluginConfig::tiled_types_export_file], the file is not overwritten:
+    /// types are merged into the project `propertyTypes`, see [map::export_types_to_project].
+    /// If [None], will not update any project at startup.
+    pub tiled_project_file: Option<PathBuf>,
 }
 
 impl Default for TiledMapPluginConfig {
@@ -62,6 +68,7 @@ impl Default for TiledMapPluginConfig {
         path.push("tiled_types_export.json");
         Self {
             tiled_types_export_file: Some(path),
+            tiled_project_file: None,
         }
     }
 }
@@ -98,6 +105,10 @@ impl Plugin for TiledMapPlugin {
                     info!("Export Tiled types to '{:?}'", &path);
                     map::export_types(&reg, path, |_| true);
                 }
+                if let Some(path) = &config.tiled_project_file {
+                    info!("Merge Tiled types into '{:?}'", &path);
+                    map::export_types_to_project(&reg, path, |_| true);
+                }
             },
         );
     }
@@ -1,6 +1,6 @@
 //! This module contains all map [Asset]s definition.
 
-use std::fmt;
+use std::{fmt, path::PathBuf};
 #[cfg(feature = "user_properties")]
 use std::ops::Deref;
 
@@ -12,8 +12,9 @@ use tiled::ChunkData;
 use crate::properties::load::DeserializedMapProperties;
 
 use crate::{
-    cache::TiledResourceCache, get_grid_size, get_map_type, iso_projection,
-    reader::BytesResourceReader,
+    cache::TiledResourceCache, get_all_layers, get_grid_size, get_map_type, iso_projection,
+    staggered_tilemap_size,
+    reader::{read_templates, BytesResourceReader},
 };
 
 use bevy::{
@@ -32,7 +33,14 @@ pub struct TiledMap {
     /// The raw Tiled map data
     pub map: tiled::Map,
     /// Map size in tiles
+    ///
+    /// For staggered isometric maps, this is the size of the `bevy_ecs_tilemap` grid
+    /// holding the map, see [staggered_tilemap_size](crate::prelude::staggered_tilemap_size).
     pub tilemap_size: TilemapSize,
+    /// Map size in Tiled tiles
+    ///
+    /// Same as `tilemap_size`, except for staggered isometric maps.
+    pub(crate) tiled_size: TilemapSize,
     /// Map bounding box, unanchored.
     ///
     /// Origin it map bottom-left.
@@ -57,6 +65,10 @@ pub struct TiledMap {
     ///
     /// Key is the Tiled tileset index
     pub(crate) tilesets: HashMap<usize, TiledMapTileset>,
+    /// Tilesets used by object templates
+    ///
+    /// Key is the tileset source path, value is its index in `tilesets`
+    pub(crate) template_tilesets: HashMap<PathBuf, usize>,
     /// Map properties
     #[cfg(feature = "user_properties")]
     pub(crate) properties: DeserializedMapProperties,
@@ -95,6 +107,7 @@ impl fmt::Debug for TiledMap {
         f.debug_struct("TiledMap")
             .field("map", &self.map)
             .field("tilemap_size", &self.tilemap_size)
+            .field("tiled_size", &self.tiled_size)
             .field("rect", &self.rect)
             .field("tiled_offset", &self.tiled_offset)
             .field("topleft_chunk", &self.topleft_chunk)
@@ -143,6 +156,92 @@ pub enum TiledMapLoaderError {
     Io(#[from] std::io::Error),
 }
 
+fn load_tileset(
+    tileset: &tiled::Tileset,
+    tileset_index: usize,
+    load_context: &mut LoadContext<'_>,
+) -> Option<TiledMapTileset> {
+    debug!(
+        "Loading tileset (index={:?} name={:?}) from {:?}",
+        tileset_index, tileset.name, tileset.source
+    );
+    let mut texture_atlas_layout_handle = None;
+    #[cfg(not(feature = "atlas"))]
+    let mut tile_image_offsets = HashMap::default();
+    let (usable_for_tiles_layer, tilemap_texture) = match &tileset.image {
+        None => {
+            #[cfg(feature = "atlas")]
+            {
+                info!("Skipping image collection tileset '{}' which is incompatible with atlas feature", tileset.name);
+                return None;
+            }
+
+            #[cfg(not(feature = "atlas"))]
+            {
+                let mut usable_for_tiles_layer = true;
+                let mut image_size: Option<(i32, i32)> = None;
+                let mut tile_images: Vec<Handle<Image>> = Vec::new();
+                for (tile_id, tile) in tileset.tiles() {
+                    if let Some(img) = &tile.image {
+                        let asset_path = AssetPath::from(img.source.clone());
+                        trace!("Loading tile image from {asset_path:?} as image ({tileset_index}, {tile_id})");
+                        let texture: Handle<Image> = load_context.load(asset_path.clone());
+                        tile_image_offsets.insert(tile_id, tile_images.len() as u32);
+                        tile_images.push(texture.clone());
+                        if usable_for_tiles_layer {
+                            if let Some(image_size) = image_size {
+                                if img.width != image_size.0 || img.height != image_size.1 {
+                                    usable_for_tiles_layer = false;
+                                }
+                            } else {
+                                image_size = Some((img.width, img.height));
+                            }
+                        }
+                    }
+                }
+                if !usable_for_tiles_layer {
+                    debug!(
+                        "Tileset (index={:?}) have non constant image size and cannot be used for tiles layer",
+                        tileset_index
+                    );
+                }
+                (usable_for_tiles_layer, TilemapTexture::Vector(tile_images))
+            }
+        }
+        Some(img) => {
+            let asset_path = AssetPath::from(img.source.clone());
+            let texture: Handle<Image> = load_context.load(asset_path.clone());
+
+            let columns = (img.width as u32 - tileset.margin + tileset.spacing)
+                / (tileset.tile_width + tileset.spacing);
+            if columns > 0 {
+                texture_atlas_layout_handle =
+                    Some(load_context.labeled_asset_scope(tileset.name.clone(), |_| {
+                        TextureAtlasLayout::from_grid(
+                            UVec2::new(tileset.tile_width, tileset.tile_height),
+                            columns,
+                            tileset.tilecount / columns,
+                            Some(UVec2::new(tileset.spacing, tileset.spacing)),
+                            Some(UVec2::new(
+                                tileset.offset_x as u32 + tileset.margin,
+                                tileset.offset_y as u32 + tileset.margin,
+                            )),
+                        )
+                    }));
+            }
+
+            (true, TilemapTexture::Single(texture.clone()))
+        }
+    };
+    Some(TiledMapTileset {
+        usable_for_tiles_layer,
+        tilemap_texture,
+        texture_atlas_layout_handle,
+        #[cfg(not(feature = "atlas"))]
+        tile_image_offsets,
+    })
+}
+
 impl AssetLoader for TiledMapLoader {
     type Asset = TiledMap;
     type Settings = ();
@@ -160,11 +259,12 @@ impl AssetLoader for TiledMapLoader {
         debug!("Start loading map '{}'", load_context.path().display());
 
         let map_path = load_context.path().to_path_buf();
+        let templates = read_templates(&bytes, load_context).await;
         let map = {
             // Allow the loader to also load tileset images.
             let mut loader = tiled::Loader::with_cache_and_reader(
                 self.cache.clone(),
-                BytesResourceReader::new(&bytes, load_context),
+                BytesResourceReader::new(&bytes).with_templates(templates),
             );
             // Load the map and all tiles.
             loader
@@ -174,95 +274,50 @@ impl AssetLoader for TiledMapLoader {
 
         let mut tilesets = HashMap::default();
         for (tileset_index, tileset) in map.tilesets().iter().enumerate() {
-            debug!(
-                "Loading tileset (index={:?} name={:?}) from {:?}",
-                tileset_index, tileset.name, tileset.source
-            );
-            let mut texture_atlas_layout_handle = None;
-            #[cfg(not(feature = "atlas"))]
-            let mut tile_image_offsets = HashMap::default();
-            let (usable_for_tiles_layer, tilemap_texture) = match &tileset.image {
-                None => {
-                    #[cfg(feature = "atlas")]
-                    {
-                        info!("Skipping image collection tileset '{}' which is incompatible with atlas feature", tileset.name);
-                        continue;
-                    }
+            if let Some(tileset) = load_tileset(tileset, tileset_index, load_context) {
+                tilesets.insert(tileset_index, tileset);
+            }
+        }
 
-                    #[cfg(not(feature = "atlas"))]
-                    {
-                        let mut usable_for_tiles_layer = true;
-                        let mut image_size: Option<(i32, i32)> = None;
-                        let mut tile_images: Vec<Handle<Image>> = Vec::new();
-                        for (tile_id, tile) in tileset.tiles() {
-                            if let Some(img) = &tile.image {
-                                let asset_path = AssetPath::from(img.source.clone());
-                                trace!("Loading tile image from {asset_path:?} as image ({tileset_index}, {tile_id})");
-                                let texture: Handle<Image> = load_context.load(asset_path.clone());
-                                tile_image_offsets.insert(tile_id, tile_images.len() as u32);
-                                tile_images.push(texture.clone());
-                                if usable_for_tiles_layer {
-                                    if let Some(image_size) = image_size {
-                                        if img.width != image_size.0 || img.height != image_size.1 {
-                                            usable_for_tiles_layer = false;
-                                        }
-                                    } else {
-                                        image_size = Some((img.width, img.height));
-                                    }
-                                }
-                            }
-                        }
-                        if !usable_for_tiles_layer {
-                            debug!(
-                                "Tileset (index={:?}) have non constant image size and cannot be used for tiles layer",
-                                tileset_index
-                            );
-                        }
-                        (usable_for_tiles_layer, TilemapTexture::Vector(tile_images))
-                    }
+        // Tile objects coming from a template reference the template tileset:
+        // load the ones which are not already part of the map, after map tilesets
+        let mut template_tilesets = HashMap::default();
+        for layer in get_all_layers(&map) {
+            let Some(object_layer) = layer.as_object_layer() else {
+                continue;
+            };
+            for object in object_layer.objects() {
+                let Some(tiled::TilesetLocation::Template(tileset)) =
+                    object.tile_data().map(|t| t.tileset_location().clone())
+                else {
+                    continue;
+                };
+                if template_tilesets.contains_key(&tileset.source) {
+                    continue;
                 }
-                Some(img) => {
-                    let asset_path = AssetPath::from(img.source.clone());
-                    let texture: Handle<Image> = load_context.load(asset_path.clone());
-
-                    let columns = (img.width as u32 - tileset.margin + tileset.spacing)
-                        / (tileset.tile_width + tileset.spacing);
-                    if columns > 0 {
-                        texture_atlas_layout_handle =
-                            Some(load_context.labeled_asset_scope(tileset.name.clone(), |_| {
-                                TextureAtlasLayout::from_grid(
-                                    UVec2::new(tileset.tile_width, tileset.tile_height),
-                                    columns,
-                                    tileset.tilecount / columns,
-                                    Some(UVec2::new(tileset.spacing, tileset.spacing)),
-                                    Some(UVec2::new(
-                                        tileset.offset_x as u32 + tileset.margin,
-                                        tileset.offset_y as u32 + tileset.margin,
-                                    )),
-                                )
-                            }));
+                let tileset_index = match map
+                    .tilesets()
+                    .iter()
+                    .position(|t| t.source == tileset.source)
+                {
+                    Some(tileset_index) => tileset_index,
+                    None => {
+                        let tileset_index = map.tilesets().len() + template_tilesets.len();
+                        if let Some(t) = load_tileset(&tileset, tileset_index, load_context) {
+                            tilesets.insert(tileset_index, t);
+                        }
+                        tileset_index
                     }
-
-                    (true, TilemapTexture::Single(texture.clone()))
-                }
-            };
-            tilesets.insert(
-                tileset_index,
-                TiledMapTileset {
-                    usable_for_tiles_layer,
-                    tilemap_texture,
-                    texture_atlas_layout_handle,
-                    #[cfg(not(feature = "atlas"))]
-                    tile_image_offsets,
-                },
-            );
+                };
+                template_tilesets.insert(tileset.source.clone(), tileset_index);
+            }
         }
 
         let mut infinite = false;
 
         // Determine top left chunk index of all infinite layers for this map
         let mut topleft = (999999, 999999);
-        for layer in map.layers() {
+        for layer in get_all_layers(&map) {
             if let tiled::LayerType::Tiles(tiled::TileLayer::Infinite(layer)) = layer.layer_type() {
                 topleft = layer.chunks().fold(topleft, |acc, (pos, _)| {
                     (acc.0.min(pos.0), acc.1.min(pos.1))
@@ -272,7 +327,7 @@ impl AssetLoader for TiledMapLoader {
         }
         // Determine bottom right chunk index of all infinite layers for this map
         let mut bottomright = (0, 0);
-        for layer in map.layers() {
+        for layer in get_all_layers(&map) {
             if let tiled::LayerType::Tiles(tiled::TileLayer::Infinite(layer)) = layer.layer_type() {
                 bottomright = layer.chunks().fold(bottomright, |acc, (pos, _)| {
                     (acc.0.max(pos.0), acc.1.max(pos.1))
@@ -283,7 +338,7 @@ impl AssetLoader for TiledMapLoader {
 
         let map_type = get_map_type(&map);
         let grid_size = get_grid_size(&map);
-        let (tilemap_size, tiled_offset) = if infinite {
+        let (tiled_size, tiled_offset) = if infinite {
             debug!(
                 "(infinite map) topleft = {:?}, bottomright = {:?}",
                 topleft, bottomright
@@ -313,7 +368,16 @@ impl AssetLoader for TiledMapLoader {
                         y: -topleft.1 as f32 * ChunkData::HEIGHT as f32 * grid_size.y,
                     },
                     TilemapType::Isometric(IsoCoordSystem::Staggered) => {
-                        panic!("Isometric (Staggered) map is not supported");
+                        match map.stagger_axis {
+                            tiled::StaggerAxis::X => Vec2 {
+                                x: -topleft.0 as f32 * ChunkData::WIDTH as f32 * grid_size.x / 2.,
+                                y: topleft.1 as f32 * ChunkData::HEIGHT as f32 * grid_size.y,
+                            },
+                            tiled::StaggerAxis::Y => Vec2 {
+                                x: -topleft.0 as f32 * ChunkData::WIDTH as f32 * grid_size.x,
+                                y: topleft.1 as f32 * ChunkData::HEIGHT as f32 * grid_size.y / 2.,
+                            },
+                        }
                     }
                     _ => unreachable!(),
                 },
@@ -334,39 +398,54 @@ impl AssetLoader for TiledMapLoader {
             min: Vec2::ZERO,
             max: match map_type {
                 TilemapType::Square => Vec2 {
-                    x: tilemap_size.x as f32 * grid_size.x,
-                    y: tilemap_size.y as f32 * grid_size.y,
+                    x: tiled_size.x as f32 * grid_size.x,
+                    y: tiled_size.y as f32 * grid_size.y,
                 },
                 TilemapType::Hexagon(HexCoordSystem::ColumnOdd)
                 | TilemapType::Hexagon(HexCoordSystem::ColumnEven) => Vec2 {
-                    x: tilemap_size.x as f32 * grid_size.x * 0.75,
-                    y: tilemap_size.y as f32 * grid_size.y,
+                    x: tiled_size.x as f32 * grid_size.x * 0.75,
+                    y: tiled_size.y as f32 * grid_size.y,
                 },
                 TilemapType::Hexagon(HexCoordSystem::RowOdd)
                 | TilemapType::Hexagon(HexCoordSystem::RowEven) => Vec2 {
-                    x: tilemap_size.x as f32 * grid_size.x,
-                    y: tilemap_size.y as f32 * grid_size.y * 0.75,
+                    x: tiled_size.x as f32 * grid_size.x,
+                    y: tiled_size.y as f32 * grid_size.y * 0.75,
                 },
                 TilemapType::Isometric(IsoCoordSystem::Diamond) => {
-                    let topleft = iso_projection(Vec2::ZERO, &tilemap_size, &grid_size);
+                    let topleft = iso_projection(Vec2::ZERO, &tiled_size, &grid_size);
                     let topright = iso_projection(
                         Vec2 {
-                            x: tilemap_size.x as f32 * grid_size.y,
+                            x: tiled_size.x as f32 * grid_size.y,
                             y: 0.,
                         },
-                        &tilemap_size,
+                        &tiled_size,
                         &grid_size,
                     );
 
                     2. * (topright - topleft)
                 }
-                TilemapType::Isometric(IsoCoordSystem::Staggered) => {
-                    panic!("Isometric (Staggered) map is not supported");
-                }
+                TilemapType::Isometric(IsoCoordSystem::Staggered) => match map.stagger_axis {
+                    tiled::StaggerAxis::X => Vec2 {
+                        x: (tiled_size.x + 1) as f32 * grid_size.x / 2.,
+                        y: tiled_size.y as f32 * grid_size.y + grid_size.y / 2.,
+                    },
+                    tiled::StaggerAxis::Y => Vec2 {
+                        x: tiled_size.x as f32 * grid_size.x + grid_size.x / 2.,
+                        y: (tiled_size.y + 1) as f32 * grid_size.y / 2.,
+                    },
+                },
                 _ => unreachable!(),
             },
         };
 
+        // bevy_ecs_tilemap grid does not match Tiled one for staggered maps
+        let tilemap_size = match map_type {
+            TilemapType::Isometric(IsoCoordSystem::Staggered) => {
+                staggered_tilemap_size(map.stagger_axis, map.stagger_index, tiled_size)
+            }
+            _ => tiled_size,
+        };
+
         #[cfg(feature = "user_properties")]
         let properties =
             DeserializedMapProperties::load(&map, self.registry.read().deref(), load_context);
@@ -378,11 +457,13 @@ impl AssetLoader for TiledMapLoader {
         let asset_map = TiledMap {
             map,
             tilemap_size,
+            tiled_size,
             tiled_offset,
             rect,
             topleft_chunk: topleft,
             bottomright_chunk: bottomright,
             tilesets,
+            template_tilesets,
             #[cfg(feature = "user_properties")]
             properties,
         };
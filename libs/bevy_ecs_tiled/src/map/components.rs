@@ -57,6 +57,23 @@ pub struct TiledMapStorage {
     pub tiles: HashMap<(String, TileId), Vec<Entity>>,
 }
 
+/// [Component] storing the chunks of infinite tiles layers streamed around cameras.
+///
+/// Inserted on maps spawned by a world using [crate::prelude::TiledWorldChunking]: instead of
+/// spawning all their tiles at once, infinite tiles layers only spawn the chunks close to a [Camera].
+/// Staggered isometric maps are not streamed.
+#[derive(Component, Default, Reflect, Clone, Debug)]
+#[reflect(Component, Default, Debug)]
+pub struct TiledMapChunkStreaming {
+    /// Streamed tiles layers, along with the opacity they inherit from their group layers
+    pub(crate) layers: Vec<(crate::prelude::TiledLayerCreated, f32)>,
+    /// Spawned chunks, using the Tiled chunk index as key.
+    ///
+    /// For each chunk, `layers` holds the chunk [Entity] spawned for each layer
+    /// and `tiles` the tiles it contains.
+    pub chunks: HashMap<IVec2, TiledMapStorage>,
+}
+
 /// Marker [Component] for a Tiled map.
 #[derive(Component, Default, Reflect, Copy, Clone, Debug)]
 #[reflect(Component, Default, Debug)]
@@ -93,6 +110,39 @@ pub struct TiledMapObjectLayer;
 #[require(Visibility, Transform)]
 pub struct TiledMapGroupLayer;
 
+/// Parallax factor of a Tiled layer, combined with the ones of its parent group layers.
+///
+/// Only added to layers which do not scroll with their parent.
+/// Layers are moved relative to the camera holding [TiledParallaxCamera]:
+/// without such a camera, parallax is ignored.
+#[derive(Component, Reflect, Copy, Clone, Debug)]
+#[reflect(Component, Debug)]
+pub struct TiledLayerParallax {
+    /// Map [Entity] this layer belongs to
+    pub map: Entity,
+    /// Parallax factor of this layer, including its parent groups
+    pub parallax: Vec2,
+    /// Parallax factor of the parent group layer, `Vec2::ONE` for top level layers
+    pub parent_parallax: Vec2,
+    /// Layer translation when the camera is at the map origin
+    pub origin: Vec2,
+}
+
+/// Marker [Component] for the camera used to compute Tiled layers parallax.
+///
+/// See [TiledLayerParallax].
+#[derive(Component, Default, Reflect, Copy, Clone, Debug)]
+#[reflect(Component, Default, Debug)]
+pub struct TiledParallaxCamera;
+
+/// [Component] for a streamed chunk of a Tiled map infinite tiles layer.
+///
+/// Holds the Tiled chunk index, see [TiledMapChunkStreaming].
+#[derive(Component, Default, Reflect, Copy, Clone, Debug)]
+#[reflect(Component, Default, Debug)]
+#[require(Visibility, Transform)]
+pub struct TiledMapTileChunk(pub IVec2);
+
 /// Marker [Component] for a Tiled map image layer.
 #[derive(Component, Default, Reflect, Copy, Clone, Debug)]
 #[reflect(Component, Default, Debug)]
@@ -134,3 +184,124 @@ pub struct TiledAnimation {
     /// Timer firing every time we should update the frame
     pub timer: Timer,
 }
+
+/// A single frame of a [TiledFrameAnimation].
+#[derive(Reflect, Copy, Clone, Debug, PartialEq)]
+#[reflect(Debug, PartialEq)]
+pub struct TiledAnimationFrame {
+    /// Texture index to display: atlas index or index in the images collection
+    pub index: u32,
+    /// How long this frame is displayed, in seconds
+    pub duration: f32,
+}
+
+/// This [Component] is used for animations which cannot be played by [bevy_ecs_tilemap]
+/// or by [TiledAnimation]: frames with different durations or frame tiles which are not
+/// consecutive in the tileset.
+///
+/// Frames are played on the CPU: we update the tile [TileTextureIndex](bevy_ecs_tilemap::tiles::TileTextureIndex)
+/// or the [Sprite] atlas index every time the current frame changes.
+#[derive(Component, Default, Reflect, Clone, Debug)]
+#[reflect(Component, Default, Debug)]
+pub struct TiledFrameAnimation {
+    /// Animation frames, played in order then looping
+    pub frames: Vec<TiledAnimationFrame>,
+    /// Index of the current frame in `frames`
+    pub current: usize,
+    /// Time already spent on the current frame, in seconds
+    pub elapsed: f32,
+}
+
+impl TiledFrameAnimation {
+    /// Create an animation starting on its first frame.
+    pub fn new(frames: Vec<TiledAnimationFrame>) -> Self {
+        Self {
+            frames,
+            current: 0,
+            elapsed: 0.,
+        }
+    }
+
+    /// Texture index of the current frame.
+    pub fn index(&self) -> Option<u32> {
+        self.frames.get(self.current).map(|frame| frame.index)
+    }
+
+    /// Advance the animation by `delta` seconds.
+    ///
+    /// Returns the new texture index if the current frame changed.
+    pub fn tick(&mut self, delta: f32) -> Option<u32> {
+        let total: f32 = self.frames.iter().map(|frame| frame.duration).sum();
+        if total <= 0. || self.current >= self.frames.len() {
+            return None;
+        }
+        let previous = self.index();
+        self.elapsed += delta;
+        // A full loop brings us back to the same frame, skip them all at once
+        if self.elapsed >= total {
+            self.elapsed %= total;
+        }
+        while self.elapsed >= self.frames[self.current].duration {
+            self.elapsed -= self.frames[self.current].duration;
+            self.current = (self.current + 1) % self.frames.len();
+        }
+        let index = self.index();
+        if index != previous {
+            index
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn animation(frames: &[(u32, f32)]) -> TiledFrameAnimation {
+        TiledFrameAnimation::new(
+            frames
+                .iter()
+                .map(|&(index, duration)| TiledAnimationFrame { index, duration })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn variable_frame_durations() {
+        let mut anim = animation(&[(4, 0.5), (9, 0.125), (2, 0.25)]);
+        assert_eq!(anim.index(), Some(4));
+        assert_eq!(anim.tick(0.25), None);
+        assert_eq!(anim.tick(0.25), Some(9));
+        assert_eq!(anim.tick(0.0625), None);
+        assert_eq!(anim.tick(0.125), Some(2));
+        assert_eq!(anim.elapsed, 0.0625);
+        assert_eq!(anim.tick(0.1875), Some(4));
+        assert_eq!(anim.current, 0);
+    }
+
+    #[test]
+    fn long_delta_wraps_around() {
+        let mut anim = animation(&[(0, 1.), (1, 2.)]);
+        // 2 full loops + 1.5s
+        assert_eq!(anim.tick(7.5), Some(1));
+        assert_eq!(anim.elapsed, 0.5);
+        // A full loop ends on the same frame
+        assert_eq!(anim.tick(3.), None);
+        assert_eq!(anim.index(), Some(1));
+    }
+
+    #[test]
+    fn degenerate_animations() {
+        let mut empty = animation(&[]);
+        assert_eq!(empty.tick(1.), None);
+        assert_eq!(empty.index(), None);
+
+        let mut instant = animation(&[(1, 0.), (2, 0.)]);
+        assert_eq!(instant.tick(1.), None);
+
+        // Zero length frames are skipped
+        let mut skip = animation(&[(1, 1.), (2, 0.), (3, 1.)]);
+        assert_eq!(skip.tick(1.), Some(3));
+    }
+}
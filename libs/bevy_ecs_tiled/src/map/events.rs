@@ -64,8 +64,13 @@ pub struct TiledLayerCreated {
     pub map: TiledMapCreated,
     /// Spawned layer [Entity]
     pub entity: Entity,
-    /// ID of this layer in the [Map]
+    /// Index of this layer in the [Map], counting layers nested in group layers
+    ///
+    /// See [get_all_layers] for ordering.
     pub id: usize,
+    /// Spawned [Entity] of the group layer this layer belongs to,
+    /// `None` for top level layers
+    pub parent: Option<Entity>,
 }
 
 impl Event for TiledLayerCreated {
@@ -78,7 +83,7 @@ impl<'a> TiledLayerCreated {
     pub fn get_layer(&self, map_asset: &'a Res<Assets<TiledMap>>) -> Option<Layer<'a>> {
         self.map
             .get_map(map_asset)
-            .and_then(|m| m.get_layer(self.id))
+            .and_then(|m| get_layer_from_index(m, self.id))
     }
 }
 
@@ -181,3 +186,22 @@ impl<'a> TiledTileCreated {
         })
     }
 }
+
+/// Event sent when a chunk of an infinite tiles layer is streamed in
+///
+/// See [TiledMapChunkStreaming].
+#[derive(Component, Reflect, Clone, Debug, Copy)]
+#[reflect(Component, Debug)]
+pub struct TiledChunkCreated {
+    /// Creation event of the layer this chunk belongs to
+    pub layer: TiledLayerCreated,
+    /// Spawned chunk [Entity]
+    pub entity: Entity,
+    /// Chunk index (Tiled referential)
+    pub chunk: IVec2,
+}
+
+impl Event for TiledChunkCreated {
+    type Traversal = &'static ChildOf;
+    const AUTO_PROPAGATE: bool = true;
+}
@@ -34,7 +34,10 @@ use crate::properties::command::PropertiesCommandExt;
 use crate::prelude::*;
 use bevy::{platform::collections::HashMap, prelude::*, sprite::Anchor};
 use bevy_ecs_tilemap::prelude::*;
-use tiled::{ImageLayer, Layer, LayerType, ObjectLayer, Tile, TileId, TileLayer, TilesetLocation};
+use tiled::{
+    ChunkData, ImageLayer, Layer, LayerTile, LayerTileData, LayerType, ObjectLayer, Tile, TileId,
+    TileLayer, TilesetLocation,
+};
 
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn load_map(
@@ -48,6 +51,7 @@ pub(crate) fn load_map(
     asset_server: &Res<AssetServer>,
     event_writers: &mut TiledMapEventWriters,
     anchor: &TilemapAnchor,
+    chunk_streaming: bool,
 ) {
     commands.entity(map_entity).insert((
         Name::new(format!("TiledMap: {}", tiled_map.map.source.display())),
@@ -62,23 +66,46 @@ pub(crate) fn load_map(
     let mut layer_events: Vec<TiledLayerCreated> = Vec::new();
     let mut object_events: Vec<TiledObjectCreated> = Vec::new();
     let mut special_tile_events: Vec<TiledTileCreated> = Vec::new();
+    let mut streamed_layers: Vec<(TiledLayerCreated, f32)> = Vec::new();
 
     // Order of the differents layers in the .TMX file is important:
     // a layer appearing last in the .TMX should appear above previous layers
     // Start with a negative offset so in the end we end up with the top layer at Z-offset from settings
-    let mut offset_z = tiled_map.map.layers().len() as f32 * (-layer_offset.0);
+    // Layers nested in group layers get their own Z-offset as well, relative to their parent
+    let layers = get_layers_with_parent(&tiled_map.map);
+    let mut offset_z = layers.len() as f32 * (-layer_offset.0);
+
+    // Spawned layers, with the values their children inherit:
+    // (entity, absolute Z-offset, opacity, parallax)
+    let mut spawned_layers: Vec<(Entity, f32, f32, Vec2)> = Vec::with_capacity(layers.len());
 
     // Once materials have been created/added we need to then create the layers.
-    for (layer_id, layer) in tiled_map.map.layers().enumerate() {
+    for (layer_id, (layer, parent, _)) in layers.into_iter().enumerate() {
+        let (parent_entity, parent_z, parent_opacity, parent_parallax) = match parent {
+            Some(index) => spawned_layers[index],
+            None => (map_entity, 0., 1., Vec2::ONE),
+        };
+        let opacity = parent_opacity * layer.opacity;
+        let parallax = parent_parallax * Vec2::new(layer.parallax_x, layer.parallax_y);
+
         // Increment Z offset and compute layer transform offset
         offset_z += layer_offset.0;
-        let offset_transform = Transform::from_xyz(layer.offset_x, -layer.offset_y, offset_z);
+        let layer_position = match layer.layer_type() {
+            LayerType::Image(_) => from_tiled_position_to_world_space(
+                tiled_map,
+                anchor,
+                Vec2::new(layer.offset_x, layer.offset_y),
+            ),
+            _ => Vec2::new(layer.offset_x, -layer.offset_y),
+        };
+        let offset_transform =
+            Transform::from_translation(layer_position.extend(offset_z - parent_z));
 
-        // Spawn layer entity and attach it to the map entity
+        // Spawn layer entity and attach it to its parent group layer or to the map entity
         let layer_entity = commands
             .spawn((
                 TiledMapLayer,
-                ChildOf(map_entity),
+                ChildOf(parent_entity),
                 // Apply layer Transform using both layer base Transform and Tiled offset
                 offset_transform,
                 // Determine layer default visibility
@@ -89,10 +116,20 @@ pub(crate) fn load_map(
             ))
             .id();
 
+        if parallax != parent_parallax {
+            commands.entity(layer_entity).insert(TiledLayerParallax {
+                map: map_entity,
+                parallax,
+                parent_parallax,
+                origin: layer_position,
+            });
+        }
+
         let layer_event = TiledLayerCreated {
             map: map_event,
             entity: layer_entity,
             id: layer_id,
+            parent: parent.map(|index| spawned_layers[index].0),
         };
 
         match layer.layer_type() {
@@ -101,17 +138,29 @@ pub(crate) fn load_map(
                     Name::new(format!("TiledMapTileLayer({})", layer.name)),
                     TiledMapTileLayer,
                 ));
-                load_tiles_layer(
-                    commands,
-                    tiled_map,
-                    &layer_event,
-                    layer,
-                    tile_layer,
-                    render_settings,
-                    &mut tiled_id_storage.tiles,
-                    &mut special_tile_events,
-                    anchor,
-                );
+                if chunk_streaming
+                    && matches!(tile_layer, TileLayer::Infinite(_))
+                    && get_map_type(&tiled_map.map)
+                        != TilemapType::Isometric(IsoCoordSystem::Staggered)
+                {
+                    // Chunks will be spawned later on, around cameras
+                    streamed_layers.push((layer_event, opacity));
+                } else {
+                    load_tiles_layer(
+                        commands,
+                        tiled_map,
+                        &layer_event,
+                        layer_entity,
+                        None,
+                        layer,
+                        tile_layer,
+                        render_settings,
+                        &mut tiled_id_storage.tiles,
+                        &mut special_tile_events,
+                        anchor,
+                        opacity,
+                    );
+                }
             }
             LayerType::Objects(object_layer) => {
                 commands.entity(layer_entity).insert((
@@ -126,32 +175,42 @@ pub(crate) fn load_map(
                     &mut tiled_id_storage.objects,
                     &mut object_events,
                     anchor,
+                    opacity,
                 );
             }
             LayerType::Group(_group_layer) => {
+                // Children layers come right after their group and will be attached to it
                 commands.entity(layer_entity).insert((
                     Name::new(format!("TiledMapGroupLayer({})", layer.name)),
                     TiledMapGroupLayer,
                 ));
-                warn!("Group layers are not yet implemented");
             }
             LayerType::Image(image_layer) => {
-                let pos = from_tiled_position_to_world_space(
-                    tiled_map,
-                    anchor,
-                    Vec2::new(layer.offset_x, layer.offset_y),
-                );
                 commands.entity(layer_entity).insert((
                     Name::new(format!("TiledMapImageLayer({})", layer.name)),
                     TiledMapImageLayer,
-                    Transform::from_translation(pos.extend(offset_z)),
                 ));
-                load_image_layer(commands, tiled_map, &layer_event, image_layer, asset_server);
+                load_image_layer(
+                    commands,
+                    tiled_map,
+                    &layer_event,
+                    image_layer,
+                    asset_server,
+                    opacity,
+                );
             }
         };
 
         tiled_id_storage.layers.insert(layer.id(), layer_entity);
         layer_events.push(layer_event);
+        spawned_layers.push((layer_entity, offset_z, opacity, parallax));
+    }
+
+    if chunk_streaming {
+        commands.entity(map_entity).insert(TiledMapChunkStreaming {
+            layers: streamed_layers,
+            ..default()
+        });
     }
 
     #[cfg(feature = "user_properties")]
@@ -207,12 +266,15 @@ fn load_tiles_layer(
     commands: &mut Commands,
     tiled_map: &TiledMap,
     layer_event: &TiledLayerCreated,
+    parent: Entity,
+    chunk: Option<(i32, i32)>,
     layer: Layer,
     tiles_layer: TileLayer,
     _render_settings: &TilemapRenderSettings,
     entity_map: &mut HashMap<(String, TileId), Vec<Entity>>,
     event_list: &mut Vec<TiledTileCreated>,
     _anchor: &TilemapAnchor,
+    opacity: f32,
 ) {
     // The TilemapBundle requires that all tile images come exclusively from a single
     // tiled texture or from a Vec of independent per-tile images. Furthermore, all of
@@ -229,6 +291,36 @@ fn load_tiles_layer(
             continue;
         }
 
+        let grid_size = get_grid_size(&tiled_map.map);
+        let map_type = get_map_type(&tiled_map.map);
+        let tile_size = TilemapTileSize {
+            x: tileset.tile_width as f32,
+            y: tileset.tile_height as f32,
+        };
+
+        // A chunk is a small tilemap of its own, moved where the chunk lies in the whole map
+        let (tilemap_size, tilemap_anchor, transform) = match chunk {
+            Some(chunk_pos) => (
+                TilemapSize {
+                    x: ChunkData::WIDTH,
+                    y: ChunkData::HEIGHT,
+                },
+                TilemapAnchor::None,
+                Transform::from_translation(
+                    chunk_origin_tile_pos(tiled_map, chunk_pos)
+                        .center_in_world(
+                            &tiled_map.tilemap_size,
+                            &grid_size,
+                            &tile_size,
+                            &map_type,
+                            _anchor,
+                        )
+                        .extend(0.),
+                ),
+            ),
+            None => (tiled_map.tilemap_size, *_anchor, Transform::default()),
+        };
+
         let layer_for_tileset_entity = commands
             .spawn((
                 Name::new(format!(
@@ -236,7 +328,8 @@ fn load_tiles_layer(
                     layer.name, tileset.name
                 )),
                 TiledMapTileLayerForTileset,
-                ChildOf(layer_event.entity),
+                ChildOf(parent),
+                transform,
             ))
             .id();
 
@@ -248,31 +341,31 @@ fn load_tiles_layer(
             &t.tilemap_texture,
             tileset_index,
             &tiles_layer,
+            chunk,
+            tilemap_size,
             entity_map,
             event_list,
+            opacity,
         );
 
         #[cfg(feature = "render")]
         {
-            let grid_size = get_grid_size(&tiled_map.map);
             commands
                 .entity(layer_for_tileset_entity)
                 .insert(TilemapBundle {
                     grid_size,
-                    size: tiled_map.tilemap_size,
+                    size: tilemap_size,
                     storage: _tile_storage,
                     texture: t.tilemap_texture.clone(),
-                    tile_size: TilemapTileSize {
-                        x: tileset.tile_width as f32,
-                        y: tileset.tile_height as f32,
-                    },
+                    tile_size,
                     spacing: TilemapSpacing {
                         x: tileset.spacing as f32,
                         y: tileset.spacing as f32,
                     },
-                    map_type: get_map_type(&tiled_map.map),
+                    map_type,
                     render_settings: *_render_settings,
-                    anchor: *_anchor,
+                    anchor: tilemap_anchor,
+                    transform,
                     ..default()
                 });
         }
@@ -288,22 +381,32 @@ fn load_tiles(
     tilemap_texture: &TilemapTexture,
     tileset_index: usize,
     tiles_layer: &TileLayer,
+    chunk: Option<(i32, i32)>,
+    tilemap_size: TilemapSize,
     entity_map: &mut HashMap<(String, TileId), Vec<Entity>>,
     event_list: &mut Vec<TiledTileCreated>,
+    opacity: f32,
 ) -> TileStorage {
-    let tilemap_size = tiled_map.tilemap_size;
     let mut tile_storage = TileStorage::empty(tilemap_size);
-    for_each_tile(
-        tiled_map,
-        tiles_layer,
-        |layer_tile, layer_tile_data, tile_pos, index| {
-            let Some(tile) = layer_tile.get_tile() else {
-                return;
-            };
-            if tileset_index != layer_tile.tileset_index() {
-                return;
-            }
-            let texture_index = match tilemap_texture {
+    // Tiles of a chunk are positioned relatively to the chunk tilemap
+    let origin = chunk
+        .map(|chunk_pos| chunk_origin_tile_pos(tiled_map, chunk_pos))
+        .unwrap_or(TilePos { x: 0, y: 0 });
+    let spawn_tile = |layer_tile: LayerTile,
+                      layer_tile_data: &LayerTileData,
+                      map_pos: TilePos,
+                      index| {
+        let tile_pos = TilePos {
+            x: map_pos.x - origin.x,
+            y: map_pos.y - origin.y,
+        };
+        let Some(tile) = layer_tile.get_tile() else {
+            return;
+        };
+        if tileset_index != layer_tile.tileset_index() {
+            return;
+        }
+        let texture_index = match tilemap_texture {
                 TilemapTexture::Single(_) => layer_tile.id(),
                 #[cfg(not(feature = "atlas"))]
                 TilemapTexture::Vector(_) => *tiled_map
@@ -316,57 +419,135 @@ fn load_tiles(
                 #[cfg(not(feature = "atlas"))]
                 _ => unreachable!(),
             };
-            let tile_entity = commands
-                .spawn((
-                    TileBundle {
-                        position: tile_pos,
-                        tilemap_id: TilemapId(layer_for_tileset_entity),
-                        texture_index: TileTextureIndex(texture_index),
-                        flip: TileFlip {
-                            x: layer_tile_data.flip_h,
-                            y: layer_tile_data.flip_v,
-                            d: layer_tile_data.flip_d,
-                        },
-                        ..default()
+        let tile_entity = commands
+            .spawn((
+                TileBundle {
+                    position: tile_pos,
+                    tilemap_id: TilemapId(layer_for_tileset_entity),
+                    texture_index: TileTextureIndex(texture_index),
+                    flip: TileFlip {
+                        x: layer_tile_data.flip_h,
+                        y: layer_tile_data.flip_v,
+                        d: layer_tile_data.flip_d,
                     },
-                    Name::new(format!("TiledMapTile({},{})", tile_pos.x, tile_pos.y)),
-                    TiledMapTile,
-                    ChildOf(layer_for_tileset_entity),
-                ))
-                .id();
-
-            // Handle animated tiles
-            if let Some(animated_tile) = get_animated_tile(&tile) {
+                    color: TileColor(Color::WHITE.with_alpha(opacity)),
+                    ..default()
+                },
+                Name::new(format!("TiledMapTile({},{})", map_pos.x, map_pos.y)),
+                TiledMapTile,
+                ChildOf(layer_for_tileset_entity),
+            ))
+            .id();
+
+        // Handle animated tiles
+        let tile_texture_index = |tile_id: TileId| match tilemap_texture {
+            TilemapTexture::Single(_) => Some(tile_id),
+            #[cfg(not(feature = "atlas"))]
+            TilemapTexture::Vector(_) => tiled_map
+                .tilesets
+                .get(&tileset_index)
+                .and_then(|t| t.tile_image_offsets.get(&tile_id).copied()),
+            #[cfg(not(feature = "atlas"))]
+            _ => unreachable!(),
+        };
+        match get_tile_animation(&tile, tile_texture_index) {
+            Some(TileAnimation::Gpu(animated_tile)) => {
                 commands.entity(tile_entity).insert(animated_tile);
             }
+            Some(TileAnimation::Cpu(animation)) => {
+                if let Some(index) = animation.index() {
+                    commands
+                        .entity(tile_entity)
+                        .insert((TileTextureIndex(index), animation));
+                }
+            }
+            None => {}
+        }
 
-            // Handle custom tiles (with user properties)
-            if !tile.properties.is_empty() {
-                event_list.push(TiledTileCreated {
-                    layer: *layer_event,
-                    parent: layer_for_tileset_entity,
-                    entity: tile_entity,
-                    index,
-                    position: tile_pos,
-                });
+        // Handle custom tiles (with user properties)
+        if !tile.properties.is_empty() {
+            event_list.push(TiledTileCreated {
+                layer: *layer_event,
+                parent: layer_for_tileset_entity,
+                entity: tile_entity,
+                index,
+                position: map_pos,
+            });
+        }
+
+        // Update map storage with tile entity
+        let key = (tile.tileset().name.clone(), layer_tile.id());
+        entity_map
+            .entry(key)
+            .and_modify(|entities| {
+                entities.push(tile_entity);
+            })
+            .or_insert(vec![tile_entity]);
+
+        // Add our tile to the bevy_ecs_tilemap::TileStorage
+        tile_storage.set(&tile_pos, tile_entity);
+    };
+    match (chunk, tiles_layer) {
+        (Some(chunk_pos), TileLayer::Infinite(layer)) => {
+            if let Some(chunk) = layer.get_chunk(chunk_pos.0, chunk_pos.1) {
+                for_each_tile_in_chunk(tiled_map, chunk_pos, &chunk, spawn_tile);
             }
+        }
+        _ => for_each_tile(tiled_map, tiles_layer, spawn_tile),
+    }
+    tile_storage
+}
 
-            // Update map storage with tile entity
-            let key = (tile.tileset().name.clone(), layer_tile.id());
-            entity_map
-                .entry(key)
-                .and_modify(|entities| {
-                    entities.push(tile_entity);
-                })
-                .or_insert(vec![tile_entity]);
-
-            // Add our tile to the bevy_ecs_tilemap::TileStorage
-            tile_storage.set(&tile_pos, tile_entity);
-        },
+/// Spawn a single chunk of an infinite tiles layer, see [TiledMapChunkStreaming].
+///
+/// Returns `None` if the layer has no tile in this chunk.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn load_tiles_chunk(
+    commands: &mut Commands,
+    tiled_map: &TiledMap,
+    layer_event: &TiledLayerCreated,
+    chunk_pos: (i32, i32),
+    render_settings: &TilemapRenderSettings,
+    entity_map: &mut HashMap<(String, TileId), Vec<Entity>>,
+    event_list: &mut Vec<TiledTileCreated>,
+    anchor: &TilemapAnchor,
+    opacity: f32,
+) -> Option<Entity> {
+    let layer = get_layer_from_index(&tiled_map.map, layer_event.id)?;
+    let tiles_layer = layer.as_tile_layer()?;
+    let TileLayer::Infinite(infinite_layer) = &tiles_layer else {
+        return None;
+    };
+    infinite_layer.get_chunk(chunk_pos.0, chunk_pos.1)?;
+
+    let chunk_entity = commands
+        .spawn((
+            Name::new(format!(
+                "TiledMapTileChunk({},{})",
+                chunk_pos.0, chunk_pos.1
+            )),
+            TiledMapTileChunk(IVec2::new(chunk_pos.0, chunk_pos.1)),
+            ChildOf(layer_event.entity),
+        ))
+        .id();
+    load_tiles_layer(
+        commands,
+        tiled_map,
+        layer_event,
+        chunk_entity,
+        Some(chunk_pos),
+        layer,
+        tiles_layer,
+        render_settings,
+        entity_map,
+        event_list,
+        anchor,
+        opacity,
     );
-    tile_storage
+    Some(chunk_entity)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn load_objects_layer(
     commands: &mut Commands,
     tiled_map: &TiledMap,
@@ -375,6 +556,7 @@ fn load_objects_layer(
     entity_map: &mut HashMap<u32, Entity>,
     event_list: &mut Vec<TiledObjectCreated>,
     anchor: &TilemapAnchor,
+    opacity: f32,
 ) {
     for (object_id, object_data) in object_layer.objects().enumerate() {
         let object_position = from_tiled_position_to_world_space(
@@ -400,57 +582,61 @@ fn load_objects_layer(
 
         // Handle objects containing tile data: we want to add a Sprite component to the object with the tile image
         if let Some(tile) = object_data.get_tile() {
-            match tile.tileset_location() {
-                TilesetLocation::Map(tileset_index) => {
-                    sprite = tiled_map.tilesets.get(tileset_index).and_then(|t| {
-                        match &t.tilemap_texture {
-                            TilemapTexture::Single(single) => {
-                                t.texture_atlas_layout_handle.as_ref().map(|handle| {
-                                    Sprite {
-                                        image: single.clone(),
-                                        texture_atlas: Some(TextureAtlas {
-                                            layout: handle.clone(),
-                                            index: tile.id() as usize,
-                                        }),
-                                        anchor: Anchor::BottomLeft,
-                                        ..default()
-                                    }
-                                })
-                            },
-                            #[cfg(not(feature = "atlas"))]
-                            TilemapTexture::Vector(vector) => {
-                                let index = *t.tile_image_offsets.get(&tile.id())
-                                    .expect("The offset into to image vector should have been saved during the initial load.");
-                                vector.get(index as usize).map(|image| {
-                                    Sprite {
-                                        image: image.clone(),
-                                        anchor: Anchor::BottomLeft,
-                                        ..default()
-                                    }
-                                })
-                            }
-                            #[cfg(not(feature = "atlas"))]
-                            _ => unreachable!(),
-                        }
-                    });
-
-                    // Handle the case of an animated tile
-                    animation =
-                        tile.get_tile()
-                            .and_then(|t| get_animated_tile(&t))
-                            .map(|animation| TiledAnimation {
-                                start: animation.start as usize,
-                                end: animation.end as usize,
-                                timer: Timer::from_seconds(
-                                    1. / (animation.speed
-                                        * (animation.end - animation.start) as f32),
-                                    TimerMode::Repeating,
-                                ),
-                            });
-                }
-                TilesetLocation::Template(_) => {
-                    log::warn!("Objects from template are not yet supported");
+            // Objects from a template can use a tileset which is not part of the map,
+            // it has been loaded alongside the map tilesets
+            let tileset_index = match tile.tileset_location() {
+                TilesetLocation::Map(tileset_index) => Some(*tileset_index),
+                TilesetLocation::Template(tileset) => {
+                    tiled_map.template_tilesets.get(&tileset.source).copied()
                 }
+            };
+            if let Some(tileset_index) = tileset_index {
+                sprite = tiled_map.tilesets.get(&tileset_index).and_then(|t| {
+                    match &t.tilemap_texture {
+                        TilemapTexture::Single(single) => {
+                            t.texture_atlas_layout_handle.as_ref().map(|handle| {
+                                Sprite {
+                                    image: single.clone(),
+                                    texture_atlas: Some(TextureAtlas {
+                                        layout: handle.clone(),
+                                        index: tile.id() as usize,
+                                    }),
+                                    anchor: Anchor::BottomLeft,
+                                    color: Color::WHITE.with_alpha(opacity),
+                                    ..default()
+                                }
+                            })
+                        },
+                        #[cfg(not(feature = "atlas"))]
+                        TilemapTexture::Vector(vector) => {
+                            let index = *t.tile_image_offsets.get(&tile.id())
+                                .expect("The offset into to image vector should have been saved during the initial load.");
+                            vector.get(index as usize).map(|image| {
+                                Sprite {
+                                    image: image.clone(),
+                                    anchor: Anchor::BottomLeft,
+                                    color: Color::WHITE.with_alpha(opacity),
+                                    ..default()
+                                }
+                            })
+                        }
+                        #[cfg(not(feature = "atlas"))]
+                        _ => unreachable!(),
+                    }
+                });
+
+                // Handle the case of an animated tile
+                animation = tiled_map.tilesets.get(&tileset_index).and_then(|t| {
+                    let object_texture_index = |tile_id: TileId| match &t.tilemap_texture {
+                        TilemapTexture::Single(_) => Some(tile_id),
+                        #[cfg(not(feature = "atlas"))]
+                        TilemapTexture::Vector(_) => t.tile_image_offsets.get(&tile_id).copied(),
+                        #[cfg(not(feature = "atlas"))]
+                        _ => unreachable!(),
+                    };
+                    tile.get_tile()
+                        .and_then(|t| get_tile_animation(&t, object_texture_index))
+                });
             }
         }
 
@@ -465,7 +651,28 @@ fn load_objects_layer(
                     },
                 ));
             }
-            (Some(sprite), Some(animation)) => {
+            (Some(sprite), Some(TileAnimation::Gpu(animation))) => {
+                commands.entity(object_entity).insert((
+                    sprite,
+                    TiledAnimation {
+                        start: animation.start as usize,
+                        end: animation.end as usize,
+                        timer: Timer::from_seconds(
+                            1. / (animation.speed * (animation.end - animation.start) as f32),
+                            TimerMode::Repeating,
+                        ),
+                    },
+                    if object_data.visible {
+                        Visibility::Inherited
+                    } else {
+                        Visibility::Hidden
+                    },
+                ));
+            }
+            (Some(mut sprite), Some(TileAnimation::Cpu(animation))) => {
+                if let (Some(atlas), Some(index)) = (&mut sprite.texture_atlas, animation.index()) {
+                    atlas.index = index as usize;
+                }
                 commands.entity(object_entity).insert((
                     sprite,
                     animation,
@@ -493,6 +700,7 @@ fn load_image_layer(
     layer_event: &TiledLayerCreated,
     image_layer: ImageLayer,
     asset_server: &Res<AssetServer>,
+    opacity: f32,
 ) {
     if let Some(image) = &image_layer.image {
         let image_position = match get_map_type(&tiled_map.map) {
@@ -514,6 +722,7 @@ fn load_image_layer(
             Sprite {
                 image: asset_server.load(image.source.clone()),
                 anchor: Anchor::TopLeft,
+                color: Color::WHITE.with_alpha(opacity),
                 ..default()
             },
             Transform::from_xyz(image_position.x, image_position.y, 0.),
@@ -521,33 +730,51 @@ fn load_image_layer(
     }
 }
 
-fn get_animated_tile(tile: &Tile) -> Option<AnimatedTile> {
-    let Some(animation_data) = &tile.animation else {
-        return None;
-    };
-    let mut previous_tile_id = None;
-    let first_tile = animation_data.iter().next()?;
-    let last_tile = animation_data.iter().last()?;
+/// Animation of a tile, as played by [bevy_ecs_tilemap] when possible
+enum TileAnimation {
+    /// Constant frame duration with consecutive frames: handled by [bevy_ecs_tilemap]
+    Gpu(AnimatedTile),
+    /// Anything else, played on the CPU
+    Cpu(TiledFrameAnimation),
+}
 
-    // Sanity checks: current limitations from bevy_ecs_tilemap
+/// Get a tile animation, `texture_index` giving the texture index of a tileset tile.
+fn get_tile_animation(
+    tile: &Tile,
+    texture_index: impl Fn(TileId) -> Option<u32>,
+) -> Option<TileAnimation> {
+    let animation_data = tile.animation.as_ref()?;
+    let mut frames = Vec::with_capacity(animation_data.len());
     for frame in animation_data {
-        if frame.duration != first_tile.duration {
-            log::warn!("Animated tile with non constant frame duration is currently not supported");
+        let Some(index) = texture_index(frame.tile_id) else {
+            log::warn!(
+                "Animated tile frame references tile {} which has no image, skip animation",
+                frame.tile_id
+            );
             return None;
-        }
-        if let Some(id) = previous_tile_id {
-            if frame.tile_id != id + 1 {
-                log::warn!("Animated tile with non-aligned frame tiles is currently not supported");
-                return None;
-            }
-        }
-        previous_tile_id = Some(frame.tile_id);
+        };
+        // duration is in ms
+        frames.push(TiledAnimationFrame {
+            index,
+            duration: frame.duration as f32 / 1000.,
+        });
+    }
+    let first_frame = frames.first()?;
+    let last_frame = frames.last()?;
+
+    // bevy_ecs_tilemap can only play constant frame duration over consecutive textures
+    let gpu_compatible = first_frame.duration > 0.
+        && frames.iter().all(|f| f.duration == first_frame.duration)
+        && frames.windows(2).all(|f| f[1].index == f[0].index + 1);
+    if !gpu_compatible {
+        debug!("Tile animation cannot be played by bevy_ecs_tilemap, play it on the CPU");
+        return Some(TileAnimation::Cpu(TiledFrameAnimation::new(frames)));
     }
 
-    // duration is in ms and we want a 'frames per second' speed
-    Some(AnimatedTile {
-        start: first_tile.tile_id,
-        end: last_tile.tile_id + 1,
-        speed: 1000. / (first_tile.duration * (last_tile.tile_id - first_tile.tile_id + 1)) as f32,
-    })
+    // we want a 'frames per second' speed
+    Some(TileAnimation::Gpu(AnimatedTile {
+        start: first_frame.index,
+        end: last_frame.index + 1,
+        speed: 1. / (first_frame.duration * frames.len() as f32),
+    }))
 }
@@ -49,11 +49,17 @@ pub(crate) fn build(app: &mut bevy::prelude::App) {
         .register_type::<TiledMapTileLayer>()
         .register_type::<TiledMapTileLayerForTileset>()
         .register_type::<TiledMapObjectLayer>()
+        .register_type::<TiledMapGroupLayer>()
+        .register_type::<TiledLayerParallax>()
+        .register_type::<TiledParallaxCamera>()
         .register_type::<TiledMapImageLayer>()
+        .register_type::<TiledMapChunkStreaming>()
+        .register_type::<TiledMapTileChunk>()
         .register_type::<TiledMapTile>()
         .register_type::<TiledMapObject>()
         .register_type::<TiledMapImage>()
         .register_type::<TiledAnimation>()
+        .register_type::<TiledFrameAnimation>()
         .add_event::<TiledMapCreated>()
         .register_type::<TiledMapCreated>()
         .add_event::<TiledLayerCreated>()
@@ -62,9 +68,22 @@ pub(crate) fn build(app: &mut bevy::prelude::App) {
         .register_type::<TiledObjectCreated>()
         .add_event::<TiledTileCreated>()
         .register_type::<TiledTileCreated>()
+        .add_event::<TiledChunkCreated>()
+        .register_type::<TiledChunkCreated>()
         .add_systems(PreUpdate, process_loaded_maps)
-        .add_systems(Update, animate_tiled_sprites)
-        .add_systems(PostUpdate, handle_map_events);
+        .add_systems(
+            Update,
+            (
+                animate_tiled_sprites,
+                animate_tiled_frames_sprites,
+                animate_tiled_frames_tiles,
+            ),
+        )
+        .add_systems(PostUpdate, handle_map_events)
+        .add_systems(
+            PostUpdate,
+            update_layers_parallax.before(TransformSystem::TransformPropagate),
+        );
 }
 
 /// Export a Tiled types to the given path.
@@ -86,6 +105,48 @@ pub fn export_types(
     serde_json::to_writer_pretty(writer, &list).unwrap();
 }
 
+/// Merge Tiled types into the `propertyTypes` of an existing `.tiled-project` file.
+///
+/// Types previously exported (listed in a project property) keep their ID, or are
+/// removed if they are not exported anymore. Other types and project settings are kept as is.
+/// The project file is left untouched if it cannot be read.
+///
+/// The predicate determines whether a symbol is exported, see [export_types].
+#[cfg(feature = "user_properties")]
+pub fn export_types_to_project(
+    reg: &AppTypeRegistry,
+    path: impl AsRef<Path>,
+    predicate: impl Fn(&str) -> bool,
+) {
+    use std::ops::Deref;
+    let path = path.as_ref();
+    let mut project: serde_json::Value = match std::fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|data| serde_json::from_str(&data).map_err(|e| e.to_string()))
+    {
+        Ok(project) => project,
+        Err(e) => {
+            error!("Cannot read Tiled project '{}': {e}", path.display());
+            return;
+        }
+    };
+    let registry = crate::properties::export::TypeExportRegistry::from_registry(reg.read().deref());
+    let mut list = registry.to_vec();
+    list.retain(|v| predicate(&v.name));
+    if let Err(e) = crate::properties::export::merge_into_project(&mut project, list) {
+        error!("Cannot merge Tiled types into '{}': {e}", path.display());
+        return;
+    }
+    match serde_json::to_string_pretty(&project) {
+        Ok(data) => {
+            if let Err(e) = std::fs::write(path, data) {
+                error!("Cannot write Tiled project '{}': {e}", path.display());
+            }
+        }
+        Err(e) => error!("Cannot serialize Tiled project '{}': {e}", path.display()),
+    }
+}
+
 /// System to spawn a map once it has been fully loaded.
 #[allow(clippy::type_complexity)]
 pub(crate) fn process_loaded_maps(
@@ -100,6 +161,7 @@ pub(crate) fn process_loaded_maps(
             &TilemapRenderSettings,
             &TilemapAnchor,
             &TiledMapLayerZOffset,
+            Has<TiledMapChunkStreaming>,
         ),
         Or<(
             Changed<TiledMapHandle>,
@@ -111,8 +173,15 @@ pub(crate) fn process_loaded_maps(
     >,
     mut event_writers: TiledMapEventWriters,
 ) {
-    for (map_entity, map_handle, mut tiled_id_storage, render_settings, anchor, layer_offset) in
-        map_query.iter_mut()
+    for (
+        map_entity,
+        map_handle,
+        mut tiled_id_storage,
+        render_settings,
+        anchor,
+        layer_offset,
+        chunk_streaming,
+    ) in map_query.iter_mut()
     {
         if let Some(load_state) = asset_server.get_recursive_dependency_load_state(&map_handle.0) {
             if !load_state.is_loaded() {
@@ -157,6 +226,7 @@ pub(crate) fn process_loaded_maps(
                 &asset_server,
                 &mut event_writers,
                 anchor,
+                chunk_streaming,
             );
 
             // Remove the respawn marker
@@ -201,21 +271,48 @@ fn handle_map_events(
 }
 
 fn remove_layers(commands: &mut Commands, tiled_id_storage: &mut TiledMapStorage) {
+    // Layers nested in a group layer are despawned along with their parent
     for layer_entity in tiled_id_storage.layers.values() {
-        commands.entity(*layer_entity).despawn();
+        commands.entity(*layer_entity).try_despawn();
     }
     tiled_id_storage.layers.clear();
     tiled_id_storage.objects.clear();
     tiled_id_storage.tiles.clear();
 }
 
+/// System to move layers with a parallax factor according to the [TiledParallaxCamera] position.
+fn update_layers_parallax(
+    camera_query: Query<&GlobalTransform, With<TiledParallaxCamera>>,
+    map_query: Query<&GlobalTransform, With<TiledMapMarker>>,
+    mut layer_query: Query<(&TiledLayerParallax, &mut Transform)>,
+) {
+    let Ok(camera_transform) = camera_query.single() else {
+        return;
+    };
+    for (parallax, mut transform) in layer_query.iter_mut() {
+        let Ok(map_transform) = map_query.get(parallax.map) else {
+            continue;
+        };
+        // Camera position relative to the map origin
+        let camera_position = map_transform
+            .affine()
+            .inverse()
+            .transform_point3(camera_transform.translation())
+            .truncate();
+        let translation =
+            parallax.origin + camera_position * (parallax.parent_parallax - parallax.parallax);
+        transform.translation.x = translation.x;
+        transform.translation.y = translation.y;
+    }
+}
+
 fn animate_tiled_sprites(
     time: Res<Time>,
     mut sprite_query: Query<(&mut TiledAnimation, &mut Sprite)>,
 ) {
     for (mut animation, mut sprite) in sprite_query.iter_mut() {
         animation.timer.tick(time.delta());
-        
+
         if animation.timer.just_finished() {
             if let Some(atlas) = &mut sprite.texture_atlas {
                 atlas.index += 1;
@@ -226,3 +323,27 @@ fn animate_tiled_sprites(
         }
     }
 }
+
+fn animate_tiled_frames_sprites(
+    time: Res<Time>,
+    mut sprite_query: Query<(&mut TiledFrameAnimation, &mut Sprite)>,
+) {
+    for (mut animation, mut sprite) in sprite_query.iter_mut() {
+        if let Some(index) = animation.tick(time.delta_secs()) {
+            if let Some(atlas) = &mut sprite.texture_atlas {
+                atlas.index = index as usize;
+            }
+        }
+    }
+}
+
+fn animate_tiled_frames_tiles(
+    time: Res<Time>,
+    mut tile_query: Query<(&mut TiledFrameAnimation, &mut TileTextureIndex)>,
+) {
+    for (mut animation, mut texture_index) in tile_query.iter_mut() {
+        if let Some(index) = animation.tick(time.delta_secs()) {
+            texture_index.0 = index;
+        }
+    }
+}
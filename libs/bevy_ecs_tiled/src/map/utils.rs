@@ -2,7 +2,10 @@
 use crate::tile_size_from_grid;
 use bevy::prelude::*;
 use bevy_ecs_tilemap::prelude::*;
-use tiled::{ChunkData, LayerTile, LayerTileData, Map, TileLayer};
+use tiled::{
+    Chunk, ChunkData, Layer, LayerTile, LayerTileData, LayerType, Map, StaggerAxis, StaggerIndex,
+    TileLayer,
+};
 
 use super::TiledMap;
 
@@ -26,10 +29,139 @@ pub fn get_map_type(map: &Map) -> TilemapType {
             _ => unreachable!(),
         },
         tiled::Orientation::Isometric => TilemapType::Isometric(IsoCoordSystem::Diamond),
-        tiled::Orientation::Staggered => {
-            panic!("Isometric (Staggered) map is not supported");
+        tiled::Orientation::Staggered => TilemapType::Isometric(IsoCoordSystem::Staggered),
+    }
+}
+
+/// Returns `(stagger along X axis, parity offset)` for a staggered isometric map.
+///
+/// Parity offset is 0 when odd rows / columns are shifted, 1 when even ones are.
+fn stagger_params(stagger_axis: StaggerAxis, stagger_index: StaggerIndex) -> (bool, u32) {
+    (
+        stagger_axis == StaggerAxis::X,
+        match stagger_index {
+            StaggerIndex::Odd => 0,
+            StaggerIndex::Even => 1,
+        },
+    )
+}
+
+/// Size of the `bevy_ecs_tilemap` grid holding a staggered isometric map.
+///
+/// `bevy_ecs_tilemap` [IsoCoordSystem::Staggered] grid is sheared compared to Tiled one:
+/// we need a bigger grid to fit a Tiled map of `tiled_size` tiles.
+pub fn staggered_tilemap_size(
+    stagger_axis: StaggerAxis,
+    stagger_index: StaggerIndex,
+    tiled_size: TilemapSize,
+) -> TilemapSize {
+    let (axis_x, offset) = stagger_params(stagger_axis, stagger_index);
+    if axis_x {
+        TilemapSize {
+            x: (tiled_size.x.saturating_sub(1) + offset).div_ceil(2) + tiled_size.y,
+            y: 2 * tiled_size.y,
+        }
+    } else {
+        TilemapSize {
+            x: tiled_size.x + (tiled_size.y.saturating_sub(1) + offset).div_ceil(2),
+            y: tiled_size.y,
+        }
+    }
+}
+
+/// Convert Tiled tile coordinates of a staggered isometric map to a `bevy_ecs_tilemap` [TilePos].
+///
+/// Tiled coordinates are relative to the top-left tile of the map, which has `tiled_size` tiles.
+/// See [staggered_tilemap_size] for the size of the resulting grid.
+pub fn staggered_tile_pos(
+    stagger_axis: StaggerAxis,
+    stagger_index: StaggerIndex,
+    tiled_size: TilemapSize,
+    tiled_pos: UVec2,
+) -> TilePos {
+    let (axis_x, offset) = stagger_params(stagger_axis, stagger_index);
+    let UVec2 { x, y } = tiled_pos;
+    if axis_x {
+        let shifted = (x + offset) % 2;
+        TilePos {
+            x: (x + offset).div_ceil(2) + y,
+            y: 2 * tiled_size.y - 1 - 2 * y - shifted,
+        }
+    } else {
+        TilePos {
+            x: x + (y + offset).div_ceil(2),
+            y: tiled_size.y - 1 - y,
+        }
+    }
+}
+
+/// Convert a position in Tiled pixels of a staggered isometric map to `bevy_ecs_tilemap` grid space,
+/// ie. the space where [TilePos::center_in_world] lives before applying the [TilemapAnchor].
+///
+/// Tiled position is relative to the top-left corner of the map, which has `tiled_size` tiles.
+pub fn staggered_projection(
+    stagger_axis: StaggerAxis,
+    stagger_index: StaggerIndex,
+    tiled_size: TilemapSize,
+    grid_size: &TilemapGridSize,
+    tiled_position: Vec2,
+) -> Vec2 {
+    let (axis_x, offset) = stagger_params(stagger_axis, stagger_index);
+    let rows = tiled_size.y as f32;
+    let offset = offset as f32;
+    if axis_x {
+        Vec2 {
+            x: tiled_position.x + (offset + 2. * rows - 2.) * grid_size.x / 2.,
+            y: rows * grid_size.y - tiled_position.y,
+        }
+    } else {
+        Vec2 {
+            x: tiled_position.x + (offset + rows - 2.) * grid_size.x / 2.,
+            y: rows * grid_size.y / 2. - tiled_position.y,
+        }
+    }
+}
+
+/// List all layers of a [Map], including the ones nested in group layers.
+///
+/// Layers come in the .TMX order, bottom to top, a group layer
+/// being listed right before its children.
+/// Each layer is paired with the index of its parent group in the returned list
+/// and with its own index inside that group (or inside the map for top level layers).
+pub fn get_layers_with_parent(map: &Map) -> Vec<(Layer<'_>, Option<usize>, usize)> {
+    fn visit<'a>(
+        layers: impl Iterator<Item = Layer<'a>>,
+        parent: Option<usize>,
+        out: &mut Vec<(Layer<'a>, Option<usize>, usize)>,
+    ) {
+        for (index, layer) in layers.enumerate() {
+            let position = out.len();
+            out.push((layer, parent, index));
+            if let LayerType::Group(group) = layer.layer_type() {
+                visit(group.layers(), Some(position), out);
+            }
         }
     }
+    let mut out = Vec::new();
+    visit(map.layers(), None, &mut out);
+    out
+}
+
+/// List all layers of a [Map], including the ones nested in group layers.
+///
+/// See [get_layers_with_parent] for ordering.
+pub fn get_all_layers(map: &Map) -> Vec<Layer<'_>> {
+    get_layers_with_parent(map)
+        .into_iter()
+        .map(|(layer, _, _)| layer)
+        .collect()
+}
+
+/// Retrieve a [Layer] from its index in [get_all_layers].
+///
+/// For maps without group layers, this is the same as [Map::get_layer].
+pub fn get_layer_from_index(map: &Map, index: usize) -> Option<Layer<'_>> {
+    get_all_layers(map).into_iter().nth(index)
 }
 
 /// Convert a [Map]'s grid size to a [TilemapGridSize]
@@ -116,83 +248,130 @@ pub fn from_tiled_position_to_world_space(
                 }
             }
             TilemapType::Isometric(IsoCoordSystem::Staggered) => {
-                panic!("Isometric (Staggered) map is not supported");
+                // Undo the half tile offset above: staggered projection gives tile centers
+                Vec2::new(grid_size.x / 2., grid_size.y / 2.)
+                    + tiled_map.tiled_offset
+                    + staggered_projection(
+                        tiled_map.map.stagger_axis,
+                        tiled_map.map.stagger_index,
+                        tiled_map.tiled_size,
+                        &grid_size,
+                        tiled_position,
+                    )
             }
             _ => unreachable!(),
         }
 }
 
+/// Convert a Tiled tile index into a bevy_ecs_tilemap [TilePos]
+///
+/// For infinite maps, index is relative to the top-left chunk of the map.
+fn tile_pos_from_index(tiled_map: &TiledMap, index: IVec2) -> TilePos {
+    let tiled_size = tiled_map.tiled_size;
+    match get_map_type(&tiled_map.map) {
+        TilemapType::Isometric(IsoCoordSystem::Staggered) => staggered_tile_pos(
+            tiled_map.map.stagger_axis,
+            tiled_map.map.stagger_index,
+            tiled_size,
+            index.as_uvec2(),
+        ),
+        _ => TilePos {
+            x: index.x as u32,
+            y: tiled_size.y - 1 - index.y as u32,
+        },
+    }
+}
+
 /// Iterate over all tiles from the given [TileLayer]
 pub fn for_each_tile<'a, F>(tiled_map: &'a TiledMap, tiles_layer: &TileLayer<'a>, mut f: F)
 where
     F: FnMut(LayerTile<'a>, &LayerTileData, TilePos, IVec2),
 {
-    let tilemap_size = tiled_map.tilemap_size;
     match tiles_layer {
         TileLayer::Finite(layer) => {
-            for x in 0..tilemap_size.x {
-                for y in 0..tilemap_size.y {
-                    // Transform TMX coords into bevy coords.
-                    let mapped_y = tilemap_size.y - 1 - y;
-                    let mapped_x = x as i32;
-                    let mapped_y = mapped_y as i32;
-
-                    let Some(layer_tile) = layer.get_tile(mapped_x, mapped_y) else {
+            let tiled_size = tiled_map.tiled_size;
+            for x in 0..tiled_size.x as i32 {
+                for y in (0..tiled_size.y as i32).rev() {
+                    let Some(layer_tile) = layer.get_tile(x, y) else {
                         continue;
                     };
-                    let Some(layer_tile_data) = layer.get_tile_data(mapped_x, mapped_y) else {
+                    let Some(layer_tile_data) = layer.get_tile_data(x, y) else {
                         continue;
                     };
 
+                    let index = IVec2::new(x, y);
                     f(
                         layer_tile,
                         layer_tile_data,
-                        TilePos::new(x, y),
-                        IVec2::new(mapped_x, mapped_y),
+                        tile_pos_from_index(tiled_map, index),
+                        index,
                     );
                 }
             }
         }
         TileLayer::Infinite(layer) => {
             for (chunk_pos, chunk) in layer.chunks() {
-                // bevy_ecs_tilemap doesn't support negative tile coordinates, so shift all chunks
-                // such that the top-left chunk is at (0, 0).
-                let chunk_pos_mapped = (
-                    chunk_pos.0 - tiled_map.topleft_chunk.0,
-                    chunk_pos.1 - tiled_map.topleft_chunk.1,
-                );
-
-                for x in 0..ChunkData::WIDTH {
-                    for y in 0..ChunkData::HEIGHT {
-                        // Invert y to match bevy coordinates.
-                        let Some(layer_tile) = chunk.get_tile(x as i32, y as i32) else {
-                            continue;
-                        };
-                        let Some(layer_tile_data) = chunk.get_tile_data(x as i32, y as i32) else {
-                            continue;
-                        };
-
-                        let index = IVec2 {
-                            x: chunk_pos_mapped.0 * ChunkData::WIDTH as i32 + x as i32,
-                            y: chunk_pos_mapped.1 * ChunkData::HEIGHT as i32 + y as i32,
-                        };
-
-                        f(
-                            layer_tile,
-                            layer_tile_data,
-                            TilePos {
-                                x: index.x as u32,
-                                y: tilemap_size.y - 1 - index.y as u32,
-                            },
-                            index,
-                        );
-                    }
-                }
+                for_each_tile_in_chunk(tiled_map, chunk_pos, &chunk, &mut f);
             }
         }
     }
 }
 
+/// Iterate over all tiles from a single [Chunk] of an infinite [TileLayer]
+///
+/// `chunk_pos` is the Tiled chunk index, as returned by [tiled::InfiniteTileLayer::chunks].
+/// Tiles [TilePos] and index are the same than the ones from [for_each_tile].
+pub fn for_each_tile_in_chunk<'a, F>(
+    tiled_map: &'a TiledMap,
+    chunk_pos: (i32, i32),
+    chunk: &Chunk<'a>,
+    mut f: F,
+) where
+    F: FnMut(LayerTile<'a>, &LayerTileData, TilePos, IVec2),
+{
+    // bevy_ecs_tilemap doesn't support negative tile coordinates, so shift all chunks
+    // such that the top-left chunk is at (0, 0).
+    let chunk_pos_mapped = (
+        chunk_pos.0 - tiled_map.topleft_chunk.0,
+        chunk_pos.1 - tiled_map.topleft_chunk.1,
+    );
+
+    for x in 0..ChunkData::WIDTH {
+        for y in 0..ChunkData::HEIGHT {
+            let Some(layer_tile) = chunk.get_tile(x as i32, y as i32) else {
+                continue;
+            };
+            let Some(layer_tile_data) = chunk.get_tile_data(x as i32, y as i32) else {
+                continue;
+            };
+
+            let index = IVec2 {
+                x: chunk_pos_mapped.0 * ChunkData::WIDTH as i32 + x as i32,
+                y: chunk_pos_mapped.1 * ChunkData::HEIGHT as i32 + y as i32,
+            };
+
+            f(
+                layer_tile,
+                layer_tile_data,
+                tile_pos_from_index(tiled_map, index),
+                index,
+            );
+        }
+    }
+}
+
+/// [TilePos] of the bottom-left tile of a chunk, in the whole map
+///
+/// Adding a tile position relative to the chunk gives the same [TilePos] than [for_each_tile].
+/// Not meaningful for staggered isometric maps, which chunks are not rectangular once converted.
+pub fn chunk_origin_tile_pos(tiled_map: &TiledMap, chunk_pos: (i32, i32)) -> TilePos {
+    TilePos {
+        x: ((chunk_pos.0 - tiled_map.topleft_chunk.0) * ChunkData::WIDTH as i32) as u32,
+        y: tiled_map.tiled_size.y
+            - ((chunk_pos.1 - tiled_map.topleft_chunk.1 + 1) * ChunkData::HEIGHT as i32) as u32,
+    }
+}
+
 /// Convert Tiled isometric coordinates into scalar coordinates
 pub(crate) fn iso_projection(
     coords: Vec2,
@@ -208,3 +387,125 @@ pub(crate) fn iso_projection(
         y: (fract.x + fract.y) * grid_size.y / 2.,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRID: TilemapGridSize = TilemapGridSize { x: 64., y: 32. };
+    const SIZE: TilemapSize = TilemapSize { x: 5, y: 4 };
+    const STAGGERS: [(StaggerAxis, StaggerIndex); 4] = [
+        (StaggerAxis::X, StaggerIndex::Odd),
+        (StaggerAxis::X, StaggerIndex::Even),
+        (StaggerAxis::Y, StaggerIndex::Odd),
+        (StaggerAxis::Y, StaggerIndex::Even),
+    ];
+
+    /// Center of a tile in Tiled pixels, following Tiled staggered layout
+    fn tiled_center(axis: StaggerAxis, index: StaggerIndex, x: u32, y: u32) -> Vec2 {
+        let is_shifted = |i: u32| (i % 2 == 1) == (index == StaggerIndex::Odd);
+        match axis {
+            StaggerAxis::X => Vec2::new(
+                x as f32 * GRID.x / 2. + GRID.x / 2.,
+                y as f32 * GRID.y + if is_shifted(x) { GRID.y / 2. } else { 0. } + GRID.y / 2.,
+            ),
+            StaggerAxis::Y => Vec2::new(
+                x as f32 * GRID.x + if is_shifted(y) { GRID.x / 2. } else { 0. } + GRID.x / 2.,
+                y as f32 * GRID.y / 2. + GRID.y / 2.,
+            ),
+        }
+    }
+
+    fn center_in_world(axis: StaggerAxis, index: StaggerIndex, x: u32, y: u32) -> Vec2 {
+        staggered_tile_pos(axis, index, SIZE, UVec2::new(x, y)).center_in_world(
+            &staggered_tilemap_size(axis, index, SIZE),
+            &GRID,
+            &tile_size_from_grid(&GRID),
+            &TilemapType::Isometric(IsoCoordSystem::Staggered),
+            &TilemapAnchor::None,
+        )
+    }
+
+    #[test]
+    fn staggered_tiles_match_tiled_positions() {
+        for (axis, index) in STAGGERS {
+            let tilemap_size = staggered_tilemap_size(axis, index, SIZE);
+            let mut seen = Vec::new();
+            for x in 0..SIZE.x {
+                for y in 0..SIZE.y {
+                    let tile_pos = staggered_tile_pos(axis, index, SIZE, UVec2::new(x, y));
+                    assert!(
+                        tile_pos.within_map_bounds(&tilemap_size),
+                        "{axis:?} {index:?} ({x}, {y}) -> {tile_pos:?} outside of {tilemap_size:?}"
+                    );
+                    assert!(!seen.contains(&tile_pos));
+                    seen.push(tile_pos);
+
+                    let expected = staggered_projection(
+                        axis,
+                        index,
+                        SIZE,
+                        &GRID,
+                        tiled_center(axis, index, x, y),
+                    );
+                    assert_eq!(
+                        center_in_world(axis, index, x, y),
+                        expected,
+                        "{axis:?} {index:?} ({x}, {y})"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn staggered_known_coordinates() {
+        // Odd rows are shifted by half a tile to the right
+        let (axis, index) = (StaggerAxis::Y, StaggerIndex::Odd);
+        assert_eq!(
+            staggered_tile_pos(axis, index, SIZE, UVec2::new(0, 0)),
+            TilePos::new(0, 3)
+        );
+        assert_eq!(
+            staggered_tile_pos(axis, index, SIZE, UVec2::new(0, 1)),
+            TilePos::new(1, 2)
+        );
+        // (0, 1) is half a tile right and down of (0, 0), (1, 0) a full tile right
+        let origin = center_in_world(axis, index, 0, 0);
+        assert_eq!(
+            center_in_world(axis, index, 0, 1) - origin,
+            Vec2::new(32., -16.)
+        );
+        assert_eq!(
+            center_in_world(axis, index, 1, 0) - origin,
+            Vec2::new(64., 0.)
+        );
+        assert_eq!(
+            center_in_world(axis, index, 0, 2) - origin,
+            Vec2::new(0., -32.)
+        );
+
+        // Even columns are shifted by half a tile down
+        let (axis, index) = (StaggerAxis::X, StaggerIndex::Even);
+        let origin = center_in_world(axis, index, 0, 0);
+        assert_eq!(
+            center_in_world(axis, index, 1, 0) - origin,
+            Vec2::new(32., 16.)
+        );
+        assert_eq!(
+            center_in_world(axis, index, 2, 0) - origin,
+            Vec2::new(64., 0.)
+        );
+        assert_eq!(
+            center_in_world(axis, index, 0, 1) - origin,
+            Vec2::new(0., -32.)
+        );
+
+        // Object at the top-left corner of the map, top-left tile center
+        let (axis, index) = (StaggerAxis::Y, StaggerIndex::Odd);
+        assert_eq!(
+            staggered_projection(axis, index, SIZE, &GRID, Vec2::new(32., 16.)),
+            center_in_world(axis, index, 0, 0)
+        );
+    }
+}
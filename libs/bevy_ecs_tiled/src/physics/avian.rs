@@ -38,6 +38,7 @@ impl TiledPhysicsBackend for TiledPhysicsAvianBackend {
         filter: &TiledNameFilter,
         collider: &TiledCollider,
         anchor: &TilemapAnchor,
+        _tiles_colliders: TiledTilesColliders,
     ) -> Vec<TiledColliderSpawnInfos> {
         match collider {
             TiledCollider::Object {
@@ -88,7 +89,7 @@ impl TiledPhysicsBackend for TiledPhysicsAvianBackend {
                 }
                 .unwrap_or_default()
             }
-            TiledCollider::TilesLayer { layer_id: _ } => {
+            TiledCollider::TilesLayer { .. } | TiledCollider::TilesChunk { .. } => {
                 let mut composables = vec![];
                 let mut spawn_infos = vec![];
                 for (tile_position, tile) in collider.get_tiles(tiled_map, anchor) {
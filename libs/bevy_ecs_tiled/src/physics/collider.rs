@@ -1,7 +1,8 @@
 //! Module that handles colliders
 use crate::prelude::*;
 use bevy::prelude::*;
-use tiled::{Layer, Object, Tile};
+use bevy_ecs_tilemap::prelude::TilePos;
+use tiled::{Layer, LayerTile, LayerTileData, Object, Tile};
 
 /// Marker component for colliders
 #[derive(Component, Default, Reflect, Copy, Clone, Debug)]
@@ -14,12 +15,21 @@ pub struct TiledColliderMarker;
 pub enum TiledCollider {
     /// Collider is created by a tiles [Layer]
     TilesLayer {
-        /// ID of the layer
+        /// ID of the layer, see [TiledLayerCreated::id]
         layer_id: usize,
     },
+    /// Collider is created by a single chunk of an infinite tiles [Layer]
+    ///
+    /// See [TiledMapChunkStreaming].
+    TilesChunk {
+        /// ID of the layer, see [TiledLayerCreated::id]
+        layer_id: usize,
+        /// Tiled chunk index, see [TiledChunkCreated::chunk]
+        chunk: IVec2,
+    },
     /// Collider is created by an [Object]
     Object {
-        /// ID of the layer containing the [Object].
+        /// ID of the layer containing the [Object], see [TiledLayerCreated::id]
         layer_id: usize,
         /// ID of the [Object].
         object_id: usize,
@@ -39,6 +49,11 @@ impl TiledCollider {
     pub fn from_tiles_layer(layer_id: usize) -> Self {
         Self::TilesLayer { layer_id }
     }
+
+    /// Create a new [TiledCollider::TilesChunk].
+    pub fn from_tiles_chunk(layer_id: usize, chunk: IVec2) -> Self {
+        Self::TilesChunk { layer_id, chunk }
+    }
 }
 
 impl<'a> TiledCollider {
@@ -48,8 +63,10 @@ impl<'a> TiledCollider {
             TiledCollider::Object {
                 layer_id,
                 object_id: _,
-            } => tiled_map.map.get_layer(*layer_id),
-            TiledCollider::TilesLayer { layer_id } => tiled_map.map.get_layer(*layer_id),
+            } => get_layer_from_index(&tiled_map.map, *layer_id),
+            TiledCollider::TilesLayer { layer_id } | TiledCollider::TilesChunk { layer_id, .. } => {
+                get_layer_from_index(&tiled_map.map, *layer_id)
+            }
         }
     }
 
@@ -59,9 +76,7 @@ impl<'a> TiledCollider {
             TiledCollider::Object {
                 layer_id,
                 object_id,
-            } => tiled_map
-                .map
-                .get_layer(*layer_id)
+            } => get_layer_from_index(&tiled_map.map, *layer_id)
                 .and_then(|layer| layer.as_object_layer())
                 .and_then(|object_layer| object_layer.get_object(*object_id)),
             _ => None,
@@ -74,32 +89,43 @@ impl<'a> TiledCollider {
         tiled_map: &'a TiledMap,
         anchor: &TilemapAnchor,
     ) -> Vec<(Vec2, Tile<'a>)> {
+        let grid_size = get_grid_size(&tiled_map.map);
+        let tile_size = tile_size_from_grid(&grid_size);
+        let map_type = get_map_type(&tiled_map.map);
+        let mut out = vec![];
+        let push_tile = |layer_tile: LayerTile<'a>, _: &LayerTileData, tile_pos: TilePos, _| {
+            if let Some(tile) = layer_tile.get_tile() {
+                let tile_coords = tile_pos.center_in_world(
+                    &tiled_map.tilemap_size,
+                    &grid_size,
+                    &tile_size,
+                    &map_type,
+                    anchor,
+                );
+                out.push((tile_coords, tile));
+            }
+        };
         match self {
-            TiledCollider::TilesLayer { layer_id } => tiled_map
-                .map
-                .get_layer(*layer_id)
-                .and_then(|layer| layer.as_tile_layer())
-                .map(|layer| {
-                    let mut out = vec![];
-                    for_each_tile(tiled_map, &layer, |layer_tile, _, tile_pos, _| {
-                        if let Some(tile) = layer_tile.get_tile() {
-                            let grid_size = get_grid_size(&tiled_map.map);
-                            let tile_size = tile_size_from_grid(&grid_size);
-                            let tile_coords = tile_pos.center_in_world(
-                                &tiled_map.tilemap_size,
-                                &grid_size,
-                                &tile_size,
-                                &get_map_type(&tiled_map.map),
-                                anchor,
-                            );
-                            out.push((tile_coords, tile));
-                        }
-                    });
-                    out
-                })
-                .unwrap_or_default(),
-            _ => vec![],
+            TiledCollider::TilesLayer { layer_id } => {
+                if let Some(layer) = get_layer_from_index(&tiled_map.map, *layer_id)
+                    .and_then(|layer| layer.as_tile_layer())
+                {
+                    for_each_tile(tiled_map, &layer, push_tile);
+                }
+            }
+            TiledCollider::TilesChunk { layer_id, chunk } => {
+                if let Some(tiled::TileLayer::Infinite(layer)) =
+                    get_layer_from_index(&tiled_map.map, *layer_id)
+                        .and_then(|layer| layer.as_tile_layer())
+                {
+                    if let Some(c) = layer.get_chunk(chunk.x, chunk.y) {
+                        for_each_tile_in_chunk(tiled_map, (chunk.x, chunk.y), &c, push_tile);
+                    }
+                }
+            }
+            _ => {}
         }
+        out
     }
 }
 
@@ -114,6 +140,7 @@ pub struct TiledColliderSpawnInfos {
     pub transform: Transform,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(super) fn spawn_colliders<T: super::TiledPhysicsBackend>(
     backend: &T,
     parent: Entity,
@@ -122,6 +149,7 @@ pub(super) fn spawn_colliders<T: super::TiledPhysicsBackend>(
     names: &TiledName,
     collider: &TiledCollider,
     anchor: &TilemapAnchor,
+    tiles_colliders: super::TiledTilesColliders,
 ) {
     for spawn_infos in backend.spawn_colliders(
         commands,
@@ -129,6 +157,7 @@ pub(super) fn spawn_colliders<T: super::TiledPhysicsBackend>(
         &TiledNameFilter::from(names),
         collider,
         anchor,
+        tiles_colliders,
     ) {
         commands.entity(spawn_infos.entity).insert((
             TiledColliderMarker,
@@ -28,6 +28,7 @@ pub mod prelude {
     pub use super::TiledPhysicsBackend;
     pub use super::TiledPhysicsPlugin;
     pub use super::TiledPhysicsSettings;
+    pub use super::TiledTilesColliders;
 }
 
 /// Physics backend public trait.
@@ -49,6 +50,9 @@ pub trait TiledPhysicsBackend:
     /// collider and return informations about it.
     /// In case the provided [TiledCollider] is not supported, it should
     /// not spawn anything and return an empty [Vec].
+    ///
+    /// `tiles_colliders` comes from [TiledPhysicsSettings::tiles_colliders], a backend
+    /// which cannot merge tiles colliders should behave as [TiledTilesColliders::PerShape].
     fn spawn_colliders(
         &self,
         commands: &mut Commands,
@@ -56,9 +60,33 @@ pub trait TiledPhysicsBackend:
         filter: &TiledNameFilter,
         collider: &TiledCollider,
         anchor: &TilemapAnchor,
+        tiles_colliders: TiledTilesColliders,
     ) -> Vec<TiledColliderSpawnInfos>;
 }
 
+/// How colliders are built for tiles layers.
+///
+/// Only the Rapier backend merges colliders, other backends always use [TiledTilesColliders::PerShape].
+#[derive(Default, Reflect, Copy, Clone, Debug, PartialEq, Eq)]
+#[reflect(Default, Debug)]
+pub enum TiledTilesColliders {
+    /// One shape per tile collision object.
+    #[default]
+    PerShape,
+    /// Tiles whose collision is a single rectangle covering the whole tile are merged
+    /// into as few rectangles as possible, other tiles keep their own shapes.
+    ///
+    /// Only applies to orthogonal maps.
+    MergedRectangles,
+    /// Tiles whose collision is a single rectangle covering the whole tile are replaced
+    /// by a polyline tracing the outline of the area they cover, other tiles keep their own shapes.
+    ///
+    /// This removes every seam between tiles but the area inside the outline is hollow:
+    /// only use it for terrain bodies cannot end up inside of.
+    /// Only applies to orthogonal maps.
+    TracedOutlines,
+}
+
 /// Physics related settings.
 #[derive(Component, Default, Reflect, Clone, Debug)]
 #[reflect(Component, Default, Debug)]
@@ -83,6 +111,10 @@ pub struct TiledPhysicsSettings<T: TiledPhysicsBackend> {
     /// Colliders will be automatically added for all tiles collision objects whose name matches this filter.
     /// By default, we add colliders for all collision objects.
     pub tiles_objects_filter: TiledName,
+    /// Specify how colliders are built for tiles layers.
+    ///
+    /// By default, each tile collision object gets its own shape.
+    pub tiles_colliders: TiledTilesColliders,
     /// Physics backend to use for adding colliders.
     pub backend: T,
 }
@@ -107,6 +139,7 @@ impl<T: TiledPhysicsBackend> Plugin for TiledPhysicsPlugin<T> {
     fn build(&self, app: &mut bevy::prelude::App) {
         app.register_type::<TiledColliderMarker>()
             .register_type::<T>()
+            .register_type::<TiledTilesColliders>()
             .register_type::<TiledPhysicsSettings<T>>()
             .add_systems(
                 PreUpdate,
@@ -114,6 +147,7 @@ impl<T: TiledPhysicsBackend> Plugin for TiledPhysicsPlugin<T> {
                     initialize_settings_for_worlds::<T>,
                     initialize_settings_for_maps::<T>,
                     collider_from_tiles_layer::<T>,
+                    collider_from_tiles_chunk::<T>,
                     collider_from_object::<T>,
                 )
                     .chain()
@@ -176,16 +210,27 @@ fn collider_from_tiles_layer<T: TiledPhysicsBackend>(
     mut layer_event: EventReader<TiledLayerCreated>,
     mut commands: Commands,
     map_asset: Res<Assets<TiledMap>>,
-    maps_query: Query<(&TiledPhysicsSettings<T>, &TilemapAnchor), With<TiledMapMarker>>,
+    maps_query: Query<
+        (
+            &TiledPhysicsSettings<T>,
+            &TilemapAnchor,
+            Option<&TiledMapChunkStreaming>,
+        ),
+        With<TiledMapMarker>,
+    >,
 ) {
     for ev in layer_event.read() {
         debug!(
             "map entity = {:?}, layer entity = {:?}",
             ev.map.entity, ev.entity
         );
-        let (settings, anchor) = maps_query
+        let (settings, anchor, streaming) = maps_query
             .get(ev.map.entity)
             .expect("TiledPhysicsSettings<T> component should be on map entity");
+        // Streamed layers get their colliders chunk by chunk
+        if streaming.is_some_and(|s| s.layers.iter().any(|(l, _)| l.id == ev.id)) {
+            continue;
+        }
         let Some(tiled_map) = ev.map.get_map_asset(&map_asset) else {
             return;
         };
@@ -205,6 +250,41 @@ fn collider_from_tiles_layer<T: TiledPhysicsBackend>(
                 &settings.tiles_objects_filter,
                 &TiledCollider::from_tiles_layer(ev.id),
                 anchor,
+                settings.tiles_colliders,
+            );
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn collider_from_tiles_chunk<T: TiledPhysicsBackend>(
+    mut chunk_event: EventReader<TiledChunkCreated>,
+    mut commands: Commands,
+    map_asset: Res<Assets<TiledMap>>,
+    maps_query: Query<(&TiledPhysicsSettings<T>, &TilemapAnchor), With<TiledMapMarker>>,
+) {
+    for ev in chunk_event.read() {
+        let Ok((settings, anchor)) = maps_query.get(ev.layer.map.entity) else {
+            // Map may have been despawned since
+            continue;
+        };
+        let Some(tiled_map) = ev.layer.map.get_map_asset(&map_asset) else {
+            continue;
+        };
+        let Some(layer) = ev.layer.get_layer(&map_asset) else {
+            continue;
+        };
+
+        if TiledNameFilter::from(&settings.tiles_layer_filter).contains(&layer.name) {
+            collider::spawn_colliders(
+                &settings.backend,
+                ev.entity,
+                &mut commands,
+                tiled_map,
+                &settings.tiles_objects_filter,
+                &TiledCollider::from_tiles_chunk(ev.layer.id, ev.chunk),
+                anchor,
+                settings.tiles_colliders,
             );
         }
     }
@@ -245,6 +325,7 @@ fn collider_from_object<T: TiledPhysicsBackend>(
                 },
                 &TiledCollider::from_object(ev.layer.id, ev.id),
                 anchor,
+                settings.tiles_colliders,
             );
         }
     }
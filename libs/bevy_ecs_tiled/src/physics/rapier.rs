@@ -2,13 +2,15 @@
 //!
 //! Only available when the `rapier` feature is enabled.
 
+use std::collections::{BTreeMap, HashMap, HashSet};
+
 use bevy::prelude::*;
 use bevy_ecs_tilemap::map::TilemapGridSize;
 use bevy_rapier2d::{
     prelude::*,
     rapier::prelude::{Isometry, Real, SharedShape},
 };
-use tiled::{ObjectLayerData, ObjectShape};
+use tiled::{ObjectData, ObjectLayerData, ObjectShape};
 
 use crate::prelude::*;
 
@@ -34,6 +36,7 @@ impl TiledPhysicsBackend for TiledPhysicsRapierBackend {
         filter: &TiledNameFilter,
         collider: &TiledCollider,
         anchor: &TilemapAnchor,
+        tiles_colliders: TiledTilesColliders,
     ) -> Vec<TiledColliderSpawnInfos> {
         match collider {
             TiledCollider::Object {
@@ -57,6 +60,7 @@ impl TiledPhysicsBackend for TiledPhysicsRapierBackend {
                             object_layer_data,
                             Vec2::ZERO,
                             get_grid_size(&tiled_map.map),
+                            None,
                             &mut composables,
                             &mut spawn_infos,
                         );
@@ -84,7 +88,12 @@ impl TiledPhysicsBackend for TiledPhysicsRapierBackend {
                 }
                 .unwrap_or_default()
             }
-            TiledCollider::TilesLayer { layer_id: _ } => {
+            TiledCollider::TilesLayer { .. } | TiledCollider::TilesChunk { .. } => {
+                let grid_size = get_grid_size(&tiled_map.map);
+                // Tiles of other orientations are not laid out on a rectangular grid
+                let merge = tiles_colliders != TiledTilesColliders::PerShape
+                    && tiled_map.map.orientation == tiled::Orientation::Orthogonal;
+                let mut solid_tiles = SolidTiles::default();
                 let mut composables = vec![];
                 let mut spawn_infos = vec![];
                 for (tile_position, tile) in collider.get_tiles(tiled_map, anchor) {
@@ -94,12 +103,29 @@ impl TiledPhysicsBackend for TiledPhysicsRapierBackend {
                             filter,
                             collision,
                             tile_position,
-                            get_grid_size(&tiled_map.map),
+                            grid_size,
+                            merge.then_some(&mut solid_tiles),
                             &mut composables,
                             &mut spawn_infos,
                         );
                     }
                 }
+                match tiles_colliders {
+                    TiledTilesColliders::PerShape => {}
+                    TiledTilesColliders::MergedRectangles => {
+                        composables.extend(solid_tiles.rectangles(grid_size));
+                    }
+                    TiledTilesColliders::TracedOutlines => {
+                        if let Some(outline) = solid_tiles.outline(grid_size) {
+                            let collider: Collider = outline.into();
+                            spawn_infos.push(TiledColliderSpawnInfos {
+                                name: "Rapier[TracedOutline]".to_string(),
+                                entity: commands.spawn(collider).id(),
+                                transform: Transform::default(),
+                            });
+                        }
+                    }
+                }
                 if !composables.is_empty() {
                     let collider: Collider = SharedShape::compound(composables).into();
                     spawn_infos.push(TiledColliderSpawnInfos {
@@ -121,6 +147,7 @@ fn compose_tiles(
     object_layer_data: &ObjectLayerData,
     tile_offset: Vec2,
     grid_size: TilemapGridSize,
+    mut solid_tiles: Option<&mut SolidTiles>,
     composables: &mut Vec<(Isometry<Real>, SharedShape)>,
     spawn_infos: &mut Vec<TiledColliderSpawnInfos>,
 ) {
@@ -128,6 +155,12 @@ fn compose_tiles(
         if !filter.contains(&object.name) {
             continue;
         }
+        if let Some(solid_tiles) = solid_tiles.as_mut() {
+            if is_full_tile(object, grid_size) {
+                solid_tiles.insert(tile_offset, grid_size);
+                continue;
+            }
+        }
         let position = tile_offset
             // Object position
             + Vec2 {
@@ -212,3 +245,261 @@ fn get_position_and_shape(shape: &ObjectShape) -> Option<(Vec2, SharedShape, boo
         _ => None,
     }
 }
+
+/// Whether a tile collision object is an unrotated rectangle covering the whole tile.
+fn is_full_tile(object: &ObjectData, grid_size: TilemapGridSize) -> bool {
+    const EPSILON: f32 = 0.01;
+    let ObjectShape::Rect { width, height } = object.shape else {
+        return false;
+    };
+    object.x.abs() < EPSILON
+        && object.y.abs() < EPSILON
+        && object.rotation.abs() < EPSILON
+        && (width - grid_size.x).abs() < EPSILON
+        && (height - grid_size.y).abs() < EPSILON
+}
+
+/// Tiles fully covered by their collision, to be merged instead of getting one shape each.
+#[derive(Default, Debug)]
+struct SolidTiles {
+    /// Center of the first inserted tile, cells are relative to it
+    origin: Vec2,
+    cells: HashSet<IVec2>,
+}
+
+impl SolidTiles {
+    fn insert(&mut self, tile_center: Vec2, grid_size: TilemapGridSize) {
+        let grid_size = Vec2::from(grid_size);
+        if self.cells.is_empty() {
+            self.origin = tile_center;
+        }
+        self.cells
+            .insert(((tile_center - self.origin) / grid_size).round().as_ivec2());
+    }
+
+    /// Position of a cell corner, `(0, 0)` being the bottom-left corner of the origin cell.
+    fn corner_position(&self, corner: Vec2, grid_size: TilemapGridSize) -> Vec2 {
+        self.origin + (corner - Vec2::splat(0.5)) * Vec2::from(grid_size)
+    }
+
+    fn rectangles(&self, grid_size: TilemapGridSize) -> Vec<(Isometry<Real>, SharedShape)> {
+        merge_rectangles(&self.cells)
+            .into_iter()
+            .map(|rect| {
+                let center = self.corner_position(rect.as_rect().center(), grid_size);
+                let half_size = rect.size().as_vec2() * Vec2::from(grid_size) / 2.;
+                (
+                    Isometry::<Real>::new(center.into(), 0.),
+                    SharedShape::cuboid(half_size.x, half_size.y),
+                )
+            })
+            .collect()
+    }
+
+    fn outline(&self, grid_size: TilemapGridSize) -> Option<SharedShape> {
+        let segments = trace_outline(&self.cells);
+        if segments.is_empty() {
+            return None;
+        }
+        let mut vertices = vec![];
+        let mut vertex_indices = HashMap::new();
+        let mut indices = vec![];
+        for segment in segments {
+            let [start, end] = segment.map(|corner| {
+                *vertex_indices.entry(corner).or_insert_with(|| {
+                    vertices.push(self.corner_position(corner.as_vec2(), grid_size).into());
+                    vertices.len() as u32 - 1
+                })
+            });
+            indices.push([start, end]);
+        }
+        Some(SharedShape::polyline(vertices, Some(indices)))
+    }
+}
+
+/// Cover cells with as few non-overlapping rectangles as a greedy scan finds.
+///
+/// Each rectangle grows right first, then up as long as whole rows are available.
+fn merge_rectangles(cells: &HashSet<IVec2>) -> Vec<IRect> {
+    let mut sorted: Vec<IVec2> = cells.iter().copied().collect();
+    sorted.sort_by_key(|cell| (cell.y, cell.x));
+    let mut used = HashSet::new();
+    let mut rectangles = vec![];
+    for start in sorted {
+        if used.contains(&start) {
+            continue;
+        }
+        let free = |cell: IVec2| cells.contains(&cell) && !used.contains(&cell);
+        let mut width = 1;
+        while free(start + IVec2::new(width, 0)) {
+            width += 1;
+        }
+        let mut height = 1;
+        while (0..width).all(|x| free(start + IVec2::new(x, height))) {
+            height += 1;
+        }
+        for y in 0..height {
+            for x in 0..width {
+                used.insert(start + IVec2::new(x, y));
+            }
+        }
+        rectangles.push(IRect::from_corners(
+            start,
+            start + IVec2::new(width, height),
+        ));
+    }
+    rectangles
+}
+
+/// Edges between cells and empty space, as segments between cell corners.
+///
+/// Cell `(x, y)` spans corners `(x, y)` to `(x + 1, y + 1)`, aligned edges are joined into a single segment.
+fn trace_outline(cells: &HashSet<IVec2>) -> Vec<[IVec2; 2]> {
+    // Unit edges by the line they lie on, identified by their lowest coordinate along it
+    let mut horizontal: BTreeMap<i32, Vec<i32>> = BTreeMap::new();
+    let mut vertical: BTreeMap<i32, Vec<i32>> = BTreeMap::new();
+    for cell in cells {
+        if !cells.contains(&(*cell - IVec2::Y)) {
+            horizontal.entry(cell.y).or_default().push(cell.x);
+        }
+        if !cells.contains(&(*cell + IVec2::Y)) {
+            horizontal.entry(cell.y + 1).or_default().push(cell.x);
+        }
+        if !cells.contains(&(*cell - IVec2::X)) {
+            vertical.entry(cell.x).or_default().push(cell.y);
+        }
+        if !cells.contains(&(*cell + IVec2::X)) {
+            vertical.entry(cell.x + 1).or_default().push(cell.y);
+        }
+    }
+
+    let mut segments = vec![];
+    let mut push_runs = |lines: BTreeMap<i32, Vec<i32>>, corner: fn(i32, i32) -> IVec2| {
+        for (line, mut starts) in lines {
+            starts.sort_unstable();
+            let mut run_start = starts[0];
+            for (i, start) in starts.iter().enumerate() {
+                let run_end = start + 1;
+                if starts.get(i + 1) != Some(&run_end) {
+                    segments.push([corner(line, run_start), corner(line, run_end)]);
+                    if let Some(next) = starts.get(i + 1) {
+                        run_start = *next;
+                    }
+                }
+            }
+        }
+    };
+    push_runs(horizontal, |line, along| IVec2::new(along, line));
+    push_runs(vertical, IVec2::new);
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRID: TilemapGridSize = TilemapGridSize { x: 16., y: 8. };
+
+    fn cells(rows: &[&str]) -> HashSet<IVec2> {
+        let mut cells = HashSet::new();
+        for (y, row) in rows.iter().rev().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                if c == '#' {
+                    cells.insert(IVec2::new(x as i32, y as i32));
+                }
+            }
+        }
+        cells
+    }
+
+    fn assert_exact_cover(cells: &HashSet<IVec2>, rectangles: &[IRect]) {
+        let mut covered = HashSet::new();
+        for rect in rectangles {
+            for y in rect.min.y..rect.max.y {
+                for x in rect.min.x..rect.max.x {
+                    assert!(covered.insert(IVec2::new(x, y)), "{x},{y} covered twice");
+                }
+            }
+        }
+        assert_eq!(&covered, cells);
+    }
+
+    #[test]
+    fn merges_a_block_into_one_rectangle() {
+        let block = cells(&["#####", "#####", "#####"]);
+        let rectangles = merge_rectangles(&block);
+        assert_eq!(rectangles, vec![IRect::new(0, 0, 5, 3)]);
+    }
+
+    #[test]
+    fn merged_rectangles_cover_the_same_area() {
+        let station = cells(&[
+            "##########",
+            "#........#",
+            "#..####..#",
+            "#..#..#...",
+            "####..####",
+            ".#.#.##.#.",
+        ]);
+        let rectangles = merge_rectangles(&station);
+        assert_exact_cover(&station, &rectangles);
+        assert!(rectangles.len() < station.len() / 2);
+    }
+
+    #[test]
+    fn merged_shapes_keep_the_tile_area() {
+        let mut solid_tiles = SolidTiles::default();
+        let tiles = cells(&["###.", "####", "#..#"]);
+        let origin = Vec2::new(-100., 40.);
+        for cell in tiles.iter() {
+            solid_tiles.insert(origin + cell.as_vec2() * Vec2::from(GRID), GRID);
+        }
+
+        let merged = solid_tiles.rectangles(GRID);
+        assert!(merged.len() < tiles.len());
+        let area: f32 = merged
+            .iter()
+            .map(|(_, shape)| {
+                let half = shape.as_cuboid().unwrap().half_extents;
+                half.x * half.y * 4.
+            })
+            .sum();
+        assert_eq!(area, tiles.len() as f32 * GRID.x * GRID.y);
+
+        // Same bounds as the per tile shapes
+        let compound = SharedShape::compound(merged);
+        let aabb = compound.compute_local_aabb();
+        assert_eq!(Vec2::from(aabb.mins), origin - Vec2::from(GRID) / 2.);
+        assert_eq!(
+            Vec2::from(aabb.maxs),
+            origin + Vec2::new(3.5 * GRID.x, 2.5 * GRID.y)
+        );
+    }
+
+    #[test]
+    fn traces_outer_and_inner_outlines() {
+        let ring = cells(&["###", "#.#", "###"]);
+        let segments = trace_outline(&ring);
+        // Four sides outside, four sides around the hole
+        assert_eq!(segments.len(), 8);
+        let length: i32 = segments
+            .iter()
+            .map(|[start, end]| (*end - *start).abs().element_sum())
+            .sum();
+        assert_eq!(length, 12 + 4);
+    }
+
+    #[test]
+    fn outline_replaces_every_tile_with_one_collider() {
+        let mut solid_tiles = SolidTiles::default();
+        for cell in cells(&["####", "####"]) {
+            solid_tiles.insert(cell.as_vec2() * Vec2::from(GRID), GRID);
+        }
+        let outline = solid_tiles.outline(GRID).unwrap();
+        let polyline = outline.as_polyline().unwrap();
+        assert_eq!(polyline.num_segments(), 4);
+        let aabb = outline.compute_local_aabb();
+        assert_eq!(Vec2::from(aabb.mins), -Vec2::from(GRID) / 2.);
+        assert_eq!(Vec2::from(aabb.maxs), Vec2::new(3.5 * GRID.x, 1.5 * GRID.y));
+    }
+}
@@ -4,8 +4,8 @@ use crate::properties::types_json::{
 use bevy::ecs::reflect::ReflectBundle;
 use bevy::platform::collections::HashMap;
 use bevy::reflect::{
-    ArrayInfo, EnumInfo, NamedField, StructInfo, TupleInfo, TupleStructInfo, TypeInfo,
-    TypeRegistration, TypeRegistry, UnnamedField, VariantInfo,
+    ArrayInfo, EnumInfo, MapInfo, NamedField, StructInfo, TupleInfo, TupleStructInfo, Type,
+    TypeInfo, TypeRegistration, TypeRegistry, UnnamedField, VariantInfo,
 };
 use bevy::{prelude::*, reflect::ReflectRef};
 use std::borrow::Cow;
@@ -13,19 +13,15 @@ use thiserror::Error;
 
 const DEFAULT_COLOR: &str = "#000000";
 const USE_AS_PROPERTY: &[UseAs] = &[UseAs::Property];
+/// Number of slots exported for lists, sets and maps, see [crate::properties]
+pub(crate) const COLLECTION_SLOTS: usize = 16;
 
 type ExportConversionResult = Result<Vec<TypeExport>, ExportConversionError>;
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Error)]
 enum ExportConversionError {
-    #[error("lists fields are not supported")]
-    ListUnsupported,
-    #[error("map fields are not supported")]
-    MapUnsupported,
     #[error("field of type {0} is not supported")]
     UnsupportedValue(&'static str),
-    #[error("set fields are not supported")]
-    SetUnsupported,
     #[error("a dependency is not supported")]
     DependencyError,
 }
@@ -106,6 +102,9 @@ impl TypeExportRegistry {
                 | TypeInfo::Struct(_)
                 | TypeInfo::Tuple(_)
                 | TypeInfo::Array(_)
+                | TypeInfo::List(_)
+                | TypeInfo::Set(_)
+                | TypeInfo::Map(_)
                 | TypeInfo::Enum(_)
                 | TypeInfo::Opaque(_)
         )
@@ -136,12 +135,24 @@ impl TypeExportRegistry {
             TypeInfo::Tuple(info) => {
                 self.generate_tuple_export(info, registry, default_value, use_as)
             }
-            TypeInfo::List(_) => Err(ExportConversionError::ListUnsupported),
+            TypeInfo::List(info) => self.generate_collection_export(
+                info.type_path(),
+                info.item_ty(),
+                registry,
+                default_value,
+                use_as,
+            ),
             TypeInfo::Array(info) => self.generate_array_export(info, registry, use_as),
-            TypeInfo::Map(_) => Err(ExportConversionError::MapUnsupported),
+            TypeInfo::Map(info) => self.generate_map_export(info, registry, default_value, use_as),
             TypeInfo::Enum(info) => self.generate_enum_export(info, registry, use_as),
             TypeInfo::Opaque(_) => Ok(vec![]),
-            TypeInfo::Set(_) => Err(ExportConversionError::SetUnsupported),
+            TypeInfo::Set(info) => self.generate_collection_export(
+                info.type_path(),
+                info.value_ty(),
+                registry,
+                default_value,
+                use_as,
+            ),
         };
 
         if out.is_ok() {
@@ -250,6 +261,111 @@ impl TypeExportRegistry {
         Ok(vec![root])
     }
 
+    fn generate_collection_export(
+        &mut self,
+        type_path: &str,
+        item_ty: Type,
+        registry: &TypeRegistry,
+        default_value: Option<&dyn Reflect>,
+        use_as: Vec<UseAs>,
+    ) -> ExportConversionResult {
+        let (type_field, property_type) = type_to_field(
+            registry
+                .get(item_ty.id())
+                .ok_or(ExportConversionError::DependencyError)?,
+        )?;
+        let defaults = default_value
+            .map(|v| value_to_json(v.as_partial_reflect()))
+            .unwrap_or_default();
+
+        let root = TypeExport {
+            id: self.next_id(),
+            name: type_path.to_string(),
+            type_data: TypeData::Class(Class {
+                use_as,
+                color: DEFAULT_COLOR.to_string(),
+                draw_fill: true,
+                members: (0..COLLECTION_SLOTS)
+                    .map(|i| {
+                        let name = format!("[{i}]");
+                        Member {
+                            value: defaults.get(&name).cloned().unwrap_or_default(),
+                            name,
+                            property_type: property_type.clone(),
+                            type_field,
+                        }
+                    })
+                    .collect(),
+            }),
+        };
+
+        Ok(vec![root])
+    }
+
+    fn generate_map_export(
+        &mut self,
+        info: &MapInfo,
+        registry: &TypeRegistry,
+        default_value: Option<&dyn Reflect>,
+        use_as: Vec<UseAs>,
+    ) -> ExportConversionResult {
+        let member = |ty: Type, name: &str| -> Result<Member, ExportConversionError> {
+            let (type_field, property_type) = type_to_field(
+                registry
+                    .get(ty.id())
+                    .ok_or(ExportConversionError::DependencyError)?,
+            )?;
+            Ok(Member {
+                name: name.to_string(),
+                property_type,
+                type_field,
+                value: Default::default(),
+            })
+        };
+
+        // Note: extra `:` is done to not conflict with any other type
+        let entry_name = info.type_path().to_string() + ":::Entry";
+        let entry = TypeExport {
+            id: self.next_id(),
+            name: entry_name.clone(),
+            type_data: TypeData::Class(Class {
+                use_as: USE_AS_PROPERTY.to_vec(),
+                color: DEFAULT_COLOR.to_string(),
+                draw_fill: true,
+                members: vec![
+                    member(info.key_ty(), "key")?,
+                    member(info.value_ty(), "value")?,
+                ],
+            }),
+        };
+
+        let defaults = default_value
+            .map(|v| value_to_json(v.as_partial_reflect()))
+            .unwrap_or_default();
+        let root = TypeExport {
+            id: self.next_id(),
+            name: info.type_path().to_string(),
+            type_data: TypeData::Class(Class {
+                use_as,
+                color: DEFAULT_COLOR.to_string(),
+                draw_fill: true,
+                members: (0..COLLECTION_SLOTS)
+                    .map(|i| {
+                        let name = format!("[{i}]");
+                        Member {
+                            value: defaults.get(&name).cloned().unwrap_or_default(),
+                            name,
+                            property_type: Some(entry_name.clone()),
+                            type_field: FieldType::Class,
+                        }
+                    })
+                    .collect(),
+            }),
+        };
+
+        Ok(vec![entry, root])
+    }
+
     fn generate_tuple_export(
         &mut self,
         info: &TupleInfo,
@@ -454,6 +570,103 @@ impl TypeExportRegistry {
     }
 }
 
+/// Project property listing the types written by [merge_into_project], one name per line
+pub(crate) const EXPORTED_TYPES_PROPERTY: &str = "bevy_ecs_tiled::exported_types";
+
+/// Replace the exported types of a `.tiled-project` with `exports`.
+///
+/// Types already in the project keep their ID, new ones are given IDs after the highest one.
+/// Previously exported types missing from `exports` are removed, hand-authored types are kept:
+/// the names of the exported types are kept in the [EXPORTED_TYPES_PROPERTY] project property.
+pub(crate) fn merge_into_project(
+    project: &mut serde_json::Value,
+    exports: Vec<TypeExport>,
+) -> Result<(), String> {
+    let Some(project) = project.as_object_mut() else {
+        return Err("project is not a JSON object".to_string());
+    };
+    let existing = match project.remove("propertyTypes") {
+        Some(serde_json::Value::Array(types)) => types,
+        None => vec![],
+        Some(_) => return Err("`propertyTypes` is not an array".to_string()),
+    };
+    let mut properties = match project.remove("properties") {
+        Some(serde_json::Value::Array(properties)) => properties,
+        None => vec![],
+        Some(_) => return Err("`properties` is not an array".to_string()),
+    };
+    let is_exported_property = |p: &serde_json::Value| {
+        p.get("name").and_then(|n| n.as_str()) == Some(EXPORTED_TYPES_PROPERTY)
+    };
+    let previously_exported: Vec<String> = properties
+        .iter()
+        .find(|p| is_exported_property(p))
+        .and_then(|p| p.get("value"))
+        .and_then(|v| v.as_str())
+        .map(|v| v.lines().map(str::to_owned).collect())
+        .unwrap_or_default();
+
+    let name_of = |t: &serde_json::Value| t.get("name").and_then(|n| n.as_str()).map(str::to_owned);
+    let mut ids: HashMap<String, u64> = HashMap::default();
+    let mut next_id = 0;
+    for t in existing.iter() {
+        let id = t.get("id").and_then(|id| id.as_u64()).unwrap_or_default();
+        next_id = next_id.max(id);
+        if let Some(name) = name_of(t) {
+            ids.insert(name, id);
+        }
+    }
+
+    let exported: HashMap<String, TypeExport> =
+        exports.into_iter().map(|t| (t.name.clone(), t)).collect();
+    let mut types = Vec::with_capacity(existing.len() + exported.len());
+    // Hand-authored types first, in their original order
+    for t in existing {
+        if name_of(&t).is_some_and(|n| previously_exported.contains(&n) || exported.contains_key(&n)) {
+            continue;
+        }
+        types.push(t);
+    }
+    let mut exported = exported.into_values().collect::<Vec<_>>();
+    exported.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let names: Vec<&str> = exported.iter().map(|t| t.name.as_str()).collect();
+    properties.retain(|p| !is_exported_property(p));
+    properties.push(serde_json::json!({
+        "name": EXPORTED_TYPES_PROPERTY,
+        "type": "string",
+        "value": names.join("\n"),
+    }));
+    project.insert("properties".to_string(), serde_json::Value::Array(properties));
+
+    for mut t in exported {
+        let id = match ids.get(&t.name) {
+            Some(id) => *id,
+            None => {
+                next_id += 1;
+                next_id
+            }
+        };
+        t.id = id as u32;
+        types.push(serde_json::to_value(t).map_err(|e| e.to_string())?);
+    }
+
+    project.insert("propertyTypes".to_string(), serde_json::Value::Array(types));
+    Ok(())
+}
+
+/// Only the first [COLLECTION_SLOTS] items of a collection default value can be exported
+fn warn_truncated(type_info: &TypeInfo, len: usize) {
+    if len > COLLECTION_SLOTS {
+        warn!(
+            "default value of `{}` has {} items, only the first {} are exported",
+            type_info.type_path(),
+            len,
+            COLLECTION_SLOTS
+        );
+    }
+}
+
 fn value_to_json(value: &dyn PartialReflect) -> serde_json::Value {
     let Some(type_info) = value.get_represented_type_info() else {
         return serde_json::Value::default();
@@ -540,6 +753,38 @@ fn value_to_json(value: &dyn PartialReflect) -> serde_json::Value {
                 )
             })
             .collect(),
+        (_, _, ReflectRef::List(v)) => {
+            warn_truncated(type_info, v.len());
+            v.iter()
+                .take(COLLECTION_SLOTS)
+                .enumerate()
+                .map(|(i, item)| (format!("[{i}]"), value_to_json(item)))
+                .collect()
+        }
+        (_, _, ReflectRef::Set(v)) => {
+            warn_truncated(type_info, v.len());
+            v.iter()
+                .take(COLLECTION_SLOTS)
+                .enumerate()
+                .map(|(i, item)| (format!("[{i}]"), value_to_json(item)))
+                .collect()
+        }
+        (_, _, ReflectRef::Map(v)) => {
+            warn_truncated(type_info, v.len());
+            v.iter()
+                .take(COLLECTION_SLOTS)
+                .enumerate()
+                .map(|(i, (key, value))| {
+                    (
+                        format!("[{i}]"),
+                        serde_json::json!({
+                            "key": value_to_json(key),
+                            "value": value_to_json(value),
+                        }),
+                    )
+                })
+                .collect()
+        }
         _ => {
             // warn!(
             //     "cannot convert type '{}' to a JSON value",
@@ -590,11 +835,6 @@ fn type_to_field(
     t: &TypeRegistration,
 ) -> Result<(FieldType, Option<String>), ExportConversionError> {
     let info = t.type_info();
-    if matches!(info, TypeInfo::List(_)) {
-        return Err(ExportConversionError::ListUnsupported);
-    } else if matches!(info, TypeInfo::Map(_)) {
-        return Err(ExportConversionError::MapUnsupported);
-    }
     Ok(match info.type_path() {
         "bool" => (FieldType::Bool, None),
         "f32" | "f64" => (FieldType::Float, None),
@@ -1049,4 +1289,166 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn generate_list_with_default() {
+        #[derive(Component, Reflect)]
+        #[reflect(Component, Default)]
+        struct Path {
+            positions: Vec<f32>,
+        }
+        impl Default for Path {
+            fn default() -> Self {
+                Self {
+                    positions: vec![1., 2.],
+                }
+            }
+        }
+
+        let mut registry = TypeRegistry::new();
+        registry.register::<Path>();
+
+        let exports = TypeExportRegistry::from_registry(&registry);
+        let export_type = &exports.types.get(Path::type_path()).unwrap();
+        assert_eq!(export_type.length(), 1);
+        let TypeData::Class(class) = &export_type[0].type_data else {
+            panic!("expected a class");
+        };
+        assert_eq!(
+            class.members[0].property_type,
+            Some(Vec::<f32>::type_path().to_string())
+        );
+        assert_eq!(
+            class.members[0].value,
+            serde_json::json!({"[0]": 1.0, "[1]": 2.0})
+        );
+
+        let export_type = &exports.types.get(Vec::<f32>::type_path()).unwrap();
+        let TypeData::Class(class) = &export_type[0].type_data else {
+            panic!("expected a class");
+        };
+        assert_eq!(class.members.len(), COLLECTION_SLOTS);
+        assert_eq!(class.members[3].name, "[3]");
+        assert_eq!(class.members[3].type_field, FieldType::Float);
+    }
+
+    #[test]
+    fn generate_map() {
+        #[derive(Component, Reflect)]
+        #[reflect(Component)]
+        struct Labels(HashMap<String, u32>);
+
+        let mut registry = TypeRegistry::new();
+        registry.register::<Labels>();
+
+        let exports = TypeExportRegistry::from_registry(&registry);
+        let map_path = HashMap::<String, u32>::type_path();
+        let export_type = &exports.types.get(map_path).unwrap();
+        assert_eq!(export_type.length(), 2);
+        assert_eq!(export_type[0].name, map_path.to_string() + ":::Entry");
+        assert_eq!(
+            export_type[0].type_data,
+            TypeData::Class(Class {
+                use_as: USE_AS_PROPERTY.to_vec(),
+                color: DEFAULT_COLOR.to_string(),
+                draw_fill: true,
+                members: vec![
+                    Member {
+                        name: "key".to_string(),
+                        property_type: None,
+                        type_field: FieldType::String,
+                        value: Default::default(),
+                    },
+                    Member {
+                        name: "value".to_string(),
+                        property_type: None,
+                        type_field: FieldType::Int,
+                        value: Default::default(),
+                    },
+                ],
+            })
+        );
+        let TypeData::Class(class) = &export_type[1].type_data else {
+            panic!("expected a class");
+        };
+        assert_eq!(class.members.len(), COLLECTION_SLOTS);
+        assert_eq!(
+            class.members[0].property_type,
+            Some(map_path.to_string() + ":::Entry")
+        );
+    }
+
+    #[test]
+    fn merge_keeps_ids_and_hand_authored_types() {
+        let export = |id, name: &str| TypeExport {
+            id,
+            name: name.to_string(),
+            type_data: TypeData::Enum(Enum {
+                storage_type: StorageType::String,
+                values: vec![],
+                values_as_flags: false,
+            }),
+        };
+        let mut project = serde_json::json!({
+            "compatibilityVersion": 1100,
+            "properties": [
+                { "name": "designer", "type": "string", "value": "kept" },
+                {
+                    "name": EXPORTED_TYPES_PROPERTY,
+                    "type": "string",
+                    "value": "game::Kept\ngame::Removed\n[f32; 3]",
+                },
+            ],
+            "propertyTypes": [
+                { "id": 3, "name": "game::Kept", "type": "enum" },
+                { "id": 4, "name": "DesignerClass", "type": "class" },
+                { "id": 5, "name": "designer::Class", "type": "class" },
+                { "id": 9, "name": "game::Removed", "type": "enum" },
+                { "id": 11, "name": "[f32; 3]", "type": "class" },
+            ]
+        });
+
+        merge_into_project(
+            &mut project,
+            vec![export(1, "game::New"), export(2, "game::Kept")],
+        )
+        .unwrap();
+
+        assert_eq!(project["compatibilityVersion"], 1100);
+        let types: Vec<(u64, &str)> = project["propertyTypes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| (t["id"].as_u64().unwrap(), t["name"].as_str().unwrap()))
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                (4, "DesignerClass"),
+                (5, "designer::Class"),
+                (3, "game::Kept"),
+                (12, "game::New")
+            ]
+        );
+        assert_eq!(
+            project["properties"],
+            serde_json::json!([
+                { "name": "designer", "type": "string", "value": "kept" },
+                {
+                    "name": EXPORTED_TYPES_PROPERTY,
+                    "type": "string",
+                    "value": "game::Kept\ngame::New",
+                },
+            ])
+        );
+
+        // Merging again does not change anything
+        let before = project.clone();
+        merge_into_project(
+            &mut project,
+            vec![export(1, "game::New"), export(2, "game::Kept")],
+        )
+        .unwrap();
+        assert_eq!(project, before);
+    }
 }
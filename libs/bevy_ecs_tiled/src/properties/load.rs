@@ -3,10 +3,12 @@ use bevy::ecs::reflect::ReflectBundle;
 use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
 use bevy::reflect::{
-    DynamicArray, DynamicEnum, DynamicStruct, DynamicTuple, DynamicTupleStruct, DynamicVariant,
-    NamedField, Reflect, ReflectMut, ReflectRef, TypeInfo, TypeRegistration, TypeRegistry,
-    UnnamedField, VariantInfo, VariantType,
+    DynamicArray, DynamicEnum, DynamicList, DynamicMap, DynamicSet, DynamicStruct, DynamicTuple,
+    DynamicTupleStruct, DynamicVariant, Map, NamedField, Reflect, ReflectFromReflect, ReflectMut,
+    ReflectRef, Set, Type, TypeInfo, TypeRegistration, TypeRegistry, UnnamedField, VariantInfo,
+    VariantType,
 };
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use tiled::{LayerType, Properties, PropertyValue, TileId};
 
@@ -24,7 +26,8 @@ impl DeserializedMapProperties<false> {
         registry: &TypeRegistry,
         load_context: &mut LoadContext<'_>,
     ) -> Self {
-        let map_props = DeserializedProperties::load(&map.properties, registry, load_context, true);
+        let map_props =
+            DeserializedProperties::load(&map.properties, registry, load_context, true, "map");
 
         let mut objects = HashMap::default();
         let mut layers = HashMap::default();
@@ -32,7 +35,13 @@ impl DeserializedMapProperties<false> {
         while let Some(layer) = to_process.pop() {
             layers.insert(
                 layer.id(),
-                DeserializedProperties::load(&layer.properties, registry, load_context, false),
+                DeserializedProperties::load(
+                    &layer.properties,
+                    registry,
+                    load_context,
+                    false,
+                    &format!("layer `{}`", layer.name),
+                ),
             );
             match layer.layer_type() {
                 LayerType::Objects(object) => {
@@ -44,6 +53,7 @@ impl DeserializedMapProperties<false> {
                                 registry,
                                 load_context,
                                 false,
+                                &format!("object `{}` (id {})", object.name, object.id()),
                             ),
                         );
                     }
@@ -70,6 +80,7 @@ impl DeserializedMapProperties<false> {
                                     registry,
                                     load_context,
                                     false,
+                                    &format!("tile {id} of tileset `{}`", s.name),
                                 ),
                             )
                         })
@@ -127,11 +138,13 @@ impl Clone for DeserializedProperties {
 }
 
 impl DeserializedProperties {
+    /// `owner` names what the properties are attached to in error messages
     fn load(
         properties: &tiled::Properties,
         registry: &TypeRegistry,
         load_cx: &mut LoadContext<'_>,
         resources_allowed: bool,
+        owner: &str,
     ) -> Self {
         let mut props: Vec<Box<dyn PartialReflect>> = Vec::new();
 
@@ -147,14 +160,14 @@ impl DeserializedProperties {
                 }
                 _ => {
                     bevy::log::warn!(
-                        "error deserializing property: unknown property `{name}`:`{property:?}`"
+                        "error deserializing property of {owner}: unknown property `{name}`:`{property:?}`"
                     );
                     continue;
                 }
             };
 
             let Some(reg) = reg else {
-                bevy::log::error!("error deserializing property `{name}`: `{reg_name}` is not registered in the TypeRegistry.");
+                bevy::log::error!("error deserializing property `{name}` of {owner}: `{reg_name}` is not registered in the TypeRegistry.");
                 continue;
             };
 
@@ -162,12 +175,12 @@ impl DeserializedProperties {
                 if reg.data::<ReflectResource>().is_some() {
                     if !resources_allowed {
                         bevy::log::warn!(
-                            "error deserializing property `{name}`: Resources are only allowed as map properties"
+                            "error deserializing property `{name}` of {owner}: Resources are only allowed as map properties"
                         );
                         continue;
                     }
                 } else {
-                    bevy::log::warn!("error deserializing property `{name}`: type `{reg_name}` is not registered as a Component, Bundle, or Resource");
+                    bevy::log::warn!("error deserializing property `{name}` of {owner}: type `{reg_name}` is not registered as a Component, Bundle, or Resource");
                     continue;
                 }
             }
@@ -177,7 +190,7 @@ impl DeserializedProperties {
                     props.push(prop);
                 }
                 Err(e) => {
-                    bevy::log::error!("error deserializing property `{name}`: {e}");
+                    bevy::log::error!("error deserializing property `{name}` of {owner}: {e}");
                 }
             }
         }
@@ -206,7 +219,8 @@ impl DeserializedProperties {
             let Some(reg) = registry.get(field.type_id()) else {
                 return Err(format!("type `{}` is not registered", field.type_path()));
             };
-            value = Self::deserialize_property(pv, reg, registry, load_cx, default_value)?;
+            value = Self::deserialize_property(pv, reg, registry, load_cx, default_value)
+                .map_err(|e| format!("in `{}`: {e}", field.name()))?;
         } else if let Some(def) = default_value {
             // If a default value from parent is provided, use it
             value = def.to_dynamic().into_partial_reflect();
@@ -247,7 +261,8 @@ impl DeserializedProperties {
             let Some(reg) = registry.get(field.type_id()) else {
                 return Err(format!("type `{}` is not registered", field.type_path()));
             };
-            value = Self::deserialize_property(pv, reg, registry, load_cx, default_value)?;
+            value = Self::deserialize_property(pv, reg, registry, load_cx, default_value)
+                .map_err(|e| format!("in `{}`: {e}", field.index()))?;
         } else if let Some(def) = default_value {
             // If a default value from parent is provided, use it
             value = def.to_dynamic().into_partial_reflect();
@@ -546,14 +561,117 @@ impl DeserializedProperties {
                     info.type_path()
                 ))
             }
-            (_, PV::ClassValue { .. }, TypeInfo::List(_)) => {
-                Err("lists are currently unsupported".to_string())
+            (_, PV::ClassValue { properties, .. }, TypeInfo::List(info)) => {
+                let reg = item_registration(registry, info.item_ty())?;
+                let mut out = DynamicList::default();
+                out.set_represented_type(Some(registration.type_info()));
+
+                // The default value of the field holding the collection comes first
+                let default_value_from_type =
+                    default_value_from_type_path(registry, info.type_path());
+                let default_value = default_value.or(default_value_from_type.as_deref());
+                let defaults = match default_value.map(|v| v.reflect_ref()) {
+                    Some(ReflectRef::List(l)) => l.iter().map(|v| v.to_dynamic()).collect(),
+                    _ => Vec::new(),
+                };
+
+                let slots = collection_slots(properties, info.type_path(), defaults, |pv, def| {
+                    let def = def.and_then(|d| d.try_as_reflect());
+                    Self::deserialize_property(pv, reg, registry, load_cx, def)
+                })?;
+                for value in slots {
+                    out.push_box(value);
+                }
+
+                Ok(Box::new(out))
             }
-            (_, PV::ClassValue { .. }, TypeInfo::Map(_)) => {
-                Err("maps are currently unsupported".to_string())
+            (_, PV::ClassValue { properties, .. }, TypeInfo::Set(info)) => {
+                let reg = item_registration(registry, info.value_ty())?;
+                let mut out = DynamicSet::default();
+                out.set_represented_type(Some(registration.type_info()));
+
+                // The default value of the field holding the collection comes first
+                let default_value_from_type =
+                    default_value_from_type_path(registry, info.type_path());
+                let default_value = default_value.or(default_value_from_type.as_deref());
+                let defaults = match default_value.map(|v| v.reflect_ref()) {
+                    Some(ReflectRef::Set(s)) => s.iter().map(|v| v.to_dynamic()).collect(),
+                    _ => Vec::new(),
+                };
+
+                let slots = collection_slots(properties, info.type_path(), defaults, |pv, def| {
+                    let def = def.and_then(|d| d.try_as_reflect());
+                    Self::deserialize_property(pv, reg, registry, load_cx, def)
+                })?;
+                for value in slots {
+                    out.insert_boxed(hashable(value, reg)?);
+                }
+
+                Ok(Box::new(out))
             }
-            (_, PV::ClassValue { .. }, TypeInfo::Set(_)) => {
-                Err("sets are currently unsupported".to_string())
+            (_, PV::ClassValue { properties, .. }, TypeInfo::Map(info)) => {
+                let key_reg = item_registration(registry, info.key_ty())?;
+                let value_reg = item_registration(registry, info.value_ty())?;
+                let mut out = DynamicMap::default();
+                out.set_represented_type(Some(registration.type_info()));
+
+                // The default value of the field holding the collection comes first
+                let default_value_from_type =
+                    default_value_from_type_path(registry, info.type_path());
+                let default_value = default_value.or(default_value_from_type.as_deref());
+                let defaults = match default_value.map(|v| v.reflect_ref()) {
+                    Some(ReflectRef::Map(m)) => m
+                        .iter()
+                        .map(|(k, v)| (k.to_dynamic(), v.to_dynamic()))
+                        .collect(),
+                    _ => Vec::new(),
+                };
+
+                let slots =
+                    collection_slots(properties, info.type_path(), defaults, |entry, def| {
+                        let PV::ClassValue {
+                            properties: mut entry,
+                            ..
+                        } = entry
+                        else {
+                            return Err(format!("invalid entry in `{}`", info.type_path()));
+                        };
+                        // An edited entry only holds what differs from the default entry
+                        let (def_key, def_value) = match def {
+                            Some((k, v)) => (k.try_as_reflect(), v.try_as_reflect()),
+                            None => (None, None),
+                        };
+                        let key = match (entry.remove("key"), def) {
+                            (Some(key), _) => Self::deserialize_property(
+                                key, key_reg, registry, load_cx, def_key,
+                            )?,
+                            (None, Some((key, _))) => key.to_dynamic(),
+                            (None, None) => {
+                                return Err(format!(
+                                    "entry in `{}` must have both a key and a value",
+                                    info.type_path()
+                                ))
+                            }
+                        };
+                        let value = match (entry.remove("value"), def) {
+                            (Some(value), _) => Self::deserialize_property(
+                                value, value_reg, registry, load_cx, def_value,
+                            )?,
+                            (None, Some((_, value))) => value.to_dynamic(),
+                            (None, None) => {
+                                return Err(format!(
+                                    "entry in `{}` must have both a key and a value",
+                                    info.type_path()
+                                ))
+                            }
+                        };
+                        Ok((key, value))
+                    })?;
+                for (key, value) in slots {
+                    out.insert_boxed(hashable(key, key_reg)?, value);
+                }
+
+                Ok(Box::new(out))
             }
             // Note: ClassValue and TypeInfo::Value is not included
             (a, b, c) => Err(format!(
@@ -569,6 +687,57 @@ impl DeserializedProperties {
     }
 }
 
+fn item_registration(registry: &TypeRegistry, ty: Type) -> Result<&TypeRegistration, String> {
+    registry
+        .get(ty.id())
+        .ok_or_else(|| format!("type `{}` is not registered", ty.path()))
+}
+
+/// Values of a list, set or map, in slot order: see [crate::properties]
+///
+/// Tiled only writes the slots which differ from the default value, so the loaded slots are
+/// merged over `defaults`, the default value items in the order they were exported.
+/// `load` gets the default item of the slot, if any.
+fn collection_slots<T>(
+    properties: Properties,
+    type_path: &str,
+    defaults: Vec<T>,
+    mut load: impl FnMut(PropertyValue, Option<&T>) -> Result<T, String>,
+) -> Result<Vec<T>, String> {
+    let mut slots: BTreeMap<usize, T> = defaults.into_iter().enumerate().collect();
+    for (name, value) in properties {
+        let index = name
+            .strip_prefix('[')
+            .and_then(|n| n.strip_suffix(']'))
+            .and_then(|n| n.parse::<usize>().ok())
+            .ok_or_else(|| format!("unexpected property `{name}` on `{type_path}`"))?;
+        let value = load(value, slots.get(&index))?;
+        slots.insert(index, value);
+    }
+    Ok(slots.into_values().collect())
+}
+
+/// Set values and map keys must be hashed: dynamic values are not, convert them to their concrete type
+fn hashable(
+    value: Box<dyn PartialReflect>,
+    registration: &TypeRegistration,
+) -> Result<Box<dyn PartialReflect>, String> {
+    if value.reflect_hash().is_some() {
+        return Ok(value);
+    }
+    registration
+        .data::<ReflectFromReflect>()
+        .and_then(|f| f.from_reflect(value.as_ref()))
+        .map(|v| v.into_partial_reflect())
+        .filter(|v| v.reflect_hash().is_some())
+        .ok_or_else(|| {
+            format!(
+                "`{}` cannot be used as a set value or a map key",
+                registration.type_info().type_path()
+            )
+        })
+}
+
 fn default_value_from_type_path(registry: &TypeRegistry, path: &str) -> Option<Box<dyn Reflect>> {
     registry
         .get_with_type_path(path)
@@ -812,4 +981,147 @@ mod tests {
         let v: Result<StructComponent, _> = FromReflect::take_from_reflect(res);
         assert_eq!(v.unwrap(), raw_value);
     }
+
+    #[test]
+    fn deserialize_collections() {
+        #[derive(Component, Reflect, Default, PartialEq, Debug)]
+        #[reflect(Component, Default)]
+        struct Path {
+            positions: Vec<Vec2>,
+            labels: HashMap<String, u32>,
+        }
+
+        let mut registry = TypeRegistry::new();
+        registry.register::<Path>();
+
+        let point = |x, y| PropertyValue::ClassValue {
+            property_type: Vec2::type_path().to_string(),
+            properties: std::collections::HashMap::from([
+                ("x".to_string(), PropertyValue::FloatValue(x)),
+                ("y".to_string(), PropertyValue::FloatValue(y)),
+            ]),
+        };
+        let tiled_value = PropertyValue::ClassValue {
+            property_type: Path::type_path().to_string(),
+            properties: std::collections::HashMap::from([
+                (
+                    "positions".to_string(),
+                    PropertyValue::ClassValue {
+                        property_type: Vec::<Vec2>::type_path().to_string(),
+                        // Unset slots are skipped, the others keep their order
+                        properties: std::collections::HashMap::from([
+                            ("[10]".to_string(), point(3., 4.)),
+                            ("[0]".to_string(), point(1., 2.)),
+                            ("[2]".to_string(), point(0., 0.5)),
+                        ]),
+                    },
+                ),
+                (
+                    "labels".to_string(),
+                    PropertyValue::ClassValue {
+                        property_type: HashMap::<String, u32>::type_path().to_string(),
+                        properties: std::collections::HashMap::from([(
+                            "[0]".to_string(),
+                            PropertyValue::ClassValue {
+                                property_type: String::new(),
+                                properties: std::collections::HashMap::from([
+                                    (
+                                        "key".to_string(),
+                                        PropertyValue::StringValue("dock".to_string()),
+                                    ),
+                                    ("value".to_string(), PropertyValue::IntValue(7)),
+                                ]),
+                            },
+                        )]),
+                    },
+                ),
+            ]),
+        };
+
+        let res = DeserializedProperties::deserialize_property(
+            tiled_value,
+            registry.get_with_type_path(Path::type_path()).unwrap(),
+            &registry,
+            &mut None,
+            None,
+        )
+        .unwrap();
+
+        let v: Path = FromReflect::take_from_reflect(res).unwrap();
+        assert_eq!(
+            v.positions,
+            vec![Vec2::new(1., 2.), Vec2::new(0., 0.5), Vec2::new(3., 4.)]
+        );
+        assert_eq!(v.labels, HashMap::from_iter([("dock".to_string(), 7)]));
+    }
+
+    #[test]
+    fn deserialize_collections_over_defaults() {
+        #[derive(Component, Reflect, PartialEq, Debug)]
+        #[reflect(Component, Default)]
+        struct Path {
+            positions: Vec<f32>,
+            labels: std::collections::BTreeMap<String, u32>,
+        }
+        impl Default for Path {
+            fn default() -> Self {
+                Self {
+                    positions: vec![1., 2., 3.],
+                    labels: [("airlock".to_string(), 1), ("dock".to_string(), 2)].into(),
+                }
+            }
+        }
+
+        let mut registry = TypeRegistry::new();
+        registry.register::<Path>();
+
+        // Designer only edited the second position and the value of the second label
+        let tiled_value = PropertyValue::ClassValue {
+            property_type: Path::type_path().to_string(),
+            properties: std::collections::HashMap::from([
+                (
+                    "positions".to_string(),
+                    PropertyValue::ClassValue {
+                        property_type: Vec::<f32>::type_path().to_string(),
+                        properties: std::collections::HashMap::from([(
+                            "[1]".to_string(),
+                            PropertyValue::FloatValue(5.),
+                        )]),
+                    },
+                ),
+                (
+                    "labels".to_string(),
+                    PropertyValue::ClassValue {
+                        property_type: String::new(),
+                        properties: std::collections::HashMap::from([(
+                            "[1]".to_string(),
+                            PropertyValue::ClassValue {
+                                property_type: String::new(),
+                                properties: std::collections::HashMap::from([(
+                                    "value".to_string(),
+                                    PropertyValue::IntValue(7),
+                                )]),
+                            },
+                        )]),
+                    },
+                ),
+            ]),
+        };
+
+        let res = DeserializedProperties::deserialize_property(
+            tiled_value,
+            registry.get_with_type_path(Path::type_path()).unwrap(),
+            &registry,
+            &mut None,
+            None,
+        )
+        .unwrap();
+
+        let v: Path = FromReflect::take_from_reflect(res).unwrap();
+        assert_eq!(v.positions, vec![1., 5., 3.]);
+        assert_eq!(
+            v.labels,
+            [("airlock".to_string(), 1), ("dock".to_string(), 7)].into()
+        );
+    }
 }
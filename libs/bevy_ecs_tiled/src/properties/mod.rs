@@ -2,6 +2,13 @@
 //!
 //! It is only available when the `user_properties` feature is enabled.
 //!
+//! ## Lists, sets and maps
+//!
+//! Tiled has no property type for variable length collections, so `Vec<T>`, sets and maps are
+//! exported as a class holding a fixed number of optional slots named `[0]`, `[1]`, ...
+//! Only the slots set in Tiled are loaded, in slot order, and empty slots in between are skipped.
+//! Each map slot is an entry class with a `key` and a `value` member.
+//!
 //! See the [associated example](https://github.com/adrien-bon/bevy_ecs_tiled/blob/main/examples/user_properties.rs) or the [dedicated book section](https://adrien-bon.github.io/bevy_ecs_tiled/guides/properties.html) for more information.
 
 pub(crate) mod command;
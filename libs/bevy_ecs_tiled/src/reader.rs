@@ -1,31 +1,143 @@
 //! This module contains an implementation for [tiled::ResourceReader]
 
-use bevy::asset::LoadContext;
+use bevy::{asset::LoadContext, log::warn, platform::collections::HashMap};
 use std::{
+    borrow::Cow,
     io::{Cursor, Error as IoError, ErrorKind, Read},
-    path::Path,
+    path::{Path, PathBuf},
     sync::Arc,
 };
 
-pub(crate) struct BytesResourceReader<'a, 'b> {
+pub(crate) struct BytesResourceReader {
     bytes: Arc<[u8]>,
-    context: &'a mut LoadContext<'b>,
+    /// Object templates content, keyed by the path Tiled will ask for
+    templates: HashMap<PathBuf, Arc<[u8]>>,
 }
-impl<'a, 'b> BytesResourceReader<'a, 'b> {
-    pub(crate) fn new(bytes: &'a [u8], context: &'a mut LoadContext<'b>) -> Self {
+impl BytesResourceReader {
+    pub(crate) fn new(bytes: &[u8]) -> Self {
         Self {
             bytes: Arc::from(bytes),
-            context,
+            templates: HashMap::default(),
         }
     }
+
+    /// Provide the object templates read with [read_templates]
+    pub(crate) fn with_templates(mut self, templates: HashMap<PathBuf, Arc<[u8]>>) -> Self {
+        self.templates = templates;
+        self
+    }
+}
+
+/// Read every object template (.tx) referenced by a .tmx file.
+///
+/// Templates must be read ahead of the synchronous Tiled loader.
+/// Reading them through the [LoadContext] also registers them as dependencies
+/// of the map, so editing a template reloads every map using it.
+pub(crate) async fn read_templates(
+    bytes: &[u8],
+    context: &mut LoadContext<'_>,
+) -> HashMap<PathBuf, Arc<[u8]>> {
+    let mut templates = HashMap::default();
+    let content = String::from_utf8_lossy(bytes);
+    let base_path = context.path().parent().map(Path::to_path_buf).unwrap_or_default();
+    for template in template_references(&content) {
+        let template = template.as_str();
+        // Tiled resolves templates relative to the map directory
+        let path = base_path.join(template);
+        if templates.contains_key(&path) {
+            continue;
+        }
+        let asset_path = match context.asset_path().resolve_embed(template) {
+            Ok(asset_path) => asset_path,
+            Err(e) => {
+                warn!("Invalid object template path {template:?}: {e}");
+                continue;
+            }
+        };
+        match context.read_asset_bytes(asset_path).await {
+            Ok(data) => {
+                templates.insert(path, Arc::from(data));
+            }
+            Err(e) => warn!("Could not read object template {template:?}: {e}"),
+        }
+    }
+    templates
 }
 
-impl<'a> tiled::ResourceReader for BytesResourceReader<'a, '_> {
-    type Resource = Box<dyn Read + 'a>;
+/// Values of every `template` attribute, with XML entities unescaped
+fn template_references(content: &str) -> Vec<String> {
+    const ATTRIBUTE: &str = "template=\"";
+
+    let mut references = vec![];
+    let mut rest = content;
+    while let Some(start) = rest.find(ATTRIBUTE) {
+        rest = &rest[start + ATTRIBUTE.len()..];
+        let Some(end) = rest.find('"') else {
+            break;
+        };
+        references.push(unescape_xml(&rest[..end]).into_owned());
+        rest = &rest[end..];
+    }
+    references
+}
+
+/// Replace the predefined XML entities and character references, unknown ones are kept as is
+fn unescape_xml(value: &str) -> Cow<'_, str> {
+    if !value.contains('&') {
+        return Cow::Borrowed(value);
+    }
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..end];
+        let unescaped = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        match unescaped {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    Cow::Owned(out)
+}
+
+impl tiled::ResourceReader for BytesResourceReader {
+    type Resource = Box<dyn Read>;
     type Error = IoError;
 
     fn read_from(&mut self, path: &Path) -> std::result::Result<Self::Resource, Self::Error> {
         if let Some(extension) = path.extension() {
+            if extension == "tx" {
+                return match self.templates.get(path) {
+                    Some(data) => Ok(Box::new(Cursor::new(data.clone()))),
+                    None => Err(IoError::new(
+                        ErrorKind::NotFound,
+                        format!("object template {path:?} was not read beforehand"),
+                    )),
+                };
+            }
             if extension == "tsx" {
                 if let Some(f) = path.file_name() {
                     if let Some(f) = f.to_str() {
@@ -35,7 +147,10 @@ impl<'a> tiled::ResourceReader for BytesResourceReader<'a, '_> {
                             "pad_test.tsx" => return Ok(Box::new(Cursor::new(include_bytes!("../../../game/assets/tilemaps/v1.0/pad_test.tsx")))),
                             "test.tsx" => return Ok(Box::new(Cursor::new(include_bytes!("../../../game/assets/tilemaps/v1.0/test.tsx")))),
                             "main.tsx" => return Ok(Box::new(Cursor::new(include_bytes!("../../../game/assets/tilemaps/v2.0/main.tsx")))),
-                            _ => {panic!("PLEASE ADD THE TILESET \"{:?}\" INTO libs/bevy_ecs_tiled/src/reader.rs", path.to_str())}
+                            _ => return Err(IoError::new(
+                                ErrorKind::NotFound,
+                                format!("tileset {f:?} is not embedded, add it into libs/bevy_ecs_tiled/src/reader.rs"),
+                            )),
                         }
                     }
                 }
@@ -45,4 +160,17 @@ impl<'a> tiled::ResourceReader for BytesResourceReader<'a, '_> {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
 
+    #[test]
+    fn template_paths_are_unescaped() {
+        let tmx = r#"<object id="1" template="templates/r&amp;d &#x41;&#66;.tx" x="0"/>
+<object id="2" template="plain.tx"/><object id="3" template="odd &unknown; &amp.tx"/>"#;
+        assert_eq!(
+            template_references(tmx),
+            vec!["templates/r&d AB.tx", "plain.tx", "odd &unknown; &amp.tx"]
+        );
+    }
+}
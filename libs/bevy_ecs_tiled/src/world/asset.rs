@@ -25,7 +25,8 @@ pub struct TiledWorld {
     ///
     /// Contains both the [TiledMap] handle and its associated [Rect] boundary
     /// as defined by the `.world` file.
-    /// Note that the actual map boundaries are not taken into account for world chunking.
+    /// Note that the actual map boundaries are not taken into account for world chunking,
+    /// except for infinite maps which boundaries are computed from their chunks.
     pub maps: Vec<(Rect, Handle<TiledMap>)>,
 }
 
@@ -71,9 +72,6 @@ pub enum TiledWorldLoaderError {
     /// No map was found in this world
     #[error("No map found in this world")]
     EmptyWorld,
-    /// Found an infinite map in this world which is not supported
-    #[error("Infinite map found in this world (not supported)")]
-    WorldWithInfiniteMap,
 }
 
 pub(crate) struct TiledWorldLoader {
@@ -109,7 +107,7 @@ impl AssetLoader for TiledWorldLoader {
         let world = {
             let mut loader = tiled::Loader::with_cache_and_reader(
                 self.cache.clone(),
-                BytesResourceReader::new(&bytes, load_context),
+                BytesResourceReader::new(&bytes),
             );
             loader
                 .load_world(&world_path)
@@ -120,45 +118,67 @@ impl AssetLoader for TiledWorldLoader {
             return Err(TiledWorldLoaderError::EmptyWorld);
         }
 
-        // Calculate the full rect of the world
+        // Compute each map boundary, in Tiled world coordinates
         let mut world_rect = Rect::new(0.0, 0.0, 0.0, 0.0);
-        for map in world.maps.iter() {
-            let (Some(map_width), Some(map_height)) = (map.width, map.height) else {
-                // Assume that we cannot get map width / map height because it's an infinite map
-                return Err(TiledWorldLoaderError::WorldWithInfiniteMap);
-            };
-            let map_rect = Rect::new(
-                map.x as f32,
-                map.y as f32, // Invert for Tiled to Bevy Y axis
-                map.x as f32 + map_width as f32,
-                map.y as f32 + map_height as f32,
-            );
-
-            world_rect = world_rect.union(map_rect);
-        }
-
-        // Load all maps
-        let mut maps = Vec::new();
-        for map in world.maps.iter() {
+        let mut maps_info = Vec::new();
+        for (idx, map) in world.maps.iter().enumerate() {
             // Seems safe to unwrap() here since we do it on the world path (which should always have a parent)
             let map_path = world_path.parent().unwrap().join(map.filename.clone());
 
-            let (Some(map_width), Some(map_height)) = (map.width, map.height) else {
-                // Assume that we cannot get map width / map height because it's an infinite map
-                return Err(TiledWorldLoaderError::WorldWithInfiniteMap);
-            };
-
-            maps.push((
-                Rect::new(
-                    map.x as f32,
-                    world_rect.max.y - map_height as f32 - map.y as f32, // Invert for Tiled to Bevy Y axis
-                    map.x as f32 + map_width as f32,
-                    world_rect.max.y - map.y as f32,
+            let (map_rect, handle) = match (map.width, map.height) {
+                (Some(map_width), Some(map_height)) => (
+                    Rect::new(
+                        map.x as f32,
+                        map.y as f32,
+                        map.x as f32 + map_width as f32,
+                        map.y as f32 + map_height as f32,
+                    ),
+                    load_context.load(AssetPath::from(map_path)),
                 ),
-                load_context.load(AssetPath::from(map_path)),
-            ));
+                _ => {
+                    // We cannot get map width / map height from the .world file because it's
+                    // an infinite map: load it right away to know which chunks it contains
+                    let loaded = load_context
+                        .loader()
+                        .immediate()
+                        .load::<TiledMap>(AssetPath::from(map_path))
+                        .await
+                        .map_err(|e| {
+                            std::io::Error::other(format!("Could not load infinite map: {e}"))
+                        })?;
+                    let tiled_map = loaded.get();
+                    // Map origin is the top-left corner of chunk (0, 0):
+                    // move the boundary to the top-left corner of the top-left chunk
+                    let min = Vec2::new(
+                        map.x as f32 - tiled_map.tiled_offset.x,
+                        map.y as f32 + tiled_map.tiled_offset.y,
+                    );
+                    (
+                        Rect::from_corners(min, min + tiled_map.rect.size()),
+                        load_context.add_loaded_labeled_asset(format!("map{idx}"), loaded),
+                    )
+                }
+            };
+            world_rect = world_rect.union(map_rect);
+            maps_info.push((map_rect, handle));
         }
 
+        // Invert for Tiled to Bevy Y axis
+        let maps: Vec<_> = maps_info
+            .into_iter()
+            .map(|(rect, handle)| {
+                (
+                    Rect::new(
+                        rect.min.x,
+                        world_rect.max.y - rect.max.y,
+                        rect.max.x,
+                        world_rect.max.y - rect.min.y,
+                    ),
+                    handle,
+                )
+            })
+            .collect();
+
         trace!(?maps, "maps");
 
         let world = TiledWorld {
@@ -20,6 +20,23 @@ impl TiledWorldChunking {
     }
 }
 
+/// [Component] holding Tiled world chunking hysteresis, in pixels.
+///
+/// Once spawned, a map (or a chunk of an infinite map) is only despawned when it no longer overlaps
+/// the chunking area grown by this margin. This prevents spawning and despawning the same maps
+/// over and over when the [Camera] moves back and forth around a boundary.
+///
+/// Must be added to the [Entity] holding the world.
+#[derive(Component, Reflect, Copy, Clone, Debug)]
+#[reflect(Component, Default, Debug)]
+pub struct TiledWorldChunkingHysteresis(pub Vec2);
+
+impl Default for TiledWorldChunkingHysteresis {
+    fn default() -> Self {
+        Self(Vec2::splat(64.))
+    }
+}
+
 /// Marker [Component] for a Tiled world.
 #[derive(Component, Default, Reflect, Copy, Clone, Debug)]
 #[reflect(Component, Default, Debug)]
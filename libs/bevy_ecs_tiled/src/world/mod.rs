@@ -19,6 +19,7 @@ use bevy::{
     prelude::*,
 };
 use bevy_ecs_tilemap::{map::TilemapRenderSettings, prelude::TilemapAnchor};
+use tiled::ChunkData;
 
 /// Wrapper around the [Handle] to the `.world` file representing the [TiledWorld].
 ///
@@ -31,6 +32,7 @@ use bevy_ecs_tilemap::{map::TilemapRenderSettings, prelude::TilemapAnchor};
     TiledMapLayerZOffset,
     TilemapRenderSettings,
     TiledWorldChunking,
+    TiledWorldChunkingHysteresis,
     Visibility,
     Transform
 )]
@@ -41,6 +43,7 @@ pub(crate) fn build(app: &mut bevy::prelude::App) {
         .init_asset_loader::<TiledWorldLoader>()
         .register_type::<TiledWorldHandle>()
         .register_type::<TiledWorldChunking>()
+        .register_type::<TiledWorldChunkingHysteresis>()
         .register_type::<TiledWorldMarker>()
         .register_type::<RespawnTiledWorld>()
         .register_type::<TiledWorldStorage>()
@@ -50,12 +53,16 @@ pub(crate) fn build(app: &mut bevy::prelude::App) {
             PreUpdate,
             process_loaded_worlds.after(crate::map::process_loaded_maps),
         )
-        .add_systems(PostUpdate, (handle_world_events, world_chunking).chain());
+        .add_systems(
+            PostUpdate,
+            (handle_world_events, world_chunking, stream_map_chunks).chain(),
+        );
 }
 
-#[allow(clippy::type_complexity)]
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 fn world_chunking(
-    camera_query: Query<&Transform, (With<Camera>, Changed<Transform>)>,
+    camera_query: Query<&Transform, With<Camera>>,
+    moved_cameras: Query<(), (With<Camera>, Changed<Transform>)>,
     worlds: Res<Assets<TiledWorld>>,
     asset_server: Res<AssetServer>,
     mut commands: Commands,
@@ -63,8 +70,9 @@ fn world_chunking(
         (
             Entity,
             &TiledWorldHandle,
-            &GlobalTransform,
-            &TiledWorldChunking,
+            Ref<GlobalTransform>,
+            Ref<TiledWorldChunking>,
+            Ref<TiledWorldChunkingHysteresis>,
             &TilemapAnchor,
             &TiledMapLayerZOffset,
             &TilemapRenderSettings,
@@ -78,6 +86,7 @@ fn world_chunking(
         world_handle,
         world_transform,
         world_chunking,
+        hysteresis,
         anchor,
         layer_offset,
         render_settings,
@@ -104,44 +113,38 @@ fn world_chunking(
         let offset = tiled_world.offset(anchor);
 
         if let Some(chunking) = world_chunking.0 {
-            let mut visible_maps = Vec::new();
-            let cameras: Vec<Aabb2d> = camera_query
-                .iter()
-                .map(|transform| {
-                    Aabb2d::new(
-                        Vec2::new(transform.translation.x, transform.translation.y),
-                        chunking,
-                    )
-                })
-                .collect();
+            // Only look again at which maps are visible when a camera moved,
+            // or when the world itself moved, was (re)spawned or got new chunking settings
+            if moved_cameras.is_empty()
+                && !storage.is_changed()
+                && !world_transform.is_changed()
+                && !world_chunking.is_changed()
+                && !hysteresis.is_changed()
+            {
+                continue;
+            }
+            let (spawn_areas, keep_areas) = chunking_areas(
+                camera_query.iter().map(|t| t.translation.truncate()),
+                chunking,
+                hysteresis.0,
+            );
             // Check which map is visible by testing them against each camera (if there are multiple)
             // If map aabb overlaps with the camera_view, it is visible
+            let mut maps_aabb = Vec::new();
             for_each_map(
                 tiled_world,
-                world_transform,
+                &world_transform,
                 offset.extend(0.0),
-                |idx, aabb| {
-                    for c in cameras.iter() {
-                        if aabb.intersects(c) {
-                            visible_maps.push(idx);
-                        }
-                    }
-                },
+                |_, aabb| maps_aabb.push(aabb),
+            );
+            let spawned: Vec<usize> = storage.spawned_maps.keys().copied().collect();
+            (to_remove, to_spawn) = streaming_changes(
+                &spawned,
+                0..maps_aabb.len(),
+                |idx| maps_aabb[idx],
+                &spawn_areas,
+                &keep_areas,
             );
-
-            // All the maps that are visible but not already spawned should be spawned
-            for idx in visible_maps.iter() {
-                if !storage.spawned_maps.contains_key(idx) {
-                    to_spawn.push(*idx);
-                }
-            }
-
-            // All the maps that are spawned but not visible should be removed
-            for (idx, _) in storage.spawned_maps.iter() {
-                if !visible_maps.iter().any(|i| i == idx) {
-                    to_remove.push(*idx);
-                }
-            }
         } else if storage.spawned_maps.is_empty() {
             // No chunking and we don't have spawned any map yet: just spawn all maps
             for idx in 0..tiled_world.maps.len() - 1 {
@@ -178,6 +181,12 @@ fn world_chunking(
                     *render_settings,
                 ))
                 .id();
+            if world_chunking.0.is_some() {
+                // Also stream the chunks of infinite maps
+                commands
+                    .entity(map_entity)
+                    .insert(TiledMapChunkStreaming::default());
+            }
             debug!(
                 "Spawn map (index = {}, handle = {:?},  entity = {:?})",
                 idx, handle, map_entity
@@ -187,6 +196,222 @@ fn world_chunking(
     }
 }
 
+/// Areas around each [Camera] position where maps and chunks should be spawned,
+/// and the larger areas where they should be kept once spawned.
+fn chunking_areas(
+    cameras: impl Iterator<Item = Vec2>,
+    chunking: Vec2,
+    hysteresis: Vec2,
+) -> (Vec<Aabb2d>, Vec<Aabb2d>) {
+    cameras
+        .map(|center| {
+            (
+                Aabb2d::new(center, chunking),
+                Aabb2d::new(center, chunking + hysteresis),
+            )
+        })
+        .unzip()
+}
+
+/// Returns which of the `spawned` maps or chunks should be despawned
+/// and which of the `candidates` should be spawned.
+///
+/// A candidate is spawned once it overlaps one of the `spawn_areas`,
+/// and is kept until it no longer overlaps any of the `keep_areas`.
+fn streaming_changes<K: Copy + PartialEq>(
+    spawned: &[K],
+    candidates: impl Iterator<Item = K>,
+    aabb: impl Fn(K) -> Aabb2d,
+    spawn_areas: &[Aabb2d],
+    keep_areas: &[Aabb2d],
+) -> (Vec<K>, Vec<K>) {
+    let to_remove = spawned
+        .iter()
+        .copied()
+        .filter(|k| {
+            let aabb = aabb(*k);
+            !keep_areas.iter().any(|c| aabb.intersects(c))
+        })
+        .collect();
+    let to_spawn = candidates
+        .filter(|k| !spawned.contains(k))
+        .filter(|k| {
+            let aabb = aabb(*k);
+            spawn_areas.iter().any(|c| aabb.intersects(c))
+        })
+        .collect();
+    (to_remove, to_spawn)
+}
+
+/// System to spawn and despawn the chunks of infinite maps around cameras.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+fn stream_map_chunks(
+    camera_query: Query<&Transform, With<Camera>>,
+    maps: Res<Assets<TiledMap>>,
+    mut commands: Commands,
+    world_query: Query<
+        (&TiledWorldChunking, &TiledWorldChunkingHysteresis),
+        With<TiledWorldMarker>,
+    >,
+    mut map_query: Query<
+        (
+            &ChildOf,
+            &TiledMapHandle,
+            &GlobalTransform,
+            &TilemapAnchor,
+            &TilemapRenderSettings,
+            &mut TiledMapChunkStreaming,
+            &mut TiledMapStorage,
+        ),
+        With<TiledMapMarker>,
+    >,
+    mut chunk_event: EventWriter<TiledChunkCreated>,
+    mut tile_event: EventWriter<TiledTileCreated>,
+) {
+    for (
+        child_of,
+        map_handle,
+        map_transform,
+        anchor,
+        render_settings,
+        mut streaming,
+        mut storage,
+    ) in map_query.iter_mut()
+    {
+        if streaming.layers.is_empty() {
+            continue;
+        }
+        let Ok((TiledWorldChunking(Some(chunking)), hysteresis)) =
+            world_query.get(child_of.parent())
+        else {
+            continue;
+        };
+        let Some(tiled_map) = maps.get(&map_handle.0) else {
+            continue;
+        };
+        let (spawn_areas, keep_areas) = chunking_areas(
+            camera_query.iter().map(|t| t.translation.truncate()),
+            *chunking,
+            hysteresis.0,
+        );
+
+        // Chunk boundaries, in world space
+        let grid_size = get_grid_size(&tiled_map.map);
+        let chunk_size = Vec2::new(
+            ChunkData::WIDTH as f32 * grid_size.x,
+            ChunkData::HEIGHT as f32 * grid_size.y,
+        );
+        let chunk_aabb = |chunk: IVec2| {
+            let min = chunk.as_vec2() * chunk_size;
+            let max = min + chunk_size;
+            let corners = [
+                Vec2::new(min.x, min.y),
+                Vec2::new(min.x, max.y),
+                Vec2::new(max.x, max.y),
+                Vec2::new(max.x, min.y),
+            ]
+            .map(|corner| {
+                let position = from_tiled_position_to_world_space(tiled_map, anchor, corner);
+                map_transform
+                    .transform_point(position.extend(0.))
+                    .truncate()
+            });
+            Aabb2d::from_point_cloud(Isometry2d::IDENTITY, &corners)
+        };
+
+        let topleft = IVec2::new(tiled_map.topleft_chunk.0, tiled_map.topleft_chunk.1);
+        let bottomright = IVec2::new(tiled_map.bottomright_chunk.0, tiled_map.bottomright_chunk.1);
+        let spawned: Vec<IVec2> = streaming.chunks.keys().copied().collect();
+        let (to_remove, to_spawn) = streaming_changes(
+            &spawned,
+            (topleft.x..=bottomright.x)
+                .flat_map(|x| (topleft.y..=bottomright.y).map(move |y| IVec2::new(x, y))),
+            chunk_aabb,
+            &spawn_areas,
+            &keep_areas,
+        );
+
+        // Despawn chunks which are too far away from every camera
+        for chunk in to_remove {
+            let Some(chunk_storage) = streaming.chunks.remove(&chunk) else {
+                continue;
+            };
+            debug!("Despawn chunk {:?} (map = {:?})", chunk, map_handle.0);
+            for chunk_entity in chunk_storage.layers.values() {
+                commands.entity(*chunk_entity).try_despawn();
+            }
+            for (key, entities) in chunk_storage.tiles.iter() {
+                if let Some(tiles) = storage.tiles.get_mut(key) {
+                    tiles.retain(|e| !entities.contains(e));
+                }
+            }
+        }
+
+        // Spawn chunks which are visible but not already spawned
+        let mut tile_events = Vec::new();
+        let mut new_tiles: Vec<((String, tiled::TileId), Vec<Entity>)> = Vec::new();
+        for chunk in to_spawn {
+            debug!("Spawn chunk {:?} (map = {:?})", chunk, map_handle.0);
+            // Keep track of empty chunks as well so we don't try again next frame
+            let mut chunk_storage = TiledMapStorage::default();
+            for (layer_event, opacity) in streaming.layers.iter() {
+                let Some(chunk_entity) = crate::map::loader::load_tiles_chunk(
+                    &mut commands,
+                    tiled_map,
+                    layer_event,
+                    (chunk.x, chunk.y),
+                    render_settings,
+                    &mut chunk_storage.tiles,
+                    &mut tile_events,
+                    anchor,
+                    *opacity,
+                ) else {
+                    continue;
+                };
+                let Some(layer) = layer_event.get_layer(&maps) else {
+                    continue;
+                };
+                chunk_storage.layers.insert(layer.id(), chunk_entity);
+                let event = TiledChunkCreated {
+                    layer: *layer_event,
+                    entity: chunk_entity,
+                    chunk,
+                };
+                commands.trigger_targets(event, layer_event.map.entity);
+                chunk_event.write(event);
+            }
+            for (key, entities) in chunk_storage.tiles.iter() {
+                storage
+                    .tiles
+                    .entry(key.clone())
+                    .or_default()
+                    .extend(entities.iter().copied());
+                new_tiles.push((key.clone(), entities.clone()));
+            }
+            streaming.chunks.insert(chunk, chunk_storage);
+        }
+
+        #[cfg(feature = "user_properties")]
+        if !new_tiles.is_empty() {
+            use crate::properties::command::PropertiesCommandExt;
+            let props = tiled_map.properties.clone().hydrate(&storage.objects);
+            for (id, entities) in new_tiles.iter() {
+                let Some(p) = props.tiles.get(&id.0).and_then(|e| e.get(&id.1)) else {
+                    continue;
+                };
+                for &entity in entities {
+                    commands.entity(entity).insert_properties(p.clone());
+                }
+            }
+        }
+
+        for e in tile_events {
+            commands.trigger_targets(e, e.layer.map.entity);
+            tile_event.write(e);
+        }
+    }
+}
+
 /// System to spawn a world once it has been fully loaded.
 #[allow(clippy::type_complexity)]
 fn process_loaded_worlds(
@@ -331,3 +556,77 @@ pub(crate) fn for_each_map<F: FnMut(usize, Aabb2d)>(
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A row of 100px wide chunks, chunk `i` spans x = [100 * i, 100 * (i + 1)]
+    fn chunk_aabb(i: i32) -> Aabb2d {
+        Aabb2d::new(Vec2::new(100. * i as f32 + 50., 50.), Vec2::splat(50.))
+    }
+
+    fn step(spawned: &mut Vec<i32>, camera_x: f32) -> (Vec<i32>, Vec<i32>) {
+        let (spawn_areas, keep_areas) = chunking_areas(
+            std::iter::once(Vec2::new(camera_x, 50.)),
+            Vec2::new(120., 100.),
+            Vec2::new(60., 0.),
+        );
+        let (to_remove, to_spawn) =
+            streaming_changes(spawned, -10..10, chunk_aabb, &spawn_areas, &keep_areas);
+        spawned.retain(|c| !to_remove.contains(c));
+        spawned.extend(to_spawn.iter().copied());
+        spawned.sort();
+        (to_remove, to_spawn)
+    }
+
+    #[test]
+    fn chunks_spawn_within_chunking_distance() {
+        let mut spawned = vec![];
+        let (to_remove, to_spawn) = step(&mut spawned, 50.);
+        // Camera covers x = [-70, 170]
+        assert!(to_remove.is_empty());
+        assert_eq!(to_spawn, vec![-1, 0, 1]);
+
+        // Nothing to do while standing still
+        assert_eq!(step(&mut spawned, 50.), (vec![], vec![]));
+
+        // Camera covers x = [230, 470], and keeps up to x = [170, 530]
+        let (to_remove, to_spawn) = step(&mut spawned, 350.);
+        assert_eq!(to_remove, vec![-1, 0]);
+        assert_eq!(to_spawn, vec![2, 3, 4]);
+        assert_eq!(spawned, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn hysteresis_keeps_chunks_around_the_boundary() {
+        let mut spawned = vec![];
+        step(&mut spawned, 50.);
+        assert_eq!(spawned, vec![-1, 0, 1]);
+
+        // Chunk -1 ends at x = 0: out of the chunking area past x = 120,
+        // but only despawned once out of the hysteresis band, past x = 180
+        for camera_x in [110., 130., 170., 130., 110., 170.] {
+            assert!(step(&mut spawned, camera_x).0.is_empty(), "at {camera_x}");
+            assert!(spawned.contains(&-1));
+        }
+        assert_eq!(step(&mut spawned, 190.).0, vec![-1]);
+
+        // Coming back, it's spawned again as soon as it enters the chunking area
+        assert!(step(&mut spawned, 130.).1.is_empty());
+        assert_eq!(step(&mut spawned, 110.).1, vec![-1]);
+    }
+
+    #[test]
+    fn chunks_are_kept_by_any_camera() {
+        let (spawn_areas, keep_areas) = chunking_areas(
+            [Vec2::new(50., 50.), Vec2::new(1050., 50.)].into_iter(),
+            Vec2::new(120., 100.),
+            Vec2::ZERO,
+        );
+        let (to_remove, to_spawn) =
+            streaming_changes(&[0, 5, 10], 0..12, chunk_aabb, &spawn_areas, &keep_areas);
+        assert_eq!(to_remove, vec![5]);
+        assert_eq!(to_spawn, vec![1, 9, 11]);
+    }
+}